@@ -0,0 +1,253 @@
+//! Table-free CRC computations, for CAN frames and the framing of other protocols.
+//!
+//! All the CRCs are computed bit by bit, most significant bit first, without reflecting the
+//! input or the output and without a final XOR. This is slower than a table lookup, but needs no
+//! memory, and the functions taking slices are `const`, so CRCs of constant data can be computed
+//! at compile time. Protocols with a final XOR can apply it to the result.
+
+use crate::{Frame, Id};
+
+/// Generator polynomial of the CAN CRC-15, x¹⁵ + x¹⁴ + x¹⁰ + x⁸ + x⁷ + x⁴ + x³ + 1, without the
+/// x¹⁵ term.
+pub const CRC15_POLY: u16 = 0x4599;
+
+/// Updates the CAN CRC-15 `crc` with one bit.
+///
+/// The CRC starts at 0. This is the shift register computation of the CAN specification,
+/// convenient when the bits are produced one at a time, for example when decoding a bus capture.
+#[inline]
+pub const fn crc15_update(crc: u16, bit: bool) -> u16 {
+    let feedback = bit != (crc >> 14 & 1 != 0);
+    let crc = (crc << 1) & 0x7FFF;
+    if feedback {
+        crc ^ CRC15_POLY
+    } else {
+        crc
+    }
+}
+
+/// Computes the CAN CRC-15 of the first `len` bits of `bits`, packed most significant bit first.
+///
+/// # Panics
+///
+/// Panics if `bits` holds less than `len` bits.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_can::crc::crc15;
+///
+/// // Check value of CRC-15/CAN.
+/// assert_eq!(crc15(b"123456789", 72), 0x059E);
+///
+/// // Computed at compile time.
+/// const CRC: u16 = crc15(&[0x12, 0x34], 12);
+/// assert_eq!(CRC, crc15(&[0x12, 0x30], 12));
+/// ```
+pub const fn crc15(bits: &[u8], len: usize) -> u16 {
+    assert!(len <= bits.len() * 8, "not enough bits");
+    let mut crc = 0;
+    let mut i = 0;
+    while i < len {
+        let bit = bits[i / 8] & (0x80 >> (i % 8)) != 0;
+        crc = crc15_update(crc, bit);
+        i += 1;
+    }
+    crc
+}
+
+/// Computes the CAN CRC-15 of a classical CAN frame, as sent in its CRC field.
+///
+/// The CRC covers the start of frame, arbitration, control and data fields, before bit
+/// stuffing: SOF, the identifier with its RTR, IDE, and for extended frames SRR bits, the
+/// reserved bits, the 4-bit DLC, and the data of data frames.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_can::{crc, Frame, StandardId};
+/// # use embedded_can::Id;
+/// # struct MyFrame { id: Id, data: [u8; 8], dlc: usize }
+/// # impl Frame for MyFrame {
+/// #     fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+/// #         let mut buf = [0; 8];
+/// #         buf.get_mut(..data.len())?.copy_from_slice(data);
+/// #         Some(Self { id: id.into(), data: buf, dlc: data.len() })
+/// #     }
+/// #     fn new_remote(_: impl Into<Id>, _: usize) -> Option<Self> { None }
+/// #     fn is_extended(&self) -> bool { matches!(self.id, Id::Extended(_)) }
+/// #     fn is_remote_frame(&self) -> bool { false }
+/// #     fn id(&self) -> Id { self.id }
+/// #     fn dlc(&self) -> usize { self.dlc }
+/// #     fn data(&self) -> &[u8] { &self.data[..self.dlc] }
+/// # }
+///
+/// let frame = MyFrame::new(StandardId::new(0x123).unwrap(), &[0x01, 0x02]).unwrap();
+/// assert_eq!(crc::frame_crc15(&frame), 0x69FE);
+/// ```
+pub fn frame_crc15(frame: &impl Frame) -> u16 {
+    // SOF.
+    let mut crc = crc15_update(0, false);
+    let mut push = |bit| crc = crc15_update(crc, bit);
+
+    let rtr = frame.is_remote_frame();
+    match frame.id() {
+        Id::Standard(id) => {
+            id.bits().for_each(&mut push);
+            push(rtr);
+            // IDE and r0.
+            push(false);
+            push(false);
+        }
+        Id::Extended(id) => {
+            let mut bits = id.bits();
+            bits.by_ref().take(11).for_each(&mut push);
+            // SRR and IDE.
+            push(true);
+            push(true);
+            bits.for_each(&mut push);
+            push(rtr);
+            // r1 and r0.
+            push(false);
+            push(false);
+        }
+    }
+    let dlc = frame.dlc();
+    (0..4).rev().for_each(|i| push(dlc >> i & 1 != 0));
+    if !rtr {
+        for byte in frame.data() {
+            (0..8).rev().for_each(|i| push(byte >> i & 1 != 0));
+        }
+    }
+    crc
+}
+
+/// Computes the CRC-8 of `data` with the polynomial `poly`, without its x⁸ term, starting from
+/// `init`.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_can::crc::crc8;
+///
+/// // CRC-8/SMBUS.
+/// assert_eq!(crc8(0x07, 0x00, b"123456789"), 0xF4);
+/// // Checksum of Sensirion sensors.
+/// assert_eq!(crc8(0x31, 0xFF, &[0xBE, 0xEF]), 0x92);
+/// ```
+pub const fn crc8(poly: u8, init: u8, data: &[u8]) -> u8 {
+    let mut crc = init;
+    let mut i = 0;
+    while i < data.len() {
+        crc ^= data[i];
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ poly
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+/// Computes the CRC-16 of `data` with the polynomial `poly`, without its x¹⁶ term, starting from
+/// `init`.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_can::crc::crc16;
+///
+/// // CRC-16/XMODEM, used by SD cards for data blocks.
+/// assert_eq!(crc16(0x1021, 0x0000, b"123456789"), 0x31C3);
+/// // CRC-16/IBM-3740, also known as CRC-16/CCITT-FALSE.
+/// assert_eq!(crc16(0x1021, 0xFFFF, b"123456789"), 0x29B1);
+/// ```
+pub const fn crc16(poly: u16, init: u16, data: &[u8]) -> u16 {
+    let mut crc = init;
+    let mut i = 0;
+    while i < data.len() {
+        crc ^= (data[i] as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ poly
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+#[cfg(test)]
+// Frame bits are grouped by field.
+#[allow(clippy::unusual_byte_groupings)]
+mod tests {
+    use super::*;
+    use crate::tests::TestFrame;
+    use crate::{ExtendedId, StandardId};
+
+    #[test]
+    fn crc15_check_value() {
+        // Check value of the CRC-15/CAN catalogue entry.
+        assert_eq!(crc15(b"123456789", 72), 0x059E);
+    }
+
+    #[test]
+    fn crc15_partial_bytes() {
+        assert_eq!(crc15(&[], 0), 0);
+        assert_eq!(crc15(&[0xFF], 3), crc15(&[0xE0], 3));
+        let crc = [true, true, true]
+            .iter()
+            .fold(0, |crc, &bit| crc15_update(crc, bit));
+        assert_eq!(crc15(&[0xE0], 3), crc);
+    }
+
+    #[test]
+    #[should_panic(expected = "not enough bits")]
+    fn crc15_too_many_bits() {
+        crc15(&[0], 9);
+    }
+
+    #[test]
+    fn standard_data_frame() {
+        let frame = TestFrame::new(StandardId::new(0x123).unwrap(), &[0x01, 0x02]).unwrap();
+        // SOF, ID, RTR, IDE, r0, DLC and data.
+        let bits = 0b0_00100100011_000_0010_00000001_00000010u64 << (64 - 35);
+        assert_eq!(frame_crc15(&frame), crc15(&bits.to_be_bytes(), 35));
+        assert_eq!(frame_crc15(&frame), 0x69FE);
+    }
+
+    #[test]
+    fn standard_remote_frame() {
+        let frame = TestFrame::new_remote(StandardId::MAX, 8).unwrap();
+        // SOF, ID, RTR, IDE, r0 and DLC, without data.
+        let bits = 0b0_11111111111_100_1000u32 << (32 - 19);
+        assert_eq!(frame_crc15(&frame), crc15(&bits.to_be_bytes(), 19));
+        assert_eq!(frame_crc15(&frame), 0x20ED);
+    }
+
+    #[test]
+    fn extended_data_frame() {
+        let frame = TestFrame::new(ExtendedId::new(0x1234_5678).unwrap(), &[0xAA]).unwrap();
+        // SOF, base ID, SRR, IDE, ID extension, RTR, r1, r0, DLC and data.
+        let bits = 0b0_10010001101_11_000101011001111000_000_0001_10101010u64 << (64 - 51);
+        assert_eq!(frame_crc15(&frame), crc15(&bits.to_be_bytes(), 51));
+        assert_eq!(frame_crc15(&frame), 0x5D68);
+    }
+
+    #[test]
+    fn frame_with_crc_has_zero_remainder() {
+        let frame = TestFrame::new(StandardId::new(0x123).unwrap(), &[0x01, 0x02]).unwrap();
+        let crc = frame_crc15(&frame);
+        let bits = (0b0_00100100011_000_0010_00000001_00000010u64 << 15 | u64::from(crc)) << 14;
+        assert_eq!(crc15(&bits.to_be_bytes(), 50), 0);
+    }
+}
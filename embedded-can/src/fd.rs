@@ -0,0 +1,106 @@
+//! CAN FD payloads.
+
+use core::convert::TryFrom;
+use core::ops::Deref;
+
+/// Maximum payload length of a CAN FD frame, in bytes.
+pub const FD_MAX_DATA_LEN: usize = 64;
+
+/// CAN FD frame payload (`0..=64` bytes), backed by a [`heapless::Vec`].
+///
+/// Unlike a fixed `[u8; 64]` buffer, this keeps track of the actual payload
+/// length, so short payloads can be passed around without carrying their
+/// unused capacity as meaningful data.
+///
+/// `FdData` dereferences to `[u8]`, so it can be passed directly to
+/// [`Frame::new`](crate::Frame::new).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FdData(heapless::Vec<u8, FD_MAX_DATA_LEN>);
+
+impl FdData {
+    /// Creates a new `FdData` by copying `data`.
+    ///
+    /// This will return `None` if `data` is longer than 64 bytes.
+    #[inline]
+    pub fn new(data: &[u8]) -> Option<Self> {
+        heapless::Vec::from_slice(data).ok().map(Self)
+    }
+
+    /// Creates an empty payload.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(heapless::Vec::new())
+    }
+
+    /// Returns the maximum number of bytes this payload can hold.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        FD_MAX_DATA_LEN
+    }
+}
+
+impl Deref for FdData {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for FdData {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Error returned when converting a slice longer than 64 bytes into [`FdData`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct FdDataTooLong;
+
+impl core::fmt::Display for FdDataTooLong {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "CAN FD payload is longer than 64 bytes")
+    }
+}
+
+impl TryFrom<&[u8]> for FdData {
+    type Error = FdDataTooLong;
+
+    #[inline]
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::new(data).ok_or(FdDataTooLong)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fd_data_new() {
+        for len in [0, 1, 8, 12, 48, FD_MAX_DATA_LEN] {
+            let bytes = [0xA5; FD_MAX_DATA_LEN];
+            let data = FdData::new(&bytes[..len]).unwrap();
+            assert_eq!(data.len(), len);
+            assert_eq!(&*data, &bytes[..len]);
+            assert_eq!(data.capacity(), FD_MAX_DATA_LEN);
+        }
+    }
+
+    #[test]
+    fn fd_data_new_too_long() {
+        let bytes = [0; FD_MAX_DATA_LEN + 1];
+        assert_eq!(FdData::new(&bytes), None);
+        assert_eq!(FdData::try_from(&bytes[..]), Err(FdDataTooLong));
+    }
+
+    #[test]
+    fn fd_data_try_from() {
+        let data = FdData::try_from(&[1, 2, 3][..]).unwrap();
+        assert_eq!(&*data, &[1, 2, 3]);
+        assert_eq!(FdData::empty().len(), 0);
+    }
+}
@@ -0,0 +1,292 @@
+//! CAN acceptance filters.
+
+use crate::StandardId;
+
+/// Mask covering all 11 bits of a standard identifier.
+const STANDARD_MASK: u16 = 0x7FF;
+
+/// Acceptance filter for standard 11-bit identifiers.
+///
+/// An identifier is accepted if it matches `id` in all the bits set in `mask`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Filter {
+    id: StandardId,
+    mask: u16,
+}
+
+impl Filter {
+    /// Creates a new filter accepting identifiers that match `id` in all bits set in `mask`.
+    ///
+    /// Bits of `mask` above the 11 identifier bits are ignored.
+    #[inline]
+    pub const fn new(id: StandardId, mask: u16) -> Self {
+        Self {
+            id,
+            mask: mask & STANDARD_MASK,
+        }
+    }
+
+    /// Creates a filter accepting exactly one identifier.
+    #[inline]
+    pub const fn exact(id: StandardId) -> Self {
+        Self::new(id, STANDARD_MASK)
+    }
+
+    /// Returns the identifier bits of this filter.
+    #[inline]
+    pub const fn id(&self) -> StandardId {
+        self.id
+    }
+
+    /// Returns the mask of this filter.
+    #[inline]
+    pub const fn mask(&self) -> u16 {
+        self.mask
+    }
+
+    /// Returns true if the filter accepts `id`.
+    #[inline]
+    pub const fn matches(&self, id: StandardId) -> bool {
+        (id.as_raw() ^ self.id.as_raw()) & self.mask == 0
+    }
+
+    /// Returns true if every identifier accepted by `self` is also accepted by `other`.
+    #[inline]
+    pub const fn is_subset_of(&self, other: &Filter) -> bool {
+        self.mask & other.mask == other.mask
+            && (self.id.as_raw() ^ other.id.as_raw()) & other.mask == 0
+    }
+
+    /// Returns a single filter accepting exactly the identifiers accepted by `self` or `other`,
+    /// to save a hardware filter slot.
+    ///
+    /// This is only possible if one filter accepts all the identifiers of the other, which is
+    /// then returned, or if both have the same mask and their masked identifiers differ in a
+    /// single bit, which the merged filter ignores. The identifier bits ignored by the merged
+    /// filter are then cleared. This will return `None` in all other cases, as the union of the
+    /// two filters can't be expressed as a single identifier and mask.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_can::{Filter, StandardId};
+    ///
+    /// let a = Filter::exact(StandardId::new(0x120).unwrap());
+    /// let b = Filter::exact(StandardId::new(0x121).unwrap());
+    /// let c = Filter::exact(StandardId::new(0x122).unwrap());
+    ///
+    /// // 0x120 and 0x121 only differ in bit 0.
+    /// let ab = a.try_merge(&b).unwrap();
+    /// assert_eq!(ab, Filter::new(StandardId::new(0x120).unwrap(), 0x7FE));
+    ///
+    /// // No single filter accepts exactly 0x120, 0x121 and 0x122.
+    /// assert_eq!(ab.try_merge(&c), None);
+    /// ```
+    #[inline]
+    pub const fn try_merge(&self, other: &Filter) -> Option<Filter> {
+        if self.is_subset_of(other) {
+            return Some(*other);
+        }
+        if other.is_subset_of(self) {
+            return Some(*self);
+        }
+        let diff = (self.id.as_raw() ^ other.id.as_raw()) & self.mask;
+        if self.mask == other.mask && diff.count_ones() == 1 {
+            let mask = self.mask & !diff;
+            // Clearing the ignored bits makes the result independent of the argument order, and
+            // keeps the identifier within 11 bits.
+            let id = unsafe { StandardId::new_unchecked(self.id.as_raw() & mask) };
+            Some(Filter::new(id, mask))
+        } else {
+            None
+        }
+    }
+}
+
+/// An inclusive range of standard identifiers, `start..=end`.
+///
+/// Like [`RangeInclusive`](core::ops::RangeInclusive), the range is empty if `start > end`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct IdRange {
+    start: StandardId,
+    end: StandardId,
+}
+
+impl IdRange {
+    /// Creates a new range containing the identifiers from `start` up to and including `end`.
+    #[inline]
+    pub const fn new(start: StandardId, end: StandardId) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the lower bound of the range (inclusive).
+    #[inline]
+    pub const fn start(&self) -> StandardId {
+        self.start
+    }
+
+    /// Returns the upper bound of the range (inclusive).
+    #[inline]
+    pub const fn end(&self) -> StandardId {
+        self.end
+    }
+
+    /// Returns true if the range contains no identifiers.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.start.as_raw() > self.end.as_raw()
+    }
+
+    /// Returns the number of identifiers in the range.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            (self.end.as_raw() - self.start.as_raw()) as usize + 1
+        }
+    }
+
+    /// Returns true if `id` lies within the range.
+    #[inline]
+    pub const fn contains(&self, id: StandardId) -> bool {
+        self.start.as_raw() <= id.as_raw() && id.as_raw() <= self.end.as_raw()
+    }
+
+    /// Returns an iterator over the identifiers in the range, in ascending order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = StandardId> {
+        // Identifiers within `start..=end` are always valid.
+        (self.start.as_raw()..=self.end.as_raw())
+            .map(|raw| unsafe { StandardId::new_unchecked(raw) })
+    }
+
+    /// Returns the filter accepting exactly the identifiers in this range.
+    ///
+    /// A filter can only express ranges whose length is a power of two, and whose start
+    /// is a multiple of that length (for example `0x100..=0x1FF`). This will return `None`
+    /// for any other range, including an empty one.
+    pub fn to_filter(&self) -> Option<Filter> {
+        let len = self.len() as u16;
+        if len == 0 || !len.is_power_of_two() || self.start.as_raw() & (len - 1) != 0 {
+            return None;
+        }
+        Some(Filter::new(self.start, !(len - 1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(raw: u16) -> StandardId {
+        StandardId::new(raw).unwrap()
+    }
+
+    #[test]
+    fn filter_matches() {
+        let filter = Filter::new(id(0x100), 0x700);
+        assert!(filter.matches(id(0x100)));
+        assert!(filter.matches(id(0x1FF)));
+        assert!(!filter.matches(id(0x200)));
+        assert!(Filter::exact(id(0x123)).matches(id(0x123)));
+        assert!(!Filter::exact(id(0x123)).matches(id(0x122)));
+    }
+
+    #[test]
+    fn filter_is_subset_of() {
+        let wide = Filter::new(id(0x100), 0x700);
+        let narrow = Filter::new(id(0x120), 0x7F0);
+        assert!(narrow.is_subset_of(&wide));
+        assert!(!wide.is_subset_of(&narrow));
+        assert!(wide.is_subset_of(&wide));
+        assert!(!Filter::new(id(0x220), 0x7F0).is_subset_of(&wide));
+    }
+
+    #[test]
+    fn filter_try_merge_one_bit() {
+        let a = Filter::exact(id(0x123));
+        let b = Filter::exact(id(0x133));
+        let merged = Filter::new(id(0x123), 0x7EF);
+        assert_eq!(a.try_merge(&b), Some(merged));
+        assert_eq!(b.try_merge(&a), Some(merged));
+        for raw in 0..=0x7FF {
+            let accepted = a.matches(id(raw)) || b.matches(id(raw));
+            assert_eq!(merged.matches(id(raw)), accepted);
+        }
+
+        // Bits ignored by both masks don't count as a difference.
+        let a = Filter::new(id(0x100), 0x700);
+        let b = Filter::new(id(0x3FF), 0x700);
+        assert_eq!(a.try_merge(&b), Some(Filter::new(id(0x100), 0x500)));
+    }
+
+    #[test]
+    fn filter_try_merge_subset() {
+        let wide = Filter::new(id(0x100), 0x700);
+        let narrow = Filter::exact(id(0x1AB));
+        assert_eq!(wide.try_merge(&narrow), Some(wide));
+        assert_eq!(narrow.try_merge(&wide), Some(wide));
+        assert_eq!(wide.try_merge(&wide), Some(wide));
+    }
+
+    #[test]
+    fn filter_try_merge_not_mergeable() {
+        // Two bits apart.
+        let a = Filter::exact(id(0x120));
+        let b = Filter::exact(id(0x123));
+        assert_eq!(a.try_merge(&b), None);
+
+        // One bit apart, but with different masks.
+        let a = Filter::new(id(0x120), 0x7F0);
+        let b = Filter::exact(id(0x130));
+        assert_eq!(a.try_merge(&b), None);
+
+        // Ignoring different bits.
+        let a = Filter::new(id(0x120), 0x7FE);
+        let b = Filter::new(id(0x120), 0x7FD);
+        assert_eq!(a.try_merge(&b), None);
+    }
+
+    #[test]
+    fn id_range() {
+        let range = IdRange::new(id(0x100), id(0x1FF));
+        assert_eq!(range.len(), 0x100);
+        assert!(!range.is_empty());
+        assert!(range.contains(id(0x100)));
+        assert!(range.contains(id(0x1FF)));
+        assert!(!range.contains(id(0x200)));
+        assert_eq!(range.iter().count(), 0x100);
+        assert_eq!(range.iter().next(), Some(id(0x100)));
+        assert_eq!(range.iter().last(), Some(id(0x1FF)));
+    }
+
+    #[test]
+    fn id_range_empty() {
+        let range = IdRange::new(id(0x200), id(0x1FF));
+        assert!(range.is_empty());
+        assert_eq!(range.len(), 0);
+        assert_eq!(range.iter().count(), 0);
+        assert_eq!(range.to_filter(), None);
+    }
+
+    #[test]
+    fn id_range_to_filter_aligned() {
+        let filter = IdRange::new(id(0x100), id(0x1FF)).to_filter().unwrap();
+        assert_eq!(filter, Filter::new(id(0x100), 0x700));
+
+        let filter = IdRange::new(StandardId::ZERO, StandardId::MAX)
+            .to_filter()
+            .unwrap();
+        assert_eq!(filter.mask(), 0);
+
+        let filter = IdRange::new(id(0x123), id(0x123)).to_filter().unwrap();
+        assert_eq!(filter, Filter::exact(id(0x123)));
+    }
+
+    #[test]
+    fn id_range_to_filter_unaligned() {
+        assert_eq!(IdRange::new(id(0x101), id(0x1FF)).to_filter(), None);
+        assert_eq!(IdRange::new(id(0x180), id(0x27F)).to_filter(), None);
+    }
+}
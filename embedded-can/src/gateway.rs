@@ -0,0 +1,92 @@
+//! Frame forwarding between CAN buses.
+
+use crate::Frame;
+
+/// Forwarding step of a gateway between two CAN buses.
+///
+/// Each frame received on one bus is passed to `transform`, which returns the frame to transmit
+/// on the other bus, for example with a translated identifier, or `None` to drop it. The gateway
+/// only transforms frames: receiving and transmitting them is left to the caller, so it can be
+/// used with any transport.
+///
+/// The transform is a `FnMut`, so it can keep state across frames, such as counters.
+#[derive(Debug, Clone)]
+pub struct Gateway<T> {
+    transform: T,
+}
+
+impl<T> Gateway<T> {
+    /// Creates a new gateway forwarding frames through `transform`.
+    #[inline]
+    pub const fn new(transform: T) -> Self {
+        Self { transform }
+    }
+
+    /// Returns the frame to forward for `frame`, or `None` if it is dropped.
+    #[inline]
+    pub fn forward<F>(&mut self, frame: &F) -> Option<F>
+    where
+        F: Frame,
+        T: FnMut(&F) -> Option<F>,
+    {
+        (self.transform)(frame)
+    }
+
+    /// Returns an iterator over the frames to forward for `frames`, in order, skipping the
+    /// dropped ones.
+    #[inline]
+    pub fn forward_all<'a, F, I>(&'a mut self, frames: I) -> impl Iterator<Item = F> + 'a
+    where
+        F: Frame,
+        T: FnMut(&F) -> Option<F>,
+        I: IntoIterator<Item = F>,
+        I::IntoIter: 'a,
+    {
+        frames
+            .into_iter()
+            .filter_map(move |frame| self.forward(&frame))
+    }
+
+    /// Destroys the gateway, returning its transform.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.transform
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestFrame;
+    use crate::StandardId;
+
+    fn frame(raw: u16, data: u8) -> TestFrame {
+        TestFrame::new(StandardId::new(raw).unwrap(), &[data]).unwrap()
+    }
+
+    #[test]
+    fn remap_and_drop() {
+        let mut gateway = Gateway::new(|frame: &TestFrame| match frame.id() {
+            id if id == StandardId::new(0x100).unwrap() => {
+                TestFrame::new(StandardId::new(0x200).unwrap(), frame.data())
+            }
+            id if id == StandardId::new(0x300).unwrap() => None,
+            id => TestFrame::new(id, frame.data()),
+        });
+
+        assert_eq!(gateway.forward(&frame(0x100, 1)), Some(frame(0x200, 1)));
+        assert_eq!(gateway.forward(&frame(0x300, 2)), None);
+
+        let mut forwarded = gateway.forward_all([
+            frame(0x300, 1),
+            frame(0x100, 2),
+            frame(0x123, 3),
+            frame(0x300, 4),
+            frame(0x200, 5),
+        ]);
+        assert_eq!(forwarded.next(), Some(frame(0x200, 2)));
+        assert_eq!(forwarded.next(), Some(frame(0x123, 3)));
+        assert_eq!(forwarded.next(), Some(frame(0x200, 5)));
+        assert_eq!(forwarded.next(), None);
+    }
+}
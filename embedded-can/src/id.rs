@@ -1,6 +1,11 @@
 //! CAN Identifiers.
 
+use core::cmp::Ordering;
+
 /// Standard 11-bit CAN Identifier (`0..=0x7FF`).
+///
+/// `Ord` compares the raw numeric values. Between two standard IDs this is the same as the
+/// arbitration order, see [`StandardId::arbitration_cmp`].
 #[derive(Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub struct StandardId(u16);
 
@@ -37,9 +42,51 @@ impl StandardId {
     pub const fn as_raw(&self) -> u16 {
         self.0
     }
+
+    /// Compares two standard IDs according to the CAN arbitration rules.
+    ///
+    /// The "smallest" ID is the one that wins arbitration. For standard IDs this is the
+    /// same as the numeric order provided by `Ord`.
+    #[inline]
+    pub fn arbitration_cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+
+    /// Returns the 11 bits of this identifier, most significant first.
+    #[inline]
+    pub fn bits(&self) -> impl Iterator<Item = bool> {
+        bits_msb_first(self.0 as u32, 11)
+    }
+
+    /// Returns the bit field of `len` bits starting at bit `start`, counted from the least
+    /// significant bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field extends past the 11 bits of the identifier, that is if
+    /// `start + len > 11`.
+    #[inline]
+    pub const fn field(&self, start: u8, len: u8) -> u16 {
+        ((self.0 as u32 & field_mask(start, len, 11)) >> start) as u16
+    }
+
+    /// Returns this identifier with the bit field of `len` bits starting at bit `start`
+    /// replaced by `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field extends past the 11 bits of the identifier, or if `value` doesn't fit
+    /// in `len` bits.
+    #[inline]
+    pub const fn with_field(self, start: u8, len: u8, value: u16) -> Self {
+        Self(with_field(self.0 as u32, start, len, value as u32, 11) as u16)
+    }
 }
 
 /// Extended 29-bit CAN Identifier (`0..=1FFF_FFFF`).
+///
+/// `Ord` compares the raw numeric values. Between two extended IDs this is the same as the
+/// arbitration order, see [`ExtendedId::arbitration_cmp`].
 #[derive(Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub struct ExtendedId(u32);
 
@@ -82,6 +129,84 @@ impl ExtendedId {
         // ID-28 to ID-18
         StandardId((self.0 >> 18) as u16)
     }
+
+    /// Compares two extended IDs according to the CAN arbitration rules.
+    ///
+    /// The "smallest" ID is the one that wins arbitration. The Base ID is sent first,
+    /// followed by the SRR and IDE bits (both recessive for every extended frame), and then
+    /// the ID extension. The result is therefore the same as the numeric order provided by `Ord`.
+    ///
+    /// Comparing an extended ID against a standard one is done by [`Id`]'s `Ord` implementation.
+    #[inline]
+    pub fn arbitration_cmp(&self, other: &Self) -> Ordering {
+        self.standard_id()
+            .arbitration_cmp(&other.standard_id())
+            .then_with(|| self.extended_part().cmp(&other.extended_part()))
+    }
+
+    /// Returns the 29 bits of this identifier, most significant first.
+    #[inline]
+    pub fn bits(&self) -> impl Iterator<Item = bool> {
+        bits_msb_first(self.0, 29)
+    }
+
+    /// Returns the bit field of `len` bits starting at bit `start`, counted from the least
+    /// significant bit.
+    ///
+    /// This extracts the values of protocols packing several of them in the identifier, such as
+    /// SAE J1939.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field extends past the 29 bits of the identifier, that is if
+    /// `start + len > 29`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_can::ExtendedId;
+    ///
+    /// // J1939 engine temperature message from source address 0x00.
+    /// let id = ExtendedId::new(0x18FE_EE00).unwrap();
+    /// let priority = id.field(26, 3);
+    /// let pgn = id.field(8, 18);
+    /// let source_address = id.field(0, 8);
+    /// assert_eq!((priority, pgn, source_address), (6, 0xFEEE, 0x00));
+    /// ```
+    #[inline]
+    pub const fn field(&self, start: u8, len: u8) -> u32 {
+        (self.0 & field_mask(start, len, 29)) >> start
+    }
+
+    /// Returns this identifier with the bit field of `len` bits starting at bit `start`
+    /// replaced by `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the field extends past the 29 bits of the identifier, or if `value` doesn't fit
+    /// in `len` bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_can::ExtendedId;
+    ///
+    /// let id = ExtendedId::ZERO
+    ///     .with_field(26, 3, 6)
+    ///     .with_field(8, 18, 0xFEEE)
+    ///     .with_field(0, 8, 0x25);
+    /// assert_eq!(id.as_raw(), 0x18FE_EE25);
+    /// ```
+    #[inline]
+    pub const fn with_field(self, start: u8, len: u8, value: u32) -> Self {
+        Self(with_field(self.0, start, len, value, 29))
+    }
+
+    /// Returns the ID extension (ID-17 to ID-0) of this extended identifier.
+    #[inline]
+    const fn extended_part(&self) -> u32 {
+        self.0 & ((1 << 18) - 1)
+    }
 }
 
 /// A CAN Identifier (standard or extended).
@@ -94,6 +219,155 @@ pub enum Id {
     Extended(ExtendedId),
 }
 
+impl Id {
+    /// Standard CAN ID `0`, [`StandardId::ZERO`].
+    pub const STANDARD_MIN: Self = Id::Standard(StandardId::ZERO);
+
+    /// Standard CAN ID `0x7FF`, [`StandardId::MAX`].
+    pub const STANDARD_MAX: Self = Id::Standard(StandardId::MAX);
+
+    /// Extended CAN ID `0`, [`ExtendedId::ZERO`].
+    pub const EXTENDED_MIN: Self = Id::Extended(ExtendedId::ZERO);
+
+    /// Extended CAN ID `0x1FFFFFFF`, [`ExtendedId::MAX`].
+    pub const EXTENDED_MAX: Self = Id::Extended(ExtendedId::MAX);
+
+    /// IDE bit of the [`to_be_bytes`](Self::to_be_bytes) encoding.
+    const IDE_BIT: u32 = 1 << 31;
+
+    /// Creates a new identifier from its raw value and IDE (extended) flag, as they are carried
+    /// over the wire.
+    ///
+    /// This will return `None` if `raw` is out of range for the identifier type selected by
+    /// `extended`.
+    #[inline]
+    pub const fn new(raw: u32, extended: bool) -> Option<Self> {
+        if extended {
+            match ExtendedId::new(raw) {
+                Some(id) => Some(Id::Extended(id)),
+                None => None,
+            }
+        } else if raw <= StandardId::MAX.0 as u32 {
+            Some(Id::Standard(StandardId(raw as u16)))
+        } else {
+            None
+        }
+    }
+
+    /// Encodes the identifier as 4 big-endian bytes, for serialization.
+    ///
+    /// The IDE bit is bit 31 (the most significant bit of the first byte): 0 for standard
+    /// identifiers and 1 for extended ones. The raw identifier is in bits 28 to 0, bits 30 and 29
+    /// are always 0.
+    #[inline]
+    pub const fn to_be_bytes(&self) -> [u8; 4] {
+        let raw = match self {
+            Id::Standard(id) => id.0 as u32,
+            Id::Extended(id) => Self::IDE_BIT | id.0,
+        };
+        raw.to_be_bytes()
+    }
+
+    /// Decodes an identifier encoded by [`to_be_bytes`](Self::to_be_bytes).
+    ///
+    /// This will return `None` if the encoded raw identifier is out of range for its IDE bit, or
+    /// if bit 30 or 29 is set.
+    #[inline]
+    pub const fn from_be_bytes(bytes: [u8; 4]) -> Option<Self> {
+        let raw = u32::from_be_bytes(bytes);
+        Self::new(raw & !Self::IDE_BIT, raw & Self::IDE_BIT != 0)
+    }
+
+    /// Returns the standard identifier, or `None` if this is an extended identifier.
+    #[inline]
+    pub const fn as_standard(&self) -> Option<StandardId> {
+        match self {
+            Id::Standard(id) => Some(*id),
+            Id::Extended(_) => None,
+        }
+    }
+
+    /// Returns the extended identifier, or `None` if this is a standard identifier.
+    #[inline]
+    pub const fn as_extended(&self) -> Option<ExtendedId> {
+        match self {
+            Id::Standard(_) => None,
+            Id::Extended(id) => Some(*id),
+        }
+    }
+
+    /// Returns the raw value of the identifier, widened to `u32`.
+    ///
+    /// This is the 11-bit value of a standard identifier, or the 29-bit value of an extended one.
+    /// The IDE bit is lost: a standard and an extended identifier with the same value return the
+    /// same number. Use [`arbitration_key`](Self::arbitration_key) for a number keeping the IDE
+    /// bit, with the fields laid out as they are sent on the bus.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_can::{ExtendedId, Id, StandardId};
+    ///
+    /// let standard = Id::Standard(StandardId::new(0x123).unwrap());
+    /// let extended = Id::Extended(ExtendedId::new(0x123).unwrap());
+    ///
+    /// assert_eq!(standard.as_u32(), 0x123);
+    /// assert_eq!(extended.as_u32(), 0x123);
+    /// assert_ne!(standard.arbitration_key(), extended.arbitration_key());
+    /// ```
+    #[inline]
+    pub const fn as_u32(&self) -> u32 {
+        match self {
+            Id::Standard(id) => id.0 as u32,
+            Id::Extended(id) => id.0,
+        }
+    }
+
+    /// Returns the bits of the identifier in the order they are sent on the bus.
+    ///
+    /// These are the 11 bits of a standard identifier followed by a dominant (`false`) IDE bit,
+    /// or the 11 bits of the Base ID of an extended identifier, a recessive (`true`) IDE bit, and
+    /// the 18 bits of the ID extension. Like for [`arbitration_key`](Self::arbitration_key), the
+    /// SRR and RTR bits are not included.
+    #[inline]
+    pub fn arbitration_bits(&self) -> impl Iterator<Item = bool> {
+        match self {
+            Id::Standard(_) => bits_msb_first(self.arbitration_key() >> 18, 12),
+            Id::Extended(_) => bits_msb_first(self.arbitration_key(), 30),
+        }
+    }
+
+    /// Returns a key whose numeric order is the CAN arbitration order of identifiers.
+    ///
+    /// The fields are packed in the order they are sent on the bus: the Base ID in bits 29 to 19,
+    /// the IDE bit in bit 18, and the ID extension in bits 17 to 0 (zero for standard IDs).
+    /// Comparing two keys is equivalent to comparing the `Id`s themselves.
+    ///
+    /// Unlike [`as_u32`](Self::as_u32), the key isn't the raw value of the identifier: the value of
+    /// a standard identifier is shifted to the Base ID bits, and the ID extension of an extended
+    /// identifier is separated from its Base ID by the IDE bit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_can::{ExtendedId, Id, StandardId};
+    ///
+    /// let standard = Id::Standard(StandardId::new(0x123).unwrap());
+    /// assert_eq!(standard.arbitration_key(), 0x123 << 19);
+    ///
+    /// // Base ID 0x123, ID extension 0x3_4567.
+    /// let extended = Id::Extended(ExtendedId::new(0x123 << 18 | 0x3_4567).unwrap());
+    /// assert_eq!(extended.arbitration_key(), 0x123 << 19 | 1 << 18 | 0x3_4567);
+    /// ```
+    #[inline]
+    pub const fn arbitration_key(&self) -> u32 {
+        match self {
+            Id::Standard(id) => (id.0 as u32) << 19,
+            Id::Extended(id) => (id.0 >> 18) << 19 | 1 << 18 | id.extended_part(),
+        }
+    }
+}
+
 /// Implement `Ord` according to the CAN arbitration rules
 ///
 /// When performing arbitration, frames are looked at bit for bit starting
@@ -108,30 +382,85 @@ pub enum Id {
 /// and when comparing two different instances of `Id` the "smallest" will
 /// always be the ID which would form the most dominant frame, all other
 /// things being equal.
+///
+/// This is not the numeric order of the raw identifiers when standard and extended IDs are
+/// mixed: an extended ID sorts between the standard IDs around its Base ID. Wrap IDs in
+/// [`NumericId`] to sort them numerically instead.
 impl Ord for Id {
-    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
-        let split_id = |id: &Id| {
-            let (standard_id_part, ide_bit, extended_id_part) = match id {
-                Id::Standard(StandardId(x)) => (*x, 0, 0),
-                Id::Extended(x) => (
-                    x.standard_id().0,
-                    1,
-                    x.0 & ((1 << 18) - 1), // Bit ID-17 to ID-0
-                ),
-            };
-            (standard_id_part, ide_bit, extended_id_part)
-        };
-
-        split_id(self).cmp(&split_id(other))
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.arbitration_key().cmp(&other.arbitration_key())
     }
 }
 
 impl PartialOrd for Id {
-    fn partial_cmp(&self, other: &Id) -> Option<core::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Id) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
+/// An [`Id`] ordered by its numeric value, rather than by arbitration priority.
+///
+/// Standard identifiers are ordered by value (`0..=0x7FF`), then extended identifiers by value
+/// (`0..=0x1FFF_FFFF`): every extended identifier sorts after every standard one. This is the
+/// order expected when storing mixed identifiers in a sorted structure and looking them up by
+/// value, while the `Ord` implementation of `Id` itself sorts them in arbitration order, in
+/// which an extended identifier sorts right after the standard identifier equal to its Base ID.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_can::{ExtendedId, Id, NumericId, StandardId};
+///
+/// let standard: Id = StandardId::new(0x101).unwrap().into();
+/// // Base ID 0x100.
+/// let extended: Id = ExtendedId::new(0x0400_0000).unwrap().into();
+///
+/// assert!(extended < standard);
+/// assert!(NumericId(standard) < NumericId(extended));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct NumericId(pub Id);
+
+impl NumericId {
+    /// Returns a key whose order is the numeric order of identifiers.
+    #[inline]
+    const fn key(&self) -> (bool, u32) {
+        match self.0 {
+            Id::Standard(id) => (false, id.0 as u32),
+            Id::Extended(id) => (true, id.0),
+        }
+    }
+}
+
+impl Ord for NumericId {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PartialOrd for NumericId {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<Id> for NumericId {
+    #[inline]
+    fn from(id: Id) -> Self {
+        NumericId(id)
+    }
+}
+
+impl From<NumericId> for Id {
+    #[inline]
+    fn from(id: NumericId) -> Self {
+        id.0
+    }
+}
+
 impl From<StandardId> for Id {
     #[inline]
     fn from(id: StandardId) -> Self {
@@ -146,6 +475,95 @@ impl From<ExtendedId> for Id {
     }
 }
 
+impl From<StandardId> for u32 {
+    #[inline]
+    fn from(id: StandardId) -> Self {
+        id.0 as u32
+    }
+}
+
+impl From<ExtendedId> for u32 {
+    #[inline]
+    fn from(id: ExtendedId) -> Self {
+        id.0
+    }
+}
+
+/// Fails with the original `Id` if it is an extended identifier.
+impl TryFrom<Id> for StandardId {
+    type Error = Id;
+
+    #[inline]
+    fn try_from(id: Id) -> Result<Self, Self::Error> {
+        id.as_standard().ok_or(id)
+    }
+}
+
+/// Fails with the original `Id` if it is a standard identifier.
+impl TryFrom<Id> for ExtendedId {
+    type Error = Id;
+
+    #[inline]
+    fn try_from(id: Id) -> Result<Self, Self::Error> {
+        id.as_extended().ok_or(id)
+    }
+}
+
+/// Only equal to standard identifiers with the same value.
+impl PartialEq<StandardId> for Id {
+    #[inline]
+    fn eq(&self, other: &StandardId) -> bool {
+        self.as_standard() == Some(*other)
+    }
+}
+
+/// Only equal to extended identifiers with the same value.
+impl PartialEq<ExtendedId> for Id {
+    #[inline]
+    fn eq(&self, other: &ExtendedId) -> bool {
+        self.as_extended() == Some(*other)
+    }
+}
+
+impl PartialEq<Id> for StandardId {
+    #[inline]
+    fn eq(&self, other: &Id) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<Id> for ExtendedId {
+    #[inline]
+    fn eq(&self, other: &Id) -> bool {
+        other == self
+    }
+}
+
+/// Returns the mask of the bit field of `len` bits starting at bit `start`, in an identifier of
+/// `width` bits.
+#[inline]
+const fn field_mask(start: u8, len: u8, width: u8) -> u32 {
+    assert!(
+        start as u32 + len as u32 <= width as u32,
+        "bit field out of range of the identifier"
+    );
+    ((1 << len) - 1) << start
+}
+
+/// Returns `raw` with the bit field of `len` bits starting at bit `start` replaced by `value`.
+#[inline]
+const fn with_field(raw: u32, start: u8, len: u8, value: u32, width: u8) -> u32 {
+    let mask = field_mask(start, len, width);
+    assert!(value >> len == 0, "value doesn't fit in the bit field");
+    raw & !mask | value << start
+}
+
+/// Returns the `len` low bits of `value`, most significant first.
+#[inline]
+fn bits_msb_first(value: u32, len: u32) -> impl Iterator<Item = bool> {
+    (0..len).rev().map(move |i| value >> i & 1 != 0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,6 +587,88 @@ mod tests {
         assert_eq!(unsafe { StandardId::new_unchecked(id) }, StandardId(id));
     }
 
+    #[test]
+    fn id_new() {
+        assert_eq!(Id::new(0x7FF, false), Some(Id::Standard(StandardId::MAX)));
+        assert_eq!(Id::new(0, false), Some(Id::Standard(StandardId::ZERO)));
+        assert_eq!(
+            Id::new(0x1FFF_FFFF, true),
+            Some(Id::Extended(ExtendedId::MAX))
+        );
+        assert_eq!(
+            Id::new(0x7FF, true),
+            Some(Id::Extended(ExtendedId::new(0x7FF).unwrap()))
+        );
+    }
+
+    #[test]
+    fn id_new_out_of_range() {
+        assert_eq!(Id::new(0x800, false), None);
+        assert_eq!(Id::new(0x1FFF_FFFF, false), None);
+        assert_eq!(Id::new(0x0001_07FF, false), None);
+        assert_eq!(Id::new(0x2000_0000, true), None);
+    }
+
+    #[test]
+    fn id_be_bytes_round_trip() {
+        let ids = [
+            Id::Standard(StandardId::ZERO),
+            Id::Standard(StandardId::new(0x123).unwrap()),
+            Id::Standard(StandardId::MAX),
+            Id::Extended(ExtendedId::ZERO),
+            Id::Extended(ExtendedId::new(0x123).unwrap()),
+            Id::Extended(ExtendedId::MAX),
+        ];
+        for id in ids {
+            assert_eq!(Id::from_be_bytes(id.to_be_bytes()), Some(id));
+        }
+
+        assert_eq!(
+            Id::Standard(StandardId::MAX).to_be_bytes(),
+            [0x00, 0x00, 0x07, 0xFF]
+        );
+        assert_eq!(
+            Id::Extended(ExtendedId::MAX).to_be_bytes(),
+            [0x9F, 0xFF, 0xFF, 0xFF]
+        );
+    }
+
+    #[test]
+    fn id_from_be_bytes_out_of_range() {
+        assert_eq!(Id::from_be_bytes([0x00, 0x00, 0x08, 0x00]), None);
+        assert_eq!(Id::from_be_bytes([0x20, 0x00, 0x00, 0x00]), None);
+        assert_eq!(Id::from_be_bytes([0xA0, 0x00, 0x00, 0x00]), None);
+        assert_eq!(Id::from_be_bytes([0xC0, 0x00, 0x00, 0x01]), None);
+    }
+
+    /// Parses a string of `0` and `1`, ignoring spaces.
+    fn bits(s: &str) -> impl Iterator<Item = bool> + '_ {
+        s.chars().filter(|c| *c != ' ').map(|c| c == '1')
+    }
+
+    #[test]
+    fn id_bits() {
+        let id = StandardId::new(0x123).unwrap();
+        assert!(id.bits().eq(bits("001 0010 0011")));
+        assert!(StandardId::MAX.bits().eq(bits("111 1111 1111")));
+
+        let id = ExtendedId::new(0x1234_5678).unwrap();
+        assert!(id.bits().eq(bits("1 0010 0011 0100 0101 0110 0111 1000")));
+        assert_eq!(ExtendedId::ZERO.bits().count(), 29);
+    }
+
+    #[test]
+    fn id_arbitration_bits() {
+        let id = Id::Standard(StandardId::new(0x123).unwrap());
+        assert!(id.arbitration_bits().eq(bits("001 0010 0011  0")));
+
+        // Base ID 0x48D, ID extension 0x5678.
+        let id = Id::Extended(ExtendedId::new(0x1234_5678).unwrap());
+        assert!(id
+            .arbitration_bits()
+            .eq(bits("100 1000 1101  1  00 0101 0110 0111 1000")));
+    }
+
     #[test]
     fn extended_id_new() {
         assert_eq!(
@@ -206,4 +706,246 @@ mod tests {
         assert!(Id::Extended(ExtendedId((1 << 11) - 1)) < Id::Standard(StandardId(1)));
         assert!(Id::Standard(StandardId(1)) < Id::Extended(ExtendedId::MAX));
     }
+
+    #[test]
+    fn arbitration_cmp_matches_id_cmp() {
+        let standard = [StandardId::ZERO, StandardId(0x123), StandardId::MAX];
+        let extended = [
+            ExtendedId::ZERO,
+            ExtendedId(0x123 << 18),
+            ExtendedId((0x123 << 18) | 1),
+            ExtendedId::MAX,
+        ];
+
+        for a in standard {
+            for b in standard {
+                assert_eq!(a.arbitration_cmp(&b), Id::from(a).cmp(&Id::from(b)));
+                assert_eq!(a.arbitration_cmp(&b), a.cmp(&b));
+            }
+        }
+        for a in extended {
+            for b in extended {
+                assert_eq!(a.arbitration_cmp(&b), Id::from(a).cmp(&Id::from(b)));
+                assert_eq!(a.arbitration_cmp(&b), a.cmp(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn cmp_id_same_base_id() {
+        let standard = Id::Standard(StandardId(0x123));
+        let extended = Id::Extended(ExtendedId(0x123 << 18));
+        assert!(standard < extended);
+        assert!(extended > standard);
+    }
+
+    #[test]
+    fn id_as_standard_and_extended() {
+        let standard = Id::Standard(StandardId::MAX);
+        let extended = Id::Extended(ExtendedId::MAX);
+
+        assert_eq!(standard.as_standard(), Some(StandardId::MAX));
+        assert_eq!(standard.as_extended(), None);
+        assert_eq!(extended.as_standard(), None);
+        assert_eq!(extended.as_extended(), Some(ExtendedId::MAX));
+    }
+
+    #[test]
+    fn try_from_id() {
+        let standard = Id::Standard(StandardId::MAX);
+        let extended = Id::Extended(ExtendedId::MAX);
+
+        assert_eq!(StandardId::try_from(standard), Ok(StandardId::MAX));
+        assert_eq!(StandardId::try_from(extended), Err(extended));
+        assert_eq!(ExtendedId::try_from(extended), Ok(ExtendedId::MAX));
+        assert_eq!(ExtendedId::try_from(standard), Err(standard));
+    }
+
+    #[test]
+    fn arbitration_key_matches_field_order() {
+        // Reference implementation comparing the fields in transmission order.
+        fn split_id(id: &Id) -> (u16, u8, u32) {
+            match id {
+                Id::Standard(x) => (x.0, 0, 0),
+                Id::Extended(x) => (x.standard_id().0, 1, x.0 & ((1 << 18) - 1)),
+            }
+        }
+
+        // xorshift32, to cover many IDs without pulling in a random number generator.
+        let mut state = 0x1234_5678u32;
+        let mut next_id = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            if state & 1 == 0 {
+                Id::Standard(StandardId((state >> 1) as u16 & 0x7FF))
+            } else {
+                Id::Extended(ExtendedId((state >> 1) & 0x1FFF_FFFF))
+            }
+        };
+
+        for _ in 0..10_000 {
+            let a = next_id();
+            let b = next_id();
+            assert_eq!(a.cmp(&b), split_id(&a).cmp(&split_id(&b)));
+            assert_eq!(a.cmp(&a), Ordering::Equal);
+        }
+    }
+
+    #[test]
+    fn arbitration_key() {
+        assert_eq!(Id::Standard(StandardId::ZERO).arbitration_key(), 0);
+        assert_eq!(Id::Standard(StandardId::MAX).arbitration_key(), 0x7FF << 19);
+        assert_eq!(Id::Extended(ExtendedId::ZERO).arbitration_key(), 1 << 18);
+        assert_eq!(Id::Extended(ExtendedId::MAX).arbitration_key(), 0x3FFF_FFFF);
+    }
+
+    #[test]
+    fn as_u32() {
+        assert_eq!(Id::STANDARD_MIN.as_u32(), 0);
+        assert_eq!(Id::STANDARD_MAX.as_u32(), 0x7FF);
+        assert_eq!(Id::EXTENDED_MIN.as_u32(), 0);
+        assert_eq!(Id::EXTENDED_MAX.as_u32(), 0x1FFF_FFFF);
+
+        let standard = StandardId::new(0x5A5).unwrap();
+        let extended = ExtendedId::new(0x1234_5678).unwrap();
+        assert_eq!(Id::from(standard).as_u32(), u32::from(standard));
+        assert_eq!(Id::from(extended).as_u32(), u32::from(extended));
+        assert_eq!(u32::from(standard), 0x5A5);
+        assert_eq!(u32::from(extended), 0x1234_5678);
+    }
+
+    #[test]
+    fn as_u32_and_arbitration_key_differ() {
+        let standard = Id::Standard(StandardId::new(0x5A5).unwrap());
+        let extended = Id::Extended(ExtendedId::new(0x5A5).unwrap());
+
+        // Same raw value, different keys.
+        assert_eq!(standard.as_u32(), extended.as_u32());
+        assert_eq!(standard.arbitration_key(), 0x5A5 << 19);
+        assert_eq!(extended.arbitration_key(), 1 << 18 | 0x5A5);
+
+        // The key of an extended ID holds its Base ID, IDE bit and ID extension.
+        let extended = Id::Extended(ExtendedId::new(0x1234_5678).unwrap());
+        let base = 0x1234_5678 >> 18;
+        let extension = 0x1234_5678 & 0x3_FFFF;
+        assert_eq!(extended.arbitration_key(), base << 19 | 1 << 18 | extension);
+    }
+
+    #[test]
+    fn id_eq_concrete_ids() {
+        let standard = Id::Standard(StandardId::new(0x123).unwrap());
+        let extended = Id::Extended(ExtendedId::new(0x123).unwrap());
+
+        assert_eq!(standard, StandardId::new(0x123).unwrap());
+        assert_eq!(StandardId::new(0x123).unwrap(), standard);
+        assert_ne!(standard, StandardId::ZERO);
+        assert_ne!(StandardId::ZERO, standard);
+
+        assert_eq!(extended, ExtendedId::new(0x123).unwrap());
+        assert_eq!(ExtendedId::new(0x123).unwrap(), extended);
+        assert_ne!(extended, ExtendedId::ZERO);
+        assert_ne!(ExtendedId::ZERO, extended);
+
+        // The variant must match, even if the raw values are equal.
+        assert_ne!(standard, ExtendedId::new(0x123).unwrap());
+        assert_ne!(ExtendedId::new(0x123).unwrap(), standard);
+        assert_ne!(extended, StandardId::new(0x123).unwrap());
+        assert_ne!(StandardId::new(0x123).unwrap(), extended);
+    }
+
+    #[test]
+    fn numeric_and_arbitration_order() {
+        let std = |raw| Id::Standard(StandardId::new(raw).unwrap());
+        let ext = |raw| Id::Extended(ExtendedId::new(raw).unwrap());
+        let ids = [
+            std(0x7FF),
+            ext(0x0400_0000),
+            std(0x101),
+            ext(0x0000_0001),
+            std(0x100),
+        ];
+
+        let mut arbitration = ids;
+        arbitration.sort_unstable();
+        assert_eq!(
+            arbitration,
+            [
+                // Base ID 0x000.
+                ext(0x0000_0001),
+                std(0x100),
+                // Base ID 0x100, after the standard ID with the same value.
+                ext(0x0400_0000),
+                std(0x101),
+                std(0x7FF),
+            ]
+        );
+
+        let mut numeric = ids.map(NumericId);
+        numeric.sort_unstable();
+        assert_eq!(
+            numeric.map(Id::from),
+            [
+                std(0x100),
+                std(0x101),
+                std(0x7FF),
+                ext(0x0000_0001),
+                ext(0x0400_0000),
+            ]
+        );
+    }
+
+    #[test]
+    fn id_bounds() {
+        assert_eq!(Id::STANDARD_MIN, StandardId::new(0).unwrap());
+        assert_eq!(Id::STANDARD_MAX, StandardId::new(0x7FF).unwrap());
+        assert_eq!(Id::EXTENDED_MIN, ExtendedId::new(0).unwrap());
+        assert_eq!(Id::EXTENDED_MAX, ExtendedId::new(0x1FFF_FFFF).unwrap());
+    }
+
+    #[test]
+    fn extended_id_fields() {
+        // J1939: priority 3, PDU1 format 0xEA (request), destination 0x17, source 0xF9.
+        let id = ExtendedId::new(0x0CEA_17F9).unwrap();
+        assert_eq!(id.field(26, 3), 3);
+        assert_eq!(id.field(25, 1), 0);
+        assert_eq!(id.field(24, 1), 0);
+        assert_eq!(id.field(16, 8), 0xEA);
+        assert_eq!(id.field(8, 8), 0x17);
+        assert_eq!(id.field(0, 8), 0xF9);
+        assert_eq!(id.field(0, 29), id.as_raw());
+        assert_eq!(id.field(29, 0), 0);
+
+        assert_eq!(id.with_field(8, 8, 0xFF).as_raw(), 0x0CEA_FFF9);
+        assert_eq!(id.with_field(26, 3, 7).as_raw(), 0x1CEA_17F9);
+        assert_eq!(id.with_field(0, 29, 0x123).as_raw(), 0x123);
+        assert_eq!(ExtendedId::MAX.with_field(1, 2, 0).as_raw(), 0x1FFF_FFF9);
+    }
+
+    #[test]
+    fn standard_id_fields() {
+        let id = StandardId::new(0x5A3).unwrap();
+        assert_eq!(id.field(8, 3), 0x5);
+        assert_eq!(id.field(0, 8), 0xA3);
+        assert_eq!(id.field(0, 11), 0x5A3);
+        assert_eq!(id.with_field(4, 4, 0xF).as_raw(), 0x5F3);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit field out of range of the identifier")]
+    fn extended_id_field_out_of_range() {
+        ExtendedId::MAX.field(24, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit field out of range of the identifier")]
+    fn standard_id_field_out_of_range() {
+        StandardId::MAX.with_field(8, 4, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "value doesn't fit in the bit field")]
+    fn with_field_value_too_large() {
+        ExtendedId::ZERO.with_field(0, 8, 0x100);
+    }
 }
@@ -0,0 +1,387 @@
+//! Minimal ISO-TP (ISO 15765-2) segmentation of payloads longer than a classical CAN frame.
+//!
+//! A payload of up to 7 bytes is sent in a single frame. Longer payloads, of up to 4095 bytes,
+//! are sent in a first frame carrying the payload length and 6 bytes, followed by consecutive
+//! frames of 7 bytes, numbered modulo 16. Each frame starts with a protocol control information
+//! (PCI) byte telling these frames apart.
+//!
+//! Only normal addressing is supported, and flow control is left to the caller: the
+//! [`Segmenter`] produces all the frames of a payload at once, and the [`Reassembler`] rejects
+//! flow control frames.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{Frame, Id};
+
+/// Maximum length of a payload, limited by the 12-bit length of first frames.
+pub const MAX_PAYLOAD_LEN: usize = 4095;
+
+/// Maximum length of a payload sent in a single frame.
+pub const SINGLE_FRAME_MAX_LEN: usize = 7;
+
+const SINGLE_FRAME: u8 = 0x0;
+const FIRST_FRAME: u8 = 0x1;
+const CONSECUTIVE_FRAME: u8 = 0x2;
+
+/// Error returned when segmenting or reassembling a payload.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum IsoTpError {
+    /// The payload to segment is empty, or longer than [`MAX_PAYLOAD_LEN`].
+    InvalidLength,
+    /// The payload being received doesn't fit in the reassembly buffer.
+    TooLong,
+    /// The frame is malformed, for example too short for the length it announces.
+    InvalidFrame,
+    /// The frame is a consecutive frame without a reception in progress, or isn't a single,
+    /// first or consecutive frame.
+    UnexpectedFrame,
+    /// The consecutive frame doesn't have the expected sequence number. The reception in
+    /// progress is aborted.
+    UnexpectedSequence,
+}
+
+impl fmt::Display for IsoTpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => write!(f, "Payload is empty or too long for ISO-TP"),
+            Self::TooLong => write!(f, "Payload is too long for the reassembly buffer"),
+            Self::InvalidFrame => write!(f, "Malformed ISO-TP frame"),
+            Self::UnexpectedFrame => write!(f, "Unexpected ISO-TP frame"),
+            Self::UnexpectedSequence => write!(f, "Unexpected consecutive frame sequence number"),
+        }
+    }
+}
+
+/// Iterator over the frames of a payload, splitting it into ISO-TP frames.
+///
+/// The frames only carry the bytes they need: the last consecutive frame and single frames are
+/// not padded to 8 bytes.
+///
+/// # Panics
+///
+/// Iterating panics if the frame type `F` can't hold 8 data bytes.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_can::isotp::{Reassembler, Segmenter};
+/// use embedded_can::{Frame, StandardId};
+/// # use embedded_can::Id;
+/// # struct CanFrame(Id, Vec<u8>);
+/// # impl Frame for CanFrame {
+/// #     fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> { Some(Self(id.into(), data.to_vec())) }
+/// #     fn new_remote(_: impl Into<Id>, _: usize) -> Option<Self> { None }
+/// #     fn is_extended(&self) -> bool { matches!(self.0, Id::Extended(_)) }
+/// #     fn is_remote_frame(&self) -> bool { false }
+/// #     fn id(&self) -> Id { self.0 }
+/// #     fn dlc(&self) -> usize { self.1.len() }
+/// #     fn data(&self) -> &[u8] { &self.1 }
+/// # }
+/// let id = StandardId::new(0x7E0).unwrap();
+/// let payload = [0x2E, 0xF1, 0x90, b'W', b'0', b'L', b'0', b'0', b'0', b'0', b'4', b'3'];
+///
+/// let frames: Vec<CanFrame> = Segmenter::new(id, &payload).unwrap().collect();
+/// assert_eq!(frames[0].data(), [0x10, 12, 0x2E, 0xF1, 0x90, b'W', b'0', b'L']);
+/// assert_eq!(frames[1].data(), [0x21, b'0', b'0', b'0', b'0', b'4', b'3']);
+///
+/// let mut reassembler = Reassembler::<64>::new();
+/// assert_eq!(reassembler.push(&frames[0]), Ok(None));
+/// assert_eq!(reassembler.push(&frames[1]), Ok(Some(&payload[..])));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Segmenter<'a, F> {
+    id: Id,
+    payload: &'a [u8],
+    /// Number of payload bytes already sent.
+    offset: usize,
+    /// Sequence number of the next consecutive frame.
+    sn: u8,
+    frame: PhantomData<fn() -> F>,
+}
+
+impl<'a, F: Frame> Segmenter<'a, F> {
+    /// Creates a new segmenter, sending `payload` in frames with identifier `id`.
+    ///
+    /// Returns [`IsoTpError::InvalidLength`] if `payload` is empty or longer than
+    /// [`MAX_PAYLOAD_LEN`].
+    #[inline]
+    pub fn new(id: impl Into<Id>, payload: &'a [u8]) -> Result<Self, IsoTpError> {
+        if payload.is_empty() || payload.len() > MAX_PAYLOAD_LEN {
+            return Err(IsoTpError::InvalidLength);
+        }
+        Ok(Self {
+            id: id.into(),
+            payload,
+            offset: 0,
+            sn: 1,
+            frame: PhantomData,
+        })
+    }
+}
+
+impl<F: Frame> Iterator for Segmenter<'_, F> {
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        let remaining = &self.payload[self.offset..];
+        if remaining.is_empty() {
+            return None;
+        }
+
+        let mut data = [0; 8];
+        let len = if self.offset == 0 && remaining.len() <= SINGLE_FRAME_MAX_LEN {
+            data[0] = SINGLE_FRAME << 4 | remaining.len() as u8;
+            data[1..=remaining.len()].copy_from_slice(remaining);
+            self.offset = remaining.len();
+            1 + remaining.len()
+        } else if self.offset == 0 {
+            let total = self.payload.len();
+            data[0] = FIRST_FRAME << 4 | (total >> 8) as u8;
+            data[1] = total as u8;
+            data[2..].copy_from_slice(&remaining[..6]);
+            self.offset = 6;
+            8
+        } else {
+            let n = remaining.len().min(7);
+            data[0] = CONSECUTIVE_FRAME << 4 | self.sn;
+            data[1..=n].copy_from_slice(&remaining[..n]);
+            self.offset += n;
+            self.sn = (self.sn + 1) & 0xF;
+            1 + n
+        };
+        Some(F::new(self.id, &data[..len]).expect("frame type can't hold 8 data bytes"))
+    }
+}
+
+/// Reassembly of ISO-TP payloads of up to `N` bytes from received frames.
+///
+/// Frames of a single sender must be [pushed](Self::push) in order. A single frame or first
+/// frame aborts the reception in progress, if any, and starts a new one.
+#[derive(Debug, Clone)]
+pub struct Reassembler<const N: usize> {
+    buf: [u8; N],
+    /// Length of the payload being received, or 0 if there is no reception in progress.
+    len: usize,
+    /// Number of payload bytes received so far.
+    received: usize,
+    /// Sequence number of the next consecutive frame.
+    sn: u8,
+}
+
+impl<const N: usize> Reassembler<N> {
+    /// Creates a new reassembler, without a reception in progress.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+            received: 0,
+            sn: 0,
+        }
+    }
+
+    /// Returns `true` if a first frame was received, and the payload isn't complete yet.
+    #[inline]
+    pub fn is_receiving(&self) -> bool {
+        self.len != 0
+    }
+
+    /// Aborts the reception in progress, if any.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.len = 0;
+    }
+
+    /// Consumes a received frame, returning the payload once it is complete.
+    ///
+    /// Bytes past the payload, such as padding, are ignored. On error, the reception in
+    /// progress is aborted, except for [`IsoTpError::UnexpectedFrame`], which leaves it
+    /// unchanged.
+    pub fn push<F: Frame>(&mut self, frame: &F) -> Result<Option<&[u8]>, IsoTpError> {
+        let data = frame.data();
+        let pci = *data.first().ok_or(IsoTpError::InvalidFrame)?;
+        match pci >> 4 {
+            SINGLE_FRAME => {
+                self.reset();
+                let len = usize::from(pci & 0xF);
+                if len == 0 || len > SINGLE_FRAME_MAX_LEN || len >= data.len() {
+                    return Err(IsoTpError::InvalidFrame);
+                }
+                let buf = self.buf.get_mut(..len).ok_or(IsoTpError::TooLong)?;
+                buf.copy_from_slice(&data[1..=len]);
+                Ok(Some(buf))
+            }
+            FIRST_FRAME => {
+                self.reset();
+                let len = usize::from(pci & 0xF) << 8 | usize::from(*data.get(1).unwrap_or(&0));
+                if data.len() < 8 || len <= SINGLE_FRAME_MAX_LEN {
+                    return Err(IsoTpError::InvalidFrame);
+                }
+                if len > N {
+                    return Err(IsoTpError::TooLong);
+                }
+                self.buf[..6].copy_from_slice(&data[2..8]);
+                self.len = len;
+                self.received = 6;
+                self.sn = 1;
+                Ok(None)
+            }
+            CONSECUTIVE_FRAME if self.is_receiving() => {
+                if pci & 0xF != self.sn {
+                    self.reset();
+                    return Err(IsoTpError::UnexpectedSequence);
+                }
+                let n = (self.len - self.received).min(7);
+                if data.len() <= n {
+                    self.reset();
+                    return Err(IsoTpError::InvalidFrame);
+                }
+                self.buf[self.received..self.received + n].copy_from_slice(&data[1..=n]);
+                self.received += n;
+                self.sn = (self.sn + 1) & 0xF;
+                if self.received < self.len {
+                    return Ok(None);
+                }
+                let len = self.len;
+                self.reset();
+                Ok(Some(&self.buf[..len]))
+            }
+            _ => Err(IsoTpError::UnexpectedFrame),
+        }
+    }
+}
+
+impl<const N: usize> Default for Reassembler<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestFrame;
+    use crate::StandardId;
+
+    fn id() -> StandardId {
+        StandardId::new(0x7E8).unwrap()
+    }
+
+    fn payload<const L: usize>() -> [u8; L] {
+        let mut payload = [0; L];
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        payload
+    }
+
+    /// Reassembles `frames`, checking only the last one completes the payload.
+    fn reassemble<'a, const N: usize>(
+        reassembler: &'a mut Reassembler<N>,
+        frames: &[TestFrame],
+    ) -> &'a [u8] {
+        let (last, frames) = frames.split_last().unwrap();
+        for frame in frames {
+            assert_eq!(reassembler.push(frame), Ok(None));
+        }
+        reassembler.push(last).unwrap().unwrap()
+    }
+
+    #[test]
+    fn round_trip_30_bytes() {
+        let payload = payload::<30>();
+        let mut frames = [(); 5].map(|()| TestFrame::new(id(), &[]).unwrap());
+        let mut segmenter = Segmenter::new(id(), &payload).unwrap();
+        for frame in &mut frames {
+            *frame = segmenter.next().unwrap();
+        }
+        assert!(segmenter.next().is_none());
+
+        assert!(frames.iter().all(|frame| frame.id() == id()));
+        assert_eq!(frames[0].data(), [0x10, 30, 0, 1, 2, 3, 4, 5]);
+        assert_eq!(frames[1].data(), [0x21, 6, 7, 8, 9, 10, 11, 12]);
+        assert_eq!(frames[2].data(), [0x22, 13, 14, 15, 16, 17, 18, 19]);
+        assert_eq!(frames[3].data(), [0x23, 20, 21, 22, 23, 24, 25, 26]);
+        assert_eq!(frames[4].data(), [0x24, 27, 28, 29]);
+
+        let mut reassembler = Reassembler::<30>::new();
+        assert_eq!(reassemble(&mut reassembler, &frames), payload);
+        assert!(!reassembler.is_receiving());
+    }
+
+    #[test]
+    fn single_frame() {
+        let payload = payload::<7>();
+        let mut segmenter = Segmenter::<TestFrame>::new(id(), &payload).unwrap();
+        let frame = segmenter.next().unwrap();
+        assert!(segmenter.next().is_none());
+        assert_eq!(frame.data(), [0x07, 0, 1, 2, 3, 4, 5, 6]);
+
+        let mut reassembler = Reassembler::<8>::new();
+        assert_eq!(reassembler.push(&frame), Ok(Some(&payload[..])));
+
+        // Padding is ignored.
+        let padded = TestFrame::new(id(), &[0x02, 0xAA, 0xBB, 0xCC, 0xCC, 0xCC]).unwrap();
+        assert_eq!(reassembler.push(&padded), Ok(Some(&[0xAA, 0xBB][..])));
+    }
+
+    #[test]
+    fn sequence_number_wraps() {
+        let payload = payload::<200>();
+        let segmenter = Segmenter::<TestFrame>::new(id(), &payload).unwrap();
+        let mut reassembler = Reassembler::<256>::new();
+        let mut complete = None;
+        for (i, frame) in segmenter.enumerate() {
+            if i > 0 {
+                assert_eq!(frame.data()[0], 0x20 | (i % 16) as u8);
+            }
+            assert!(complete.is_none());
+            complete = reassembler.push(&frame).unwrap().map(<[u8]>::len);
+        }
+        assert_eq!(complete, Some(200));
+        assert_eq!(reassembler.buf[..200], payload);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Segmenter::<TestFrame>::new(id(), &[]).err(),
+            Some(IsoTpError::InvalidLength)
+        );
+        let long = [0; MAX_PAYLOAD_LEN + 1];
+        assert_eq!(
+            Segmenter::<TestFrame>::new(id(), &long).err(),
+            Some(IsoTpError::InvalidLength)
+        );
+
+        let payload = payload::<20>();
+        let mut frames = Segmenter::<TestFrame>::new(id(), &payload).unwrap();
+        let (first, second, third) = (
+            frames.next().unwrap(),
+            frames.next().unwrap(),
+            frames.next().unwrap(),
+        );
+
+        let mut small = Reassembler::<16>::new();
+        assert_eq!(small.push(&first), Err(IsoTpError::TooLong));
+
+        let mut reassembler = Reassembler::<32>::new();
+        assert_eq!(reassembler.push(&second), Err(IsoTpError::UnexpectedFrame));
+        assert_eq!(reassembler.push(&first), Ok(None));
+        assert_eq!(
+            reassembler.push(&third),
+            Err(IsoTpError::UnexpectedSequence)
+        );
+        assert!(!reassembler.is_receiving());
+
+        let flow_control = TestFrame::new(id(), &[0x30, 0x00, 0x00]).unwrap();
+        assert_eq!(
+            reassembler.push(&flow_control),
+            Err(IsoTpError::UnexpectedFrame)
+        );
+        let empty = TestFrame::new(id(), &[]).unwrap();
+        assert_eq!(reassembler.push(&empty), Err(IsoTpError::InvalidFrame));
+    }
+}
@@ -4,11 +4,32 @@
 #![no_std]
 
 pub mod blocking;
+pub mod crc;
+#[cfg(feature = "isotp")]
+pub mod isotp;
 pub mod nb;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 
+#[cfg(feature = "heapless")]
+mod fd;
+mod filter;
+mod gateway;
 mod id;
+#[cfg(feature = "heapless")]
+mod ring;
+mod timestamp;
+mod trace;
 
+#[cfg(feature = "heapless")]
+pub use fd::*;
+pub use filter::*;
+pub use gateway::*;
 pub use id::*;
+#[cfg(feature = "heapless")]
+pub use ring::*;
+pub use timestamp::*;
+pub use trace::*;
 
 /// A CAN2.0 Frame
 pub trait Frame: Sized {
@@ -51,6 +72,51 @@ pub trait Frame: Sized {
     fn data(&self) -> &[u8];
 }
 
+/// A CAN2.0 [`Frame`] whose data can be modified in place.
+///
+/// This is separate from [`Frame`] so that adding it doesn't break existing implementations.
+pub trait FrameMut: Frame {
+    /// Returns the frame data as a mutable slice, to patch it in place.
+    ///
+    /// The slice has the same length as [`data`](Frame::data), so this can't change the DLC.
+    fn data_mut(&mut self) -> &mut [u8];
+
+    /// Replaces the frame data, turning a remote frame into a data frame.
+    ///
+    /// This will return [`LenError`] and leave the frame unchanged if `data` is too long.
+    ///
+    /// The default implementation rebuilds the frame with [`new`](Frame::new) and the current
+    /// identifier. Implementations carrying more state than the identifier and data should
+    /// override it.
+    fn set_data(&mut self, data: &[u8]) -> Result<(), LenError> {
+        *self = Self::new(self.id(), data).ok_or(LenError)?;
+        Ok(())
+    }
+}
+
+/// Error returned by [`FrameMut::set_data`] when the data doesn't fit in the frame.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct LenError;
+
+impl core::fmt::Display for LenError {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Data is too long for the frame")
+    }
+}
+
+/// Sorts `frames` in the order they would be sent on the bus, highest priority first.
+///
+/// When several frames are pending at once, the bus arbitration lets the frame with the
+/// smallest [`Id`] (see its `Ord` implementation) go first. Between frames with the same
+/// identifier, data frames win over remote frames.
+///
+/// The sort is done in place and doesn't allocate. It is not stable, so frames with the same
+/// identifier and type may be reordered.
+pub fn sort_by_arbitration<F: Frame>(frames: &mut [F]) {
+    frames.sort_unstable_by_key(|frame| (frame.id(), frame.is_remote_frame()));
+}
+
 /// CAN error
 pub trait Error: core::fmt::Debug {
     /// Convert error to a generic CAN error kind
@@ -131,3 +197,106 @@ impl core::fmt::Display for ErrorKind {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Classic CAN frame shared by the tests of all modules.
+    #[derive(Debug, PartialEq)]
+    pub(crate) struct TestFrame {
+        id: Id,
+        remote: bool,
+        dlc: usize,
+        data: [u8; 8],
+    }
+
+    impl Frame for TestFrame {
+        fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+            let mut buf = [0; 8];
+            buf.get_mut(..data.len())?.copy_from_slice(data);
+            Some(Self {
+                id: id.into(),
+                remote: false,
+                dlc: data.len(),
+                data: buf,
+            })
+        }
+        fn new_remote(id: impl Into<Id>, dlc: usize) -> Option<Self> {
+            (dlc <= 8).then(|| Self {
+                id: id.into(),
+                remote: true,
+                dlc,
+                data: [0; 8],
+            })
+        }
+        fn is_extended(&self) -> bool {
+            matches!(self.id, Id::Extended(_))
+        }
+        fn is_remote_frame(&self) -> bool {
+            self.remote
+        }
+        fn id(&self) -> Id {
+            self.id
+        }
+        fn dlc(&self) -> usize {
+            self.dlc
+        }
+        fn data(&self) -> &[u8] {
+            if self.remote {
+                &[]
+            } else {
+                &self.data[..self.dlc]
+            }
+        }
+    }
+
+    impl FrameMut for TestFrame {
+        fn data_mut(&mut self) -> &mut [u8] {
+            if self.remote {
+                &mut []
+            } else {
+                &mut self.data[..self.dlc]
+            }
+        }
+    }
+
+    fn data(id: impl Into<Id>) -> TestFrame {
+        TestFrame::new(id, &[]).unwrap()
+    }
+
+    fn remote(id: impl Into<Id>) -> TestFrame {
+        TestFrame::new_remote(id, 0).unwrap()
+    }
+
+    #[test]
+    fn sort_by_arbitration_order() {
+        let std = |raw| StandardId::new(raw).unwrap();
+        let ext = |raw| ExtendedId::new(raw).unwrap();
+
+        let mut frames = [
+            data(ext(0x0800_0000)),
+            remote(std(0x100)),
+            data(std(0x101)),
+            data(ext(0x0400_0000)),
+            data(std(0x100)),
+            data(std(0x000)),
+            remote(ext(0x0400_0000)),
+        ];
+        sort_by_arbitration(&mut frames);
+
+        assert_eq!(
+            frames,
+            [
+                data(std(0x000)),
+                data(std(0x100)),
+                remote(std(0x100)),
+                // Same base ID as 0x100, but standard frames win over extended ones.
+                data(ext(0x0400_0000)),
+                remote(ext(0x0400_0000)),
+                data(std(0x101)),
+                data(ext(0x0800_0000)),
+            ]
+        );
+    }
+}
@@ -26,3 +26,93 @@ pub trait Can {
     /// Returns a received frame if available.
     fn receive(&mut self) -> nb::Result<Self::Frame, Self::Error>;
 }
+
+/// Puts as many `frames` as possible in the transmit buffer, in order.
+///
+/// Stops at the first frame that doesn't fit, when [`Can::transmit`] returns `WouldBlock`, and
+/// returns the number of frames that were accepted. The remaining frames can be passed again
+/// once the controller has made room. Other errors are returned immediately.
+///
+/// Pending frames that [`Can::transmit`] replaces with higher priority ones are dropped. If they
+/// must be kept, call [`Can::transmit`] directly.
+pub fn transmit_all<T: Can>(can: &mut T, frames: &[T::Frame]) -> Result<usize, T::Error> {
+    for (sent, frame) in frames.iter().enumerate() {
+        match can.transmit(frame) {
+            Ok(_) => {}
+            Err(nb::Error::WouldBlock) => return Ok(sent),
+            Err(nb::Error::Other(e)) => return Err(e),
+        }
+    }
+    Ok(frames.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestFrame;
+    use crate::{ErrorKind, Frame, StandardId};
+
+    /// Accepts `free` frames, then blocks.
+    struct Mailbox {
+        free: usize,
+        sent: usize,
+        fail: bool,
+    }
+
+    impl Can for Mailbox {
+        type Frame = TestFrame;
+        type Error = ErrorKind;
+
+        fn transmit(&mut self, _frame: &TestFrame) -> nb::Result<Option<TestFrame>, ErrorKind> {
+            if self.fail {
+                return Err(nb::Error::Other(ErrorKind::Bit));
+            }
+            if self.sent == self.free {
+                return Err(nb::Error::WouldBlock);
+            }
+            self.sent += 1;
+            Ok(None)
+        }
+
+        fn receive(&mut self) -> nb::Result<TestFrame, ErrorKind> {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    fn frames() -> [TestFrame; 3] {
+        [0x100, 0x200, 0x300].map(|raw| TestFrame::new(StandardId::new(raw).unwrap(), &[]).unwrap())
+    }
+
+    #[test]
+    fn transmit_all_stops_when_full() {
+        let mut can = Mailbox {
+            free: 2,
+            sent: 0,
+            fail: false,
+        };
+        assert_eq!(transmit_all(&mut can, &frames()), Ok(2));
+        assert_eq!(can.sent, 2);
+        assert_eq!(transmit_all(&mut can, &frames()[2..]), Ok(0));
+    }
+
+    #[test]
+    fn transmit_all_sends_everything() {
+        let mut can = Mailbox {
+            free: 8,
+            sent: 0,
+            fail: false,
+        };
+        assert_eq!(transmit_all(&mut can, &frames()), Ok(3));
+        assert_eq!(transmit_all(&mut can, &[]), Ok(0));
+    }
+
+    #[test]
+    fn transmit_all_propagates_errors() {
+        let mut can = Mailbox {
+            free: 8,
+            sent: 0,
+            fail: true,
+        };
+        assert_eq!(transmit_all(&mut can, &frames()), Err(ErrorKind::Bit));
+    }
+}
@@ -0,0 +1,251 @@
+//! Ring buffer for received frames.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use heapless::spsc::{Consumer, Producer, Queue};
+
+/// Fixed-capacity ring buffer of received frames, backed by a [`heapless::spsc::Queue`].
+///
+/// This is meant for passing frames from a receive interrupt handler to the main loop: after
+/// [splitting](Self::split) the ring, the [`FrameProducer`] and [`FrameConsumer`] halves can be
+/// used from different contexts without any locking.
+///
+/// The ring holds up to `N - 1` frames. When it is full, newly received frames are dropped and
+/// counted as overruns, so the consumer can tell frames were lost.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_can::FrameRing;
+///
+/// let mut ring = FrameRing::<u32, 4>::new();
+/// let (mut producer, mut consumer) = ring.split();
+///
+/// // In the receive interrupt handler:
+/// for frame in 0..5 {
+///     let _ = producer.push(frame);
+/// }
+///
+/// // In the main loop:
+/// assert_eq!(consumer.pop(), Some(0));
+/// assert_eq!(consumer.overruns(), 2);
+/// ```
+pub struct FrameRing<F, const N: usize> {
+    queue: Queue<F, N>,
+    overruns: AtomicUsize,
+}
+
+impl<F, const N: usize> FrameRing<F, N> {
+    /// Creates an empty ring.
+    ///
+    /// `N` must be at least 2, which is checked at compile time.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            queue: Queue::new(),
+            overruns: AtomicUsize::new(0),
+        }
+    }
+
+    /// Adds a frame at the back of the ring.
+    ///
+    /// If the ring is full, the overrun count is incremented and `frame` is returned back.
+    #[inline]
+    // `Result::inspect_err` is newer than the MSRV.
+    #[allow(clippy::manual_inspect)]
+    pub fn push(&mut self, frame: F) -> Result<(), F> {
+        self.queue.enqueue(frame).map_err(|frame| {
+            count_overrun(&self.overruns);
+            frame
+        })
+    }
+
+    /// Removes the oldest frame from the ring.
+    #[inline]
+    pub fn pop(&mut self) -> Option<F> {
+        self.queue.dequeue()
+    }
+
+    /// Returns the number of frames in the ring.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if the ring holds no frames.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns the maximum number of frames the ring can hold, `N - 1`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    /// Returns the number of frames dropped because the ring was full.
+    ///
+    /// The count wraps around at `usize::MAX`.
+    #[inline]
+    pub fn overruns(&self) -> usize {
+        self.overruns.load(Ordering::Relaxed)
+    }
+
+    /// Splits the ring into a producer and a consumer half.
+    #[inline]
+    pub fn split(&mut self) -> (FrameProducer<'_, F, N>, FrameConsumer<'_, F, N>) {
+        let (producer, consumer) = self.queue.split();
+        let overruns = &self.overruns;
+        (
+            FrameProducer { producer, overruns },
+            FrameConsumer { consumer, overruns },
+        )
+    }
+}
+
+impl<F, const N: usize> Default for FrameRing<F, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Producer half of a [`FrameRing`], typically owned by the receive interrupt handler.
+pub struct FrameProducer<'a, F, const N: usize> {
+    producer: Producer<'a, F, N>,
+    overruns: &'a AtomicUsize,
+}
+
+impl<F, const N: usize> FrameProducer<'_, F, N> {
+    /// Adds a frame at the back of the ring.
+    ///
+    /// If the ring is full, the overrun count is incremented and `frame` is returned back.
+    #[inline]
+    // `Result::inspect_err` is newer than the MSRV.
+    #[allow(clippy::manual_inspect)]
+    pub fn push(&mut self, frame: F) -> Result<(), F> {
+        self.producer.enqueue(frame).map_err(|frame| {
+            count_overrun(self.overruns);
+            frame
+        })
+    }
+}
+
+/// Consumer half of a [`FrameRing`], typically owned by the main loop.
+pub struct FrameConsumer<'a, F, const N: usize> {
+    consumer: Consumer<'a, F, N>,
+    overruns: &'a AtomicUsize,
+}
+
+impl<F, const N: usize> FrameConsumer<'_, F, N> {
+    /// Removes the oldest frame from the ring.
+    #[inline]
+    pub fn pop(&mut self) -> Option<F> {
+        self.consumer.dequeue()
+    }
+
+    /// Returns the number of frames in the ring.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.consumer.len()
+    }
+
+    /// Returns true if the ring holds no frames.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        !self.consumer.ready()
+    }
+
+    /// Returns the number of frames dropped because the ring was full.
+    ///
+    /// The count wraps around at `usize::MAX`.
+    #[inline]
+    pub fn overruns(&self) -> usize {
+        self.overruns.load(Ordering::Relaxed)
+    }
+}
+
+/// Increments the overrun count.
+#[inline]
+fn count_overrun(overruns: &AtomicUsize) {
+    // Only the producer writes the count, so this doesn't need an atomic read-modify-write,
+    // which some targets don't support.
+    let count = overruns.load(Ordering::Relaxed);
+    overruns.store(count.wrapping_add(1), Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_and_drain() {
+        let mut ring = FrameRing::<u32, 4>::new();
+        assert!(ring.is_empty());
+        assert_eq!(ring.capacity(), 3);
+
+        for frame in 0..3 {
+            ring.push(frame).unwrap();
+        }
+        assert_eq!(ring.len(), 3);
+
+        for frame in 0..3 {
+            assert_eq!(ring.pop(), Some(frame));
+        }
+        assert_eq!(ring.pop(), None);
+        assert!(ring.is_empty());
+        assert_eq!(ring.overruns(), 0);
+    }
+
+    #[test]
+    fn wraparound() {
+        let mut ring = FrameRing::<u32, 4>::new();
+
+        for frame in 0..10 {
+            ring.push(frame).unwrap();
+            ring.push(frame + 100).unwrap();
+            assert_eq!(ring.pop(), Some(frame));
+            assert_eq!(ring.pop(), Some(frame + 100));
+        }
+        assert!(ring.is_empty());
+        assert_eq!(ring.overruns(), 0);
+    }
+
+    #[test]
+    fn overruns() {
+        let mut ring = FrameRing::<u32, 4>::new();
+
+        for frame in 0..3 {
+            ring.push(frame).unwrap();
+        }
+        assert_eq!(ring.push(3), Err(3));
+        assert_eq!(ring.push(4), Err(4));
+        assert_eq!(ring.overruns(), 2);
+
+        // The oldest frames are kept.
+        assert_eq!(ring.pop(), Some(0));
+        ring.push(5).unwrap();
+        assert_eq!(ring.overruns(), 2);
+    }
+
+    #[test]
+    fn split() {
+        let mut ring = FrameRing::<u32, 3>::new();
+        let (mut producer, mut consumer) = ring.split();
+
+        producer.push(1).unwrap();
+        producer.push(2).unwrap();
+        assert_eq!(producer.push(3), Err(3));
+        assert_eq!(consumer.len(), 2);
+        assert_eq!(consumer.overruns(), 1);
+
+        assert_eq!(consumer.pop(), Some(1));
+        producer.push(4).unwrap();
+        assert_eq!(consumer.pop(), Some(2));
+        assert_eq!(consumer.pop(), Some(4));
+        assert!(consumer.is_empty());
+
+        assert_eq!(ring.overruns(), 1);
+    }
+}
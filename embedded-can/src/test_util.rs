@@ -0,0 +1,204 @@
+//! Helpers for testing CAN drivers.
+//!
+//! These compare frames through the [`Frame`] trait only, so test suites can check the frames of
+//! different HAL implementations against each other, or against a reference implementation.
+
+use core::fmt;
+
+use crate::{Frame, Id};
+
+/// Asserts that two frames are equal, possibly of different [`Frame`] types.
+///
+/// Frames are equal if they have the same identifier (including whether it is standard or
+/// extended), are both data or both remote frames, and have the same DLC and data.
+///
+/// # Panics
+///
+/// Panics if the frames differ, with a message showing both frames and what differs between
+/// them, down to the individual data bytes.
+///
+/// # Examples
+///
+/// ```should_panic
+/// use embedded_can::test_util::frame_assert_eq;
+/// # use embedded_can::{Frame, Id, StandardId};
+/// # struct HalFrame(Id, Vec<u8>);
+/// # impl Frame for HalFrame {
+/// #     fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> { Some(Self(id.into(), data.to_vec())) }
+/// #     fn new_remote(_: impl Into<Id>, _: usize) -> Option<Self> { None }
+/// #     fn is_extended(&self) -> bool { matches!(self.0, Id::Extended(_)) }
+/// #     fn is_remote_frame(&self) -> bool { false }
+/// #     fn id(&self) -> Id { self.0 }
+/// #     fn dlc(&self) -> usize { self.1.len() }
+/// #     fn data(&self) -> &[u8] { &self.1 }
+/// # }
+/// # type OtherHalFrame = HalFrame;
+/// let id = StandardId::new(0x123).unwrap();
+/// let sent = HalFrame::new(id, &[0x01, 0x02, 0x03]).unwrap();
+/// let received = OtherHalFrame::new(id, &[0x01, 0xFF, 0x03]).unwrap();
+///
+/// // Panics with:
+/// // frames are not equal
+/// //   left: standard ID 0x123, data frame, DLC 3, data [01 02 03]
+/// //  right: standard ID 0x123, data frame, DLC 3, data [01 FF 03]
+/// //   diff: data[1] 02 != FF
+/// frame_assert_eq(&sent, &received);
+/// ```
+#[track_caller]
+pub fn frame_assert_eq(left: &impl Frame, right: &impl Frame) {
+    let left = FrameFields::new(left);
+    let right = FrameFields::new(right);
+    if left != right {
+        panic!(
+            "frames are not equal\n  left: {}\n right: {}\n  diff: {}",
+            left,
+            right,
+            Diff(&left, &right)
+        );
+    }
+}
+
+/// The fields of a [`Frame`] compared by [`frame_assert_eq`].
+#[derive(PartialEq)]
+struct FrameFields<'a> {
+    id: Id,
+    remote: bool,
+    dlc: usize,
+    data: &'a [u8],
+}
+
+impl<'a> FrameFields<'a> {
+    fn new(frame: &'a impl Frame) -> Self {
+        Self {
+            id: frame.id(),
+            remote: frame.is_remote_frame(),
+            dlc: frame.dlc(),
+            data: frame.data(),
+        }
+    }
+}
+
+impl fmt::Display for FrameFields<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.id {
+            Id::Standard(id) => write!(f, "standard ID {:#05X}", id.as_raw())?,
+            Id::Extended(id) => write!(f, "extended ID {:#010X}", id.as_raw())?,
+        }
+        let kind = if self.remote { "remote" } else { "data" };
+        write!(f, ", {} frame, DLC {}, data [", kind, self.dlc)?;
+        for (i, byte) in self.data.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{:02X}", byte)?;
+        }
+        f.write_str("]")
+    }
+}
+
+/// The differences between two frames, separated by commas.
+struct Diff<'a, 'b>(&'a FrameFields<'b>, &'a FrameFields<'b>);
+
+impl fmt::Display for Diff<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (left, right) = (self.0, self.1);
+        let mut sep = "";
+        let mut item = |f: &mut fmt::Formatter<'_>, args: fmt::Arguments<'_>| {
+            f.write_str(sep)?;
+            sep = ", ";
+            f.write_fmt(args)
+        };
+
+        if matches!(left.id, Id::Extended(_)) != matches!(right.id, Id::Extended(_)) {
+            item(f, format_args!("ID format"))?;
+        } else if left.id != right.id {
+            item(f, format_args!("ID"))?;
+        }
+        if left.remote != right.remote {
+            item(f, format_args!("frame kind"))?;
+        }
+        if left.dlc != right.dlc {
+            item(f, format_args!("DLC {} != {}", left.dlc, right.dlc))?;
+        }
+        for i in 0..left.data.len().max(right.data.len()) {
+            match (left.data.get(i), right.data.get(i)) {
+                (Some(l), Some(r)) if l == r => {}
+                (Some(l), Some(r)) => item(f, format_args!("data[{}] {:02X} != {:02X}", i, l, r))?,
+                (Some(l), None) => item(f, format_args!("data[{}] {:02X} != none", i, l))?,
+                (None, Some(r)) => item(f, format_args!("data[{}] none != {:02X}", i, r))?,
+                (None, None) => unreachable!(),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestFrame;
+    use crate::{ExtendedId, StandardId};
+
+    /// A frame of another type, storing only its identifier and data.
+    struct OtherFrame(Id, [u8; 3]);
+
+    impl Frame for OtherFrame {
+        fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+            Some(Self(id.into(), data.try_into().ok()?))
+        }
+        fn new_remote(_id: impl Into<Id>, _dlc: usize) -> Option<Self> {
+            None
+        }
+        fn is_extended(&self) -> bool {
+            matches!(self.0, Id::Extended(_))
+        }
+        fn is_remote_frame(&self) -> bool {
+            false
+        }
+        fn id(&self) -> Id {
+            self.0
+        }
+        fn dlc(&self) -> usize {
+            self.1.len()
+        }
+        fn data(&self) -> &[u8] {
+            &self.1
+        }
+    }
+
+    fn id() -> StandardId {
+        StandardId::new(0x123).unwrap()
+    }
+
+    #[test]
+    fn equal_frames() {
+        let frame = TestFrame::new(id(), &[1, 2, 3]).unwrap();
+        frame_assert_eq(&frame, &TestFrame::new(id(), &[1, 2, 3]).unwrap());
+        frame_assert_eq(&frame, &OtherFrame::new(id(), &[1, 2, 3]).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "frames are not equal
+  left: standard ID 0x123, data frame, DLC 3, data [01 02 03]
+ right: standard ID 0x123, data frame, DLC 3, data [01 FF 03]
+  diff: data[1] 02 != FF")]
+    fn different_data() {
+        frame_assert_eq(
+            &TestFrame::new(id(), &[1, 2, 3]).unwrap(),
+            &OtherFrame::new(id(), &[1, 0xFF, 3]).unwrap(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "
+  left: standard ID 0x123, data frame, DLC 3, data [01 02 03]
+ right: extended ID 0x00000123, remote frame, DLC 2, data []
+  diff: ID format, frame kind, DLC 3 != 2, data[0] 01 != none, data[1] 02 != none, \
+           data[2] 03 != none")]
+    fn different_everything() {
+        frame_assert_eq(
+            &TestFrame::new(id(), &[1, 2, 3]).unwrap(),
+            &TestFrame::new_remote(ExtendedId::new(0x123).unwrap(), 2).unwrap(),
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Timestamped CAN frames.
+
+use crate::{Frame, FrameMut, Id, LenError};
+
+/// A [`Frame`] paired with a timestamp, such as the time it was received at.
+///
+/// The timestamp is a raw tick count of a clock chosen by the HAL, which should document its
+/// frequency and when it wraps around.
+///
+/// `TimestampedFrame` implements [`Frame`], and [`FrameMut`] if the wrapped frame does, by
+/// delegating to the wrapped frame, so it can be used wherever the frame itself is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TimestampedFrame<F> {
+    frame: F,
+    timestamp: u64,
+}
+
+impl<F> TimestampedFrame<F> {
+    /// Pairs `frame` with `timestamp`.
+    #[inline]
+    pub const fn new(frame: F, timestamp: u64) -> Self {
+        Self { frame, timestamp }
+    }
+
+    /// Returns the timestamp, in ticks.
+    #[inline]
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Returns a reference to the frame.
+    #[inline]
+    pub fn frame(&self) -> &F {
+        &self.frame
+    }
+
+    /// Returns the frame, discarding the timestamp.
+    #[inline]
+    pub fn into_frame(self) -> F {
+        self.frame
+    }
+}
+
+/// Frames created through this implementation have a timestamp of 0.
+impl<F: Frame> Frame for TimestampedFrame<F> {
+    #[inline]
+    fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+        F::new(id, data).map(|frame| Self::new(frame, 0))
+    }
+
+    #[inline]
+    fn new_remote(id: impl Into<Id>, dlc: usize) -> Option<Self> {
+        F::new_remote(id, dlc).map(|frame| Self::new(frame, 0))
+    }
+
+    #[inline]
+    fn is_extended(&self) -> bool {
+        self.frame.is_extended()
+    }
+
+    #[inline]
+    fn is_standard(&self) -> bool {
+        self.frame.is_standard()
+    }
+
+    #[inline]
+    fn is_remote_frame(&self) -> bool {
+        self.frame.is_remote_frame()
+    }
+
+    #[inline]
+    fn is_data_frame(&self) -> bool {
+        self.frame.is_data_frame()
+    }
+
+    #[inline]
+    fn id(&self) -> Id {
+        self.frame.id()
+    }
+
+    #[inline]
+    fn dlc(&self) -> usize {
+        self.frame.dlc()
+    }
+
+    #[inline]
+    fn data(&self) -> &[u8] {
+        self.frame.data()
+    }
+}
+
+impl<F: FrameMut> FrameMut for TimestampedFrame<F> {
+    #[inline]
+    fn data_mut(&mut self) -> &mut [u8] {
+        self.frame.data_mut()
+    }
+
+    #[inline]
+    fn set_data(&mut self, data: &[u8]) -> Result<(), LenError> {
+        self.frame.set_data(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestFrame;
+    use crate::{ExtendedId, StandardId};
+
+    #[test]
+    fn timestamped_frame() {
+        let id = StandardId::new(0x123).unwrap();
+        let frame = TestFrame::new(id, &[1, 2, 3]).unwrap();
+        let frame = TimestampedFrame::new(frame, 123_456_789);
+
+        assert_eq!(frame.timestamp(), 123_456_789);
+        assert_eq!(frame.id(), id);
+        assert!(frame.is_standard());
+        assert!(frame.is_data_frame());
+        assert_eq!(frame.dlc(), 3);
+        assert_eq!(frame.data(), [1, 2, 3]);
+        assert_eq!(frame.into_frame(), TestFrame::new(id, &[1, 2, 3]).unwrap());
+    }
+
+    #[test]
+    fn frame_constructors() {
+        let id = ExtendedId::new(0x1234_5678).unwrap();
+
+        let frame = TimestampedFrame::<TestFrame>::new_remote(id, 4).unwrap();
+        assert_eq!(frame.timestamp(), 0);
+        assert!(frame.is_extended());
+        assert!(frame.is_remote_frame());
+        assert_eq!(frame.dlc(), 4);
+
+        assert!(<TimestampedFrame<TestFrame> as Frame>::new(id, &[0; 9]).is_none());
+        assert!(TimestampedFrame::<TestFrame>::new_remote(id, 9).is_none());
+    }
+
+    #[test]
+    fn mutate_data() {
+        let id = StandardId::new(0x123).unwrap();
+        let frame = TestFrame::new(id, &[1, 2, 3]).unwrap();
+        let mut frame = TimestampedFrame::new(frame, 42);
+
+        frame.data_mut()[1] = 0xAA;
+        assert_eq!(frame.data(), [1, 0xAA, 3]);
+
+        frame.set_data(&[4, 5, 6, 7, 8]).unwrap();
+        assert_eq!(frame.data(), [4, 5, 6, 7, 8]);
+        assert_eq!(frame.dlc(), 5);
+        assert_eq!(frame.id(), id);
+        assert_eq!(frame.timestamp(), 42);
+
+        assert_eq!(frame.set_data(&[0; 9]), Err(LenError));
+        assert_eq!(frame.data(), [4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn set_data_on_remote_frame() {
+        let id = ExtendedId::new(0x1234_5678).unwrap();
+        let mut frame = TestFrame::new_remote(id, 4).unwrap();
+        assert!(frame.data_mut().is_empty());
+
+        frame.set_data(&[1, 2]).unwrap();
+        assert!(frame.is_data_frame());
+        assert_eq!(frame.id(), id);
+        assert_eq!(frame.data(), [1, 2]);
+    }
+}
@@ -0,0 +1,251 @@
+//! Captured CAN traces.
+
+use core::iter::FusedIterator;
+
+use crate::{Frame, Id};
+
+/// Maximum length of the output of [`format_candump`]: an extended identifier, `#` and 8 bytes
+/// of data.
+pub const CANDUMP_MAX_LEN: usize = 8 + 1 + 2 * 8;
+
+/// Writes the `candump` representation of `frame` into `buf`, returning the number of bytes
+/// written.
+///
+/// This is the compact format of the Linux `can-utils` tools: the identifier in uppercase hex,
+/// with 3 digits for standard identifiers and 8 for extended ones, `#`, then the data in
+/// uppercase hex, or `R` for a remote frame. For example `123#DEADBEEF` or `1FFFFFFF#R`.
+///
+/// # Panics
+///
+/// Panics if `buf` is too short for the output. A buffer of [`CANDUMP_MAX_LEN`] bytes is always
+/// long enough.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_can::{format_candump, Frame, StandardId, CANDUMP_MAX_LEN};
+/// # use embedded_can::Id;
+/// # struct HalFrame(Id, Vec<u8>);
+/// # impl Frame for HalFrame {
+/// #     fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> { Some(Self(id.into(), data.to_vec())) }
+/// #     fn new_remote(_: impl Into<Id>, _: usize) -> Option<Self> { None }
+/// #     fn is_extended(&self) -> bool { matches!(self.0, Id::Extended(_)) }
+/// #     fn is_remote_frame(&self) -> bool { false }
+/// #     fn id(&self) -> Id { self.0 }
+/// #     fn dlc(&self) -> usize { self.1.len() }
+/// #     fn data(&self) -> &[u8] { &self.1 }
+/// # }
+/// let frame = HalFrame::new(StandardId::new(0x123).unwrap(), &[0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
+///
+/// let mut buf = [0; CANDUMP_MAX_LEN];
+/// let len = format_candump(&frame, &mut buf);
+/// assert_eq!(&buf[..len], b"123#DEADBEEF");
+/// ```
+pub fn format_candump(frame: &impl Frame, buf: &mut [u8]) -> usize {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    let (raw, digits) = match frame.id() {
+        Id::Standard(id) => (id.as_raw() as u32, 3),
+        Id::Extended(id) => (id.as_raw(), 8),
+    };
+    let data_len = if frame.is_remote_frame() {
+        1
+    } else {
+        2 * frame.data().len()
+    };
+    let len = digits + 1 + data_len;
+    assert!(
+        buf.len() >= len,
+        "buffer too short for the candump output, {} bytes needed, got {}",
+        len,
+        buf.len()
+    );
+
+    for (i, b) in buf[..digits].iter_mut().enumerate() {
+        *b = HEX[(raw >> (4 * (digits - 1 - i)) & 0xF) as usize];
+    }
+    buf[digits] = b'#';
+    let out = &mut buf[digits + 1..len];
+    if frame.is_remote_frame() {
+        out[0] = b'R';
+    } else {
+        for (pair, byte) in out.chunks_exact_mut(2).zip(frame.data()) {
+            pair[0] = HEX[(byte >> 4) as usize];
+            pair[1] = HEX[(byte & 0xF) as usize];
+        }
+    }
+    len
+}
+
+/// Iterator over the frames of a captured trace, as `(Id, &[u8])` tuples.
+///
+/// The trace is a sequence of records, each made of:
+///
+/// 1. the identifier, in the 4-byte encoding of [`Id::to_be_bytes`],
+/// 2. the data length, in 1 byte, from 0 to 8,
+/// 3. the data, of that length.
+///
+/// Records are packed without padding. Iteration stops at the first record that is truncated or
+/// invalid (bad identifier encoding or length over 8), since the following records can't be
+/// located. [`remainder`](Self::remainder) returns the bytes that were not parsed, so that an
+/// incomplete trace can be told apart from a complete one.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_can::{FrameIter, Id, StandardId};
+///
+/// let trace = [0x00, 0x00, 0x01, 0x23, 2, 0xAB, 0xCD];
+/// let mut frames = FrameIter::new(&trace);
+///
+/// let id = Id::Standard(StandardId::new(0x123).unwrap());
+/// assert_eq!(frames.next(), Some((id, &[0xAB, 0xCD][..])));
+/// assert_eq!(frames.next(), None);
+/// assert!(frames.remainder().is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameIter<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> FrameIter<'a> {
+    /// Maximum data length of a record.
+    const MAX_LEN: usize = 8;
+
+    /// Creates an iterator over the records of `buf`.
+    #[inline]
+    pub const fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    /// Returns the bytes not parsed yet.
+    ///
+    /// Once the iterator returned `None`, this is empty if the whole trace was valid, and starts
+    /// with the truncated or invalid record otherwise.
+    #[inline]
+    pub const fn remainder(&self) -> &'a [u8] {
+        self.buf
+    }
+}
+
+impl<'a> Iterator for FrameIter<'a> {
+    type Item = (Id, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.len() < 5 {
+            return None;
+        }
+        let (header, rest) = self.buf.split_at(5);
+        let id = Id::from_be_bytes([header[0], header[1], header[2], header[3]])?;
+        let len = header[4] as usize;
+        if len > Self::MAX_LEN || rest.len() < len {
+            return None;
+        }
+        let (data, rest) = rest.split_at(len);
+        self.buf = rest;
+        Some((id, data))
+    }
+}
+
+impl FusedIterator for FrameIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestFrame;
+    use crate::{ExtendedId, StandardId};
+
+    fn candump(frame: &TestFrame) -> ([u8; CANDUMP_MAX_LEN], usize) {
+        let mut buf = [0; CANDUMP_MAX_LEN];
+        let len = format_candump(frame, &mut buf);
+        (buf, len)
+    }
+
+    #[test]
+    fn candump_standard_data_frame() {
+        let frame = TestFrame::new(StandardId::new(0x123).unwrap(), &[0xDE, 0xAD, 0xBE, 0xEF]);
+        let (buf, len) = candump(&frame.unwrap());
+        assert_eq!(&buf[..len], b"123#DEADBEEF");
+
+        // Leading zeros are kept, and empty data frames have nothing after `#`.
+        let frame = TestFrame::new(StandardId::new(0x5).unwrap(), &[]);
+        let (buf, len) = candump(&frame.unwrap());
+        assert_eq!(&buf[..len], b"005#");
+    }
+
+    #[test]
+    fn candump_extended_remote_frame() {
+        let frame = TestFrame::new_remote(ExtendedId::MAX, 4);
+        let (buf, len) = candump(&frame.unwrap());
+        assert_eq!(&buf[..len], b"1FFFFFFF#R");
+
+        let frame = TestFrame::new_remote(ExtendedId::new(0x123).unwrap(), 0);
+        let (buf, len) = candump(&frame.unwrap());
+        assert_eq!(&buf[..len], b"00000123#R");
+    }
+
+    #[test]
+    fn candump_max_len() {
+        let frame = TestFrame::new(ExtendedId::MAX, &[0xFF; 8]).unwrap();
+        assert_eq!(candump(&frame).1, CANDUMP_MAX_LEN);
+    }
+
+    #[test]
+    #[should_panic(expected = "buffer too short for the candump output, 12 bytes needed, got 11")]
+    fn candump_buffer_too_short() {
+        let frame = TestFrame::new(StandardId::new(0x123).unwrap(), &[0xDE, 0xAD, 0xBE, 0xEF]);
+        format_candump(&frame.unwrap(), &mut [0; 11]);
+    }
+
+    const TRACE: [u8; 20] = [
+        // Standard 0x123, 2 bytes.
+        0x00, 0x00, 0x01, 0x23, 2, 0xAB, 0xCD, //
+        // Extended 0x1234567, no data.
+        0x81, 0x23, 0x45, 0x67, 0, //
+        // Standard 0x7FF, 8 bytes, truncated after 3.
+        0x00, 0x00, 0x07, 0xFF, 8, 1, 2, 3,
+    ];
+
+    #[test]
+    fn two_frames_and_truncated_third() {
+        let mut frames = FrameIter::new(&TRACE);
+
+        let std = Id::Standard(StandardId::new(0x123).unwrap());
+        let ext = Id::Extended(ExtendedId::new(0x123_4567).unwrap());
+        assert_eq!(frames.next(), Some((std, &[0xAB, 0xCD][..])));
+        assert_eq!(frames.next(), Some((ext, &[][..])));
+        assert_eq!(frames.next(), None);
+        assert_eq!(frames.remainder(), &TRACE[12..]);
+        // The iterator is fused.
+        assert_eq!(frames.next(), None);
+    }
+
+    #[test]
+    fn truncated_header() {
+        // The extended record, then a single byte of the next header.
+        let buf = [0x81, 0x23, 0x45, 0x67, 0, 0x00];
+        let mut frames = FrameIter::new(&buf);
+        assert!(frames.next().is_some());
+        assert_eq!(frames.next(), None);
+        assert_eq!(frames.remainder(), &[0x00]);
+    }
+
+    #[test]
+    fn invalid_records() {
+        // Bit 30 of the identifier is reserved.
+        let bad_id = [0x40, 0x00, 0x00, 0x00, 0];
+        assert_eq!(FrameIter::new(&bad_id).next(), None);
+
+        let bad_len = [0x00, 0x00, 0x00, 0x01, 9, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let mut frames = FrameIter::new(&bad_len);
+        assert_eq!(frames.next(), None);
+        assert_eq!(frames.remainder(), &bad_len);
+    }
+
+    #[test]
+    fn empty() {
+        let mut frames = FrameIter::new(&[]);
+        assert_eq!(frames.next(), None);
+        assert!(frames.remainder().is_empty());
+    }
+}
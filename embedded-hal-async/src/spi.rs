@@ -26,6 +26,9 @@ pub trait SpiDevice<Word: Copy + 'static = u8>: ErrorType {
     ///
     /// On bus errors the implementation should try to deassert CS.
     /// If an error occurs while deasserting CS the bus error should take priority as the return value.
+    ///
+    /// A transaction with an empty `operations` slice should do nothing: implementations should
+    /// return `Ok(())` without locking the bus, asserting CS or flushing.
     async fn transaction(
         &mut self,
         operations: &mut [Operation<'_, Word>],
@@ -0,0 +1,96 @@
+//! Compares the single operation fast path of `ExclusiveDevice` with the general transaction
+//! path.
+//!
+//! Run with `cargo bench --features bench --bench single_operation`. It is gated behind the
+//! `bench` feature, as `std::hint::black_box` needs Rust 1.66, newer than the MSRV. This is a
+//! plain timing loop, as the crate has no benchmark framework dependency: compare the results of
+//! several runs.
+
+use core::convert::Infallible;
+use std::hint::black_box;
+use std::time::Instant;
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::spi::{self, Operation, SpiBus, SpiDevice};
+use embedded_hal_bus::spi::ExclusiveDevice;
+
+const ITERATIONS: u32 = 10_000_000;
+
+/// Bus doing nothing, but opaque to the optimizer.
+struct Bus;
+
+impl spi::ErrorType for Bus {
+    type Error = Infallible;
+}
+
+impl SpiBus for Bus {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        black_box(words);
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        black_box(words);
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Infallible> {
+        black_box((read, write));
+        Ok(())
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        black_box(words);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+struct Cs;
+
+impl digital::ErrorType for Cs {
+    type Error = Infallible;
+}
+
+impl OutputPin for Cs {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        black_box(self);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        black_box(self);
+        Ok(())
+    }
+}
+
+/// Runs `f` `ITERATIONS` times, printing the average time per call.
+fn bench(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let ns = start.elapsed().as_nanos() as f64 / f64::from(ITERATIONS);
+    println!("{:<28} {:>8.2} ns/iter", name, ns);
+}
+
+fn main() {
+    let mut device = ExclusiveDevice::new_no_delay(Bus, Cs);
+    let mut buf = [0x55; 4];
+
+    bench("write", || device.write(black_box(&buf)).unwrap());
+    bench("transaction(Write)", || {
+        device
+            .transaction(&mut [Operation::Write(black_box(&buf))])
+            .unwrap()
+    });
+    bench("read", || device.read(black_box(&mut buf)).unwrap());
+    bench("transaction(Read)", || {
+        device
+            .transaction(&mut [Operation::Read(black_box(&mut buf))])
+            .unwrap()
+    });
+}
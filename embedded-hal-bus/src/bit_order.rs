@@ -0,0 +1,18 @@
+//! Bit order of bit-banged and configurable buses.
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// Order in which the bits of each word are shifted on the bus.
+///
+/// Re-exported as [`spi::BitOrder`](crate::spi::BitOrder) and
+/// [`i2c::BitOrder`](crate::i2c::BitOrder).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum BitOrder {
+    /// Most significant bit first, as used by most SPI devices and required by the I2C
+    /// specification.
+    MsbFirst,
+    /// Least significant bit first, for non-standard devices.
+    LsbFirst,
+}
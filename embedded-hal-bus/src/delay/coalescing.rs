@@ -0,0 +1,94 @@
+use embedded_hal::delay::DelayUs;
+
+/// [`DelayUs`] wrapper that accumulates short delays and forwards them in larger batches.
+///
+/// Requested delays are added up, and only forwarded to the inner delay once the total reaches
+/// `threshold_us`, or when [`flush`](Self::flush) is called. This avoids the overhead of calling
+/// the inner delay for many tiny delays.
+///
+/// **Warning**: this changes timing semantics. Delays shorter than the threshold return
+/// immediately, and the pending time is waited for later, after whatever comes next. For example,
+/// when used as the delay of an [`SpiDevice`](embedded_hal::spi::SpiDevice), an
+/// `Operation::DelayUs` may no longer separate the operations around it. Only use it when the
+/// total delay matters, but not where exactly it happens.
+///
+/// Pending time is not waited for when the wrapper is dropped, call [`flush`](Self::flush) first
+/// if needed.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::delay::DelayUs;
+/// use embedded_hal_bus::delay::Coalescing;
+/// # #[derive(Default)]
+/// # struct Delay { calls: Vec<u32> }
+/// # impl DelayUs for Delay {
+/// #     fn delay_us(&mut self, us: u32) { self.calls.push(us); }
+/// # }
+/// let mut delay = Coalescing::new(Delay::default(), 4);
+///
+/// for _ in 0..10 {
+///     delay.delay_us(1);
+/// }
+/// delay.flush();
+///
+/// assert_eq!(delay.inner().calls, [4, 4, 2]);
+/// ```
+pub struct Coalescing<D> {
+    delay: D,
+    threshold_us: u32,
+    pending_us: u32,
+}
+
+impl<D> Coalescing<D> {
+    /// Create a new `Coalescing` delay, forwarding delays once they add up to `threshold_us`.
+    #[inline]
+    pub fn new(delay: D, threshold_us: u32) -> Self {
+        Self {
+            delay,
+            threshold_us,
+            pending_us: 0,
+        }
+    }
+
+    /// Returns the accumulated time not waited for yet, in microseconds.
+    #[inline]
+    pub fn pending_us(&self) -> u32 {
+        self.pending_us
+    }
+
+    /// Returns a reference to the inner delay.
+    #[inline]
+    pub fn inner(&self) -> &D {
+        &self.delay
+    }
+
+    /// Destroys the wrapper, returning the inner delay.
+    ///
+    /// Pending time is discarded, call [`flush`](Self::flush) first if needed.
+    #[inline]
+    pub fn into_inner(self) -> D {
+        self.delay
+    }
+}
+
+impl<D: DelayUs> Coalescing<D> {
+    /// Wait for the pending time, if any.
+    #[inline]
+    pub fn flush(&mut self) {
+        if self.pending_us > 0 {
+            self.delay.delay_us(self.pending_us);
+            self.pending_us = 0;
+        }
+    }
+}
+
+impl<D: DelayUs> DelayUs for Coalescing<D> {
+    #[inline]
+    fn delay_us(&mut self, us: u32) {
+        self.pending_us = self.pending_us.saturating_add(us);
+        if self.pending_us >= self.threshold_us {
+            self.flush();
+        }
+    }
+}
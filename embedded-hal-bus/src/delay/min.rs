@@ -0,0 +1,80 @@
+use embedded_hal::delay::DelayUs;
+
+/// [`DelayUs`] wrapper rounding short delays up to a minimum.
+///
+/// Some delay implementations have a coarse resolution, and may round short delays down to
+/// nothing. For example, a delay based on a millisecond tick may return immediately for
+/// `delay_us(10)`. Wrapping it in a `MinDelay` with a `min_us` of its resolution makes sure such
+/// delays wait at least one tick instead, at the cost of waiting longer than requested.
+///
+/// Delays of 0 are not rounded up, since no wait was requested.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::delay::DelayUs;
+/// use embedded_hal_bus::delay::MinDelay;
+/// # #[derive(Default)]
+/// # struct Delay { calls: Vec<u32> }
+/// # impl DelayUs for Delay {
+/// #     fn delay_us(&mut self, us: u32) { self.calls.push(us); }
+/// # }
+/// let mut delay = MinDelay::new(Delay::default(), 1000);
+///
+/// delay.delay_us(1);
+/// delay.delay_us(2500);
+/// delay.delay_us(0);
+///
+/// assert_eq!(delay.inner().calls, [1000, 2500]);
+/// ```
+pub struct MinDelay<D> {
+    delay: D,
+    min_us: u32,
+}
+
+impl<D> MinDelay<D> {
+    /// Create a new `MinDelay`, rounding delays shorter than `min_us` microseconds up to it.
+    #[inline]
+    pub fn new(delay: D, min_us: u32) -> Self {
+        Self { delay, min_us }
+    }
+
+    /// Returns the minimum delay, in microseconds.
+    #[inline]
+    pub fn min_us(&self) -> u32 {
+        self.min_us
+    }
+
+    /// Returns a reference to the inner delay.
+    #[inline]
+    pub fn inner(&self) -> &D {
+        &self.delay
+    }
+
+    /// Destroys the wrapper, returning the inner delay.
+    #[inline]
+    pub fn into_inner(self) -> D {
+        self.delay
+    }
+}
+
+impl<D: DelayUs> DelayUs for MinDelay<D> {
+    #[inline]
+    fn delay_us(&mut self, us: u32) {
+        if us > 0 {
+            self.delay.delay_us(us.max(self.min_us));
+        }
+    }
+
+    #[inline]
+    fn delay_ms(&mut self, ms: u32) {
+        if ms == 0 {
+            return;
+        }
+        if u64::from(ms) * 1000 < u64::from(self.min_us) {
+            self.delay.delay_us(self.min_us);
+        } else {
+            self.delay.delay_ms(ms);
+        }
+    }
+}
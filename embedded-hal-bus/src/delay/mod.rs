@@ -0,0 +1,12 @@
+//! Delay adapters.
+
+mod coalescing;
+pub use coalescing::*;
+mod min;
+pub use min::*;
+mod paced;
+pub use paced::*;
+#[cfg(feature = "async")]
+mod ticker;
+#[cfg(feature = "async")]
+pub use ticker::*;
@@ -0,0 +1,130 @@
+use embedded_hal::delay::DelayUs;
+
+/// Delay pacing a loop at a fixed period, without drift.
+///
+/// Calling [`tick`](Self::tick) at the end of each iteration of a loop waits until the next
+/// scheduled instant, which is exactly one period after the previous one. The time spent in the
+/// loop body is thus compensated for, and doesn't accumulate over the iterations as it would with
+/// a plain fixed delay.
+///
+/// Time is read from `clock`, a closure returning a monotonic timestamp in microseconds that
+/// wraps around at `u32::MAX`. The period must be less than `i32::MAX` microseconds (about 35
+/// minutes).
+///
+/// If an iteration takes longer than the period, `tick` returns immediately and reports how late
+/// it was. The schedule then restarts from the current time, instead of running the next
+/// iterations back to back to catch up.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::delay::DelayUs;
+/// use embedded_hal_bus::delay::PacedDelay;
+/// # use core::cell::Cell;
+/// # struct Delay<'a>(&'a Cell<u32>);
+/// # impl DelayUs for Delay<'_> {
+/// #     fn delay_us(&mut self, us: u32) { self.0.set(self.0.get() + us); }
+/// # }
+/// // A fake clock, advanced by the delay and by the loop body.
+/// let now = Cell::new(0);
+/// let mut delay = PacedDelay::from_hz(Delay(&now), || now.get(), 100);
+///
+/// let mut starts = Vec::new();
+/// for work_us in [1_000, 3_000, 7_000, 2_000] {
+///     starts.push(now.get());
+///     now.set(now.get() + work_us);
+///     assert_eq!(delay.tick(), 0);
+/// }
+/// assert_eq!(starts, [0, 10_000, 20_000, 30_000]);
+///
+/// // The loop body took 15 ms, 5 ms longer than the period.
+/// now.set(now.get() + 15_000);
+/// assert_eq!(delay.tick(), 5_000);
+/// ```
+pub struct PacedDelay<D, C> {
+    delay: D,
+    clock: C,
+    period_us: u32,
+    next: u32,
+}
+
+impl<D, C> PacedDelay<D, C>
+where
+    C: FnMut() -> u32,
+{
+    /// Create a new `PacedDelay`, with a period of `period_us` microseconds.
+    ///
+    /// The first period starts now.
+    #[inline]
+    pub fn new(delay: D, mut clock: C, period_us: u32) -> Self {
+        let next = clock().wrapping_add(period_us);
+        Self {
+            delay,
+            clock,
+            period_us,
+            next,
+        }
+    }
+
+    /// Create a new `PacedDelay`, running `rate_hz` iterations per second.
+    ///
+    /// The period is rounded down to a whole number of microseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate_hz` is 0.
+    #[inline]
+    pub fn from_hz(delay: D, clock: C, rate_hz: u32) -> Self {
+        Self::new(delay, clock, 1_000_000 / rate_hz)
+    }
+
+    /// Returns the period, in microseconds.
+    #[inline]
+    pub fn period_us(&self) -> u32 {
+        self.period_us
+    }
+
+    /// Restart the schedule, with the next period starting now.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.next = (self.clock)().wrapping_add(self.period_us);
+    }
+
+    /// Returns a reference to the inner delay.
+    #[inline]
+    pub fn inner(&self) -> &D {
+        &self.delay
+    }
+
+    /// Destroys the wrapper, returning the inner delay.
+    #[inline]
+    pub fn into_inner(self) -> D {
+        self.delay
+    }
+}
+
+impl<D, C> PacedDelay<D, C>
+where
+    D: DelayUs,
+    C: FnMut() -> u32,
+{
+    /// Wait until the next scheduled instant.
+    ///
+    /// Returns by how many microseconds the instant was missed, or 0 if it wasn't. When it was
+    /// missed, no delay is performed and the schedule restarts from now.
+    #[inline]
+    pub fn tick(&mut self) -> u32 {
+        let now = (self.clock)();
+        let remaining = self.next.wrapping_sub(now) as i32;
+        if remaining >= 0 {
+            if remaining > 0 {
+                self.delay.delay_us(remaining as u32);
+            }
+            self.next = self.next.wrapping_add(self.period_us);
+            0
+        } else {
+            self.next = now.wrapping_add(self.period_us);
+            remaining.unsigned_abs()
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use embedded_hal_async::delay::DelayUs;
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// What a [`Ticker`] does with the ticks missed while the task was busy.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum MissedTicks {
+    /// Skip the missed ticks: the late tick completes immediately, and the next one is the first
+    /// tick of the schedule still in the future. This is the default.
+    Skip,
+    /// Catch up: all the missed ticks complete immediately, back to back, until the schedule is
+    /// caught up.
+    Burst,
+}
+
+/// Async ticker for loops running at a fixed rate, without drift.
+///
+/// Awaiting [`next`](Self::next) at the end of each iteration of a loop waits until the next
+/// tick, exactly one period after the previous one. Like for the blocking
+/// [`PacedDelay`](super::PacedDelay), the time spent in the loop body is compensated for, and
+/// doesn't accumulate over the iterations as it would with a plain fixed delay.
+///
+/// Time is read from `clock`, a closure returning a monotonic timestamp in microseconds that
+/// wraps around at `u32::MAX`. The period must be less than `i32::MAX` microseconds (about 35
+/// minutes).
+///
+/// If an iteration takes longer than the period, the ticks it overran are missed. Unlike
+/// `PacedDelay`, whose schedule restarts from the late tick, the ticker keeps to its schedule:
+/// missed ticks are either skipped or run back to back to catch up, as set with
+/// [`set_missed_ticks`](Self::set_missed_ticks).
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::delay::Ticker;
+/// # use core::cell::Cell;
+/// # use embedded_hal_async::delay;
+/// # struct Delay<'a>(&'a Cell<u32>);
+/// # impl delay::DelayUs for Delay<'_> {
+/// #     async fn delay_us(&mut self, us: u32) { self.0.set(self.0.get() + us); }
+/// #     async fn delay_ms(&mut self, ms: u32) { self.0.set(self.0.get() + ms * 1000); }
+/// # }
+/// // A fake clock, advanced by the delay and by the loop body.
+/// let now = Cell::new(0);
+/// let mut ticker = Ticker::new(Delay(&now), || now.get(), 10_000);
+///
+/// let mut starts = Vec::new();
+/// futures::executor::block_on(async {
+///     for work_us in [1_000, 3_000, 7_000, 2_000] {
+///         starts.push(now.get());
+///         now.set(now.get() + work_us);
+///         ticker.next().await;
+///     }
+/// });
+/// assert_eq!(starts, [0, 10_000, 20_000, 30_000]);
+/// ```
+pub struct Ticker<D, C> {
+    delay: D,
+    clock: C,
+    period_us: u32,
+    next: u32,
+    missed_ticks: MissedTicks,
+}
+
+impl<D, C> Ticker<D, C>
+where
+    C: FnMut() -> u32,
+{
+    /// Create a new `Ticker`, ticking every `period_us` microseconds, and skipping missed ticks.
+    ///
+    /// The first period starts now.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period_us` is 0.
+    #[inline]
+    pub fn new(delay: D, mut clock: C, period_us: u32) -> Self {
+        assert!(period_us > 0, "the period must be non-zero");
+        let next = clock().wrapping_add(period_us);
+        Self {
+            delay,
+            clock,
+            period_us,
+            next,
+            missed_ticks: MissedTicks::Skip,
+        }
+    }
+
+    /// Returns the period, in microseconds.
+    #[inline]
+    pub fn period_us(&self) -> u32 {
+        self.period_us
+    }
+
+    /// Returns what is done with missed ticks.
+    #[inline]
+    pub fn missed_ticks(&self) -> MissedTicks {
+        self.missed_ticks
+    }
+
+    /// Sets what is done with missed ticks.
+    #[inline]
+    pub fn set_missed_ticks(&mut self, missed_ticks: MissedTicks) {
+        self.missed_ticks = missed_ticks;
+    }
+
+    /// Restart the schedule, with the next period starting now.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.next = (self.clock)().wrapping_add(self.period_us);
+    }
+
+    /// Returns a reference to the inner delay.
+    #[inline]
+    pub fn inner(&self) -> &D {
+        &self.delay
+    }
+
+    /// Destroys the ticker, returning the inner delay.
+    #[inline]
+    pub fn into_inner(self) -> D {
+        self.delay
+    }
+}
+
+impl<D, C> Ticker<D, C>
+where
+    D: DelayUs,
+    C: FnMut() -> u32,
+{
+    /// Wait until the next tick.
+    ///
+    /// Returns by how many microseconds the tick was missed, or 0 if it wasn't. When it was
+    /// missed, no delay is performed, and the next tick depends on [`MissedTicks`].
+    #[inline]
+    pub async fn next(&mut self) -> u32 {
+        let now = (self.clock)();
+        let remaining = self.next.wrapping_sub(now) as i32;
+        if remaining >= 0 {
+            if remaining > 0 {
+                self.delay.delay_us(remaining as u32).await;
+            }
+            self.next = self.next.wrapping_add(self.period_us);
+            return 0;
+        }
+
+        let late_us = remaining.unsigned_abs();
+        let ticks = match self.missed_ticks {
+            // All the ticks up to now are missed, the first one still in the future is next.
+            MissedTicks::Skip => late_us / self.period_us + 1,
+            MissedTicks::Burst => 1,
+        };
+        self.next = self.next.wrapping_add(ticks.wrapping_mul(self.period_us));
+        late_us
+    }
+}
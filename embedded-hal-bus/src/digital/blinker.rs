@@ -0,0 +1,96 @@
+use core::convert::Infallible;
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::OutputPin;
+
+/// Blocking LED blinker.
+///
+/// Drives an [`OutputPin`] high for the "on" time and low for the "off" time, using a delay to
+/// wait. For an active-low LED, wrap its pin in [`Inverted`](super::Inverted).
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::digital::{Blinker, Inverted};
+/// # use core::cell::RefCell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{delay, digital};
+/// # #[derive(Debug, PartialEq)]
+/// # enum Event { On, Off, Wait(u32) }
+/// # struct Led<'a>(&'a RefCell<Vec<Event>>);
+/// # impl digital::ErrorType for Led<'_> { type Error = Infallible; }
+/// # impl digital::OutputPin for Led<'_> {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(self.0.borrow_mut().push(Event::Off)) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(self.0.borrow_mut().push(Event::On)) }
+/// # }
+/// # struct Delay<'a>(&'a RefCell<Vec<Event>>);
+/// # impl delay::DelayUs for Delay<'_> {
+/// #     fn delay_us(&mut self, us: u32) { self.0.borrow_mut().push(Event::Wait(us)); }
+/// #     fn delay_ms(&mut self, ms: u32) { self.0.borrow_mut().push(Event::Wait(ms * 1000)); }
+/// # }
+/// let log = RefCell::new(Vec::new());
+/// let mut blinker = Blinker::new(Led(&log), Delay(&log));
+///
+/// blinker.blink(100, 400, 2)?;
+///
+/// # use Event::*;
+/// assert_eq!(
+///     *log.borrow(),
+///     [On, Wait(100_000), Off, Wait(400_000), On, Wait(100_000), Off, Wait(400_000)]
+/// );
+///
+/// // An active-low LED is driven low to turn it on.
+/// log.borrow_mut().clear();
+/// let mut blinker = Blinker::new(Inverted::new(Led(&log)), Delay(&log));
+///
+/// blinker.blink(100, 400, 1)?;
+///
+/// assert_eq!(*log.borrow(), [Off, Wait(100_000), On, Wait(400_000)]);
+/// # Ok::<(), Infallible>(())
+/// ```
+pub struct Blinker<P, D> {
+    pin: P,
+    delay: D,
+}
+
+impl<P, D> Blinker<P, D> {
+    /// Create a new `Blinker`.
+    #[inline]
+    pub fn new(pin: P, delay: D) -> Self {
+        Self { pin, delay }
+    }
+
+    /// Destroys the blinker, returning the pin and the delay.
+    #[inline]
+    pub fn into_inner(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+}
+
+impl<P: OutputPin, D: DelayUs> Blinker<P, D> {
+    /// Blink `count` times, staying on for `on_ms` and then off for `off_ms` milliseconds.
+    ///
+    /// The pin is left off. Returns early if setting the pin fails.
+    #[inline]
+    pub fn blink(&mut self, on_ms: u32, off_ms: u32, count: usize) -> Result<(), P::Error> {
+        for _ in 0..count {
+            self.pin.set_high()?;
+            self.delay.delay_ms(on_ms);
+            self.pin.set_low()?;
+            self.delay.delay_ms(off_ms);
+        }
+        Ok(())
+    }
+
+    /// Blink forever, with a period of `period_ms` milliseconds, half of it on and half off.
+    ///
+    /// This only returns if setting the pin fails.
+    #[inline]
+    pub fn blink_forever(&mut self, period_ms: u32) -> Result<Infallible, P::Error> {
+        let on_ms = period_ms / 2;
+        let off_ms = period_ms - on_ms;
+        loop {
+            self.blink(on_ms, off_ms, 1)?;
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::InputPin;
+
+/// Input pin wrapper debouncing its level.
+///
+/// Each [`read`](Self::read) samples the inner pin every `interval_us` microseconds until it
+/// reads the same level `samples` times in a row, and returns that level. This filters out the
+/// bounces of a mechanical switch, at the cost of blocking for at least `samples - 1` intervals
+/// per read. A signal that keeps changing faster than that blocks the read until it settles.
+///
+/// As reading waits with a delay, this doesn't implement [`InputPin`], whose methods take `&self`.
+/// See [`AsyncDebounced`](super::AsyncDebounced) for an async version.
+///
+/// If a sample fails, its error is returned right away, and no more samples are taken.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::digital::Debounced;
+/// # use core::cell::Cell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{delay, digital};
+/// # struct Pin<'a>(&'a [bool], Cell<usize>);
+/// # impl digital::ErrorType for Pin<'_> { type Error = Infallible; }
+/// # impl digital::InputPin for Pin<'_> {
+/// #     fn is_high(&self) -> Result<bool, Infallible> { let i = self.1.get(); self.1.set(i + 1); Ok(self.0[i]) }
+/// #     fn is_low(&self) -> Result<bool, Infallible> { self.is_high().map(|high| !high) }
+/// # }
+/// # struct Delay;
+/// # impl delay::DelayUs for Delay { fn delay_us(&mut self, _: u32) {} }
+/// // A switch bouncing twice before settling high.
+/// # let pin = Pin(&[true, false, true, true, true], Cell::new(0));
+/// let mut switch = Debounced::new(pin, Delay, 1000, 3);
+/// assert!(switch.read()?);
+/// # Ok::<(), Infallible>(())
+/// ```
+pub struct Debounced<P, D> {
+    pin: P,
+    delay: D,
+    interval_us: u32,
+    samples: u32,
+}
+
+impl<P, D> Debounced<P, D> {
+    /// Create a new `Debounced` pin, sampling `pin` every `interval_us` microseconds until it
+    /// reads the same level `samples` times in a row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is 0.
+    #[inline]
+    pub fn new(pin: P, delay: D, interval_us: u32, samples: u32) -> Self {
+        assert!(samples > 0, "the number of samples must not be 0");
+        Self {
+            pin,
+            delay,
+            interval_us,
+            samples,
+        }
+    }
+
+    /// Returns a reference to the inner pin.
+    #[inline]
+    pub fn inner(&self) -> &P {
+        &self.pin
+    }
+
+    /// Destroys the wrapper, returning the pin and the delay.
+    #[inline]
+    pub fn into_inner(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+}
+
+impl<P: InputPin, D: DelayUs> Debounced<P, D> {
+    /// Returns the debounced level of the pin, `true` if it is high.
+    #[inline]
+    pub fn read(&mut self) -> Result<bool, P::Error> {
+        let mut debouncer = Debouncer::new(self.samples);
+        loop {
+            if let Some(high) = debouncer.push(self.pin.is_high()?) {
+                return Ok(high);
+            }
+            self.delay.delay_us(self.interval_us);
+        }
+    }
+}
+
+/// Debouncing logic shared by [`Debounced`] and its async version, counting identical samples.
+pub(super) struct Debouncer {
+    samples: u32,
+    last: bool,
+    count: u32,
+}
+
+impl Debouncer {
+    #[inline]
+    pub(super) fn new(samples: u32) -> Self {
+        Self {
+            samples,
+            last: false,
+            count: 0,
+        }
+    }
+
+    /// Adds a sample, returning the level once it was sampled enough times in a row.
+    #[inline]
+    pub(super) fn push(&mut self, high: bool) -> Option<bool> {
+        if self.count > 0 && high == self.last {
+            self.count += 1;
+        } else {
+            self.last = high;
+            self.count = 1;
+        }
+        if self.count >= self.samples {
+            Some(high)
+        } else {
+            None
+        }
+    }
+}
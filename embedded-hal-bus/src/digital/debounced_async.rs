@@ -0,0 +1,137 @@
+use embedded_hal::digital::{ErrorType, InputPin};
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::digital::Wait;
+
+use super::debounced::Debouncer;
+
+/// Async version of [`Debounced`](super::Debounced), awaiting the delay between samples.
+///
+/// [`read`](Self::read) samples the inner pin every `interval_us` microseconds until it reads the
+/// same level `samples` times in a row, and returns that level.
+///
+/// It also implements [`Wait`] by polling the debounced level, waiting `interval_us` microseconds
+/// between reads. As with [`PollingWait`](super::PollingWait), an edge is only seen once the pin
+/// has settled on both sides of it, so bounces don't resolve
+/// [`wait_for_rising_edge`](Wait::wait_for_rising_edge) early, but short pulses are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_async::digital::Wait;
+/// use embedded_hal_bus::digital::AsyncDebounced;
+/// # use core::cell::Cell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::digital;
+/// # use embedded_hal_async::delay;
+/// # struct Pin<'a>(&'a [bool], Cell<usize>);
+/// # impl digital::ErrorType for Pin<'_> { type Error = Infallible; }
+/// # impl digital::InputPin for Pin<'_> {
+/// #     fn is_high(&self) -> Result<bool, Infallible> { let i = self.1.get(); self.1.set(i + 1); Ok(self.0[i]) }
+/// #     fn is_low(&self) -> Result<bool, Infallible> { self.is_high().map(|high| !high) }
+/// # }
+/// # struct Delay;
+/// # impl delay::DelayUs for Delay {
+/// #     async fn delay_us(&mut self, _: u32) {}
+/// #     async fn delay_ms(&mut self, _: u32) {}
+/// # }
+/// // A button released, then pressed with a bounce.
+/// # let pin = Pin(&[false, false, true, false, true, true], Cell::new(0));
+/// let mut button = AsyncDebounced::new(pin, Delay, 1000, 2);
+///
+/// futures::executor::block_on(button.wait_for_rising_edge())?;
+/// # Ok::<(), Infallible>(())
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub struct AsyncDebounced<P, D> {
+    pin: P,
+    delay: D,
+    interval_us: u32,
+    samples: u32,
+}
+
+impl<P, D> AsyncDebounced<P, D> {
+    /// Create a new `AsyncDebounced` pin, sampling `pin` every `interval_us` microseconds until
+    /// it reads the same level `samples` times in a row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is 0.
+    #[inline]
+    pub fn new(pin: P, delay: D, interval_us: u32, samples: u32) -> Self {
+        assert!(samples > 0, "the number of samples must not be 0");
+        Self {
+            pin,
+            delay,
+            interval_us,
+            samples,
+        }
+    }
+
+    /// Returns a reference to the inner pin.
+    #[inline]
+    pub fn inner(&self) -> &P {
+        &self.pin
+    }
+
+    /// Destroys the wrapper, returning the pin and the delay.
+    #[inline]
+    pub fn into_inner(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+}
+
+impl<P: InputPin, D: DelayUs> AsyncDebounced<P, D> {
+    /// Returns the debounced level of the pin, `true` if it is high.
+    #[inline]
+    pub async fn read(&mut self) -> Result<bool, P::Error> {
+        let mut debouncer = Debouncer::new(self.samples);
+        loop {
+            if let Some(high) = debouncer.push(self.pin.is_high()?) {
+                return Ok(high);
+            }
+            self.delay.delay_us(self.interval_us).await;
+        }
+    }
+
+    /// Reads the debounced level until it is `high`.
+    async fn wait_for_level(&mut self, high: bool) -> Result<(), P::Error> {
+        while self.read().await? != high {
+            self.delay.delay_us(self.interval_us).await;
+        }
+        Ok(())
+    }
+}
+
+impl<P: ErrorType, D> ErrorType for AsyncDebounced<P, D> {
+    type Error = P::Error;
+}
+
+impl<P: InputPin, D: DelayUs> Wait for AsyncDebounced<P, D> {
+    #[inline]
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(true).await
+    }
+
+    #[inline]
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(false).await
+    }
+
+    #[inline]
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(false).await?;
+        self.wait_for_level(true).await
+    }
+
+    #[inline]
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(true).await?;
+        self.wait_for_level(false).await
+    }
+
+    #[inline]
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        let high = self.read().await?;
+        self.wait_for_level(!high).await
+    }
+}
@@ -0,0 +1,85 @@
+use embedded_hal::digital::{ErrorType, OutputPin, PinState, StatefulOutputPin};
+
+use crate::defmt;
+
+/// [`OutputPin`] wrapper that logs level changes with `defmt`.
+///
+/// Every time the pin is driven to a level different from the last one, a
+/// `"{name}: high"` or `"{name}: low"` message is logged at `info` level. Setting
+/// the pin to the level it already has is forwarded to the inner pin, but not logged.
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt-03")))]
+pub struct DefmtPin<P> {
+    pin: P,
+    name: &'static str,
+    state: Option<PinState>,
+}
+
+impl<P> DefmtPin<P> {
+    /// Create a new `DefmtPin`, identified by `name` in the logs.
+    ///
+    /// The level of the pin is considered unknown until it's first set, so the first
+    /// `set_high`/`set_low` call is always logged.
+    #[inline]
+    pub fn new(pin: P, name: &'static str) -> Self {
+        Self {
+            pin,
+            name,
+            state: None,
+        }
+    }
+
+    /// Returns the inner pin.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: OutputPin> DefmtPin<P> {
+    fn set(&mut self, state: PinState) -> Result<(), P::Error> {
+        self.pin.set_state(state)?;
+
+        if self.state != Some(state) {
+            match state {
+                PinState::High => defmt::info!("{=str}: high", self.name),
+                PinState::Low => defmt::info!("{=str}: low", self.name),
+            }
+            self.state = Some(state);
+        }
+
+        Ok(())
+    }
+}
+
+impl<P: ErrorType> ErrorType for DefmtPin<P> {
+    type Error = P::Error;
+}
+
+impl<P: OutputPin> OutputPin for DefmtPin<P> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set(PinState::Low)
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set(PinState::High)
+    }
+
+    #[inline]
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        self.set(state)
+    }
+}
+
+impl<P: StatefulOutputPin> StatefulOutputPin for DefmtPin<P> {
+    #[inline]
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        self.pin.is_set_high()
+    }
+
+    #[inline]
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        self.pin.is_set_low()
+    }
+}
@@ -0,0 +1,89 @@
+use embedded_hal::digital::InputPin;
+
+/// Group of [`InputPin`]s read as a single packed word.
+///
+/// Bit `i` of the word is the level of `pins[i]`, 1 meaning high. This is handy for reading a
+/// DIP switch or a parallel data bus. Up to 32 pins are supported.
+///
+/// The pins are read one after the other, so the samples are *not* simultaneous: if the inputs
+/// change during a read, the word may mix old and new levels. [`read_sampled`](Self::read_sampled)
+/// keeps this window as short as the pins allow.
+///
+/// Pins of different types can be grouped by using trait objects, for example
+/// `[&dyn InputPin<Error = E>; N]`.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::digital::PinGroupIn;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::digital;
+/// # struct Pin(bool);
+/// # impl digital::ErrorType for Pin { type Error = Infallible; }
+/// # impl digital::InputPin for Pin {
+/// #     fn is_high(&self) -> Result<bool, Infallible> { Ok(self.0) }
+/// #     fn is_low(&self) -> Result<bool, Infallible> { Ok(!self.0) }
+/// # }
+/// let mut switches = PinGroupIn::new([Pin(true), Pin(false), Pin(true), Pin(true)]);
+///
+/// assert_eq!(switches.read().unwrap(), 0b1101);
+/// assert_eq!(switches.read_sampled().unwrap(), 0b1101);
+/// ```
+pub struct PinGroupIn<P, const N: usize> {
+    pins: [P; N],
+}
+
+impl<P, const N: usize> PinGroupIn<P, N> {
+    /// Create a new `PinGroupIn`, `pins[i]` being read into bit `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is greater than 32.
+    #[inline]
+    pub fn new(pins: [P; N]) -> Self {
+        assert!(N <= 32, "PinGroupIn supports at most 32 pins");
+        Self { pins }
+    }
+
+    /// Returns a reference to the pins.
+    #[inline]
+    pub fn pins(&self) -> &[P; N] {
+        &self.pins
+    }
+
+    /// Returns the pins.
+    #[inline]
+    pub fn into_inner(self) -> [P; N] {
+        self.pins
+    }
+}
+
+impl<P: InputPin, const N: usize> PinGroupIn<P, N> {
+    /// Read each pin in turn, packing its level into the word as it goes.
+    #[inline]
+    pub fn read(&mut self) -> Result<u32, P::Error> {
+        let mut word = 0;
+        for (i, pin) in self.pins.iter().enumerate() {
+            if pin.is_high()? {
+                word |= 1 << i;
+            }
+        }
+        Ok(word)
+    }
+
+    /// Read all pins first, then pack their levels into the word.
+    ///
+    /// Nothing but the pin reads happens between the first and the last sample, which keeps the
+    /// skew between them as small as possible.
+    #[inline]
+    pub fn read_sampled(&mut self) -> Result<u32, P::Error> {
+        let mut levels = [false; N];
+        for (level, pin) in levels.iter_mut().zip(&self.pins) {
+            *level = pin.is_high()?;
+        }
+        Ok(levels
+            .iter()
+            .enumerate()
+            .fold(0, |word, (i, &high)| word | (high as u32) << i))
+    }
+}
@@ -0,0 +1,140 @@
+use embedded_hal::digital::{
+    ErrorType, InputPin, OutputPin, PinState, StatefulOutputPin, ToggleableOutputPin,
+};
+
+/// Pin wrapper inverting its logic level.
+///
+/// Setting the wrapper high drives the inner pin low and vice versa, and reading it returns the
+/// opposite of the inner pin's level. This allows using active-low pins, such as an LED wired
+/// between the supply and the pin, with code written for active-high ones.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::digital::{OutputPin, StatefulOutputPin};
+/// use embedded_hal_bus::digital::Inverted;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::digital;
+/// # struct Pin(bool);
+/// # impl digital::ErrorType for Pin { type Error = Infallible; }
+/// # impl OutputPin for Pin {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(self.0 = false) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(self.0 = true) }
+/// # }
+/// # impl StatefulOutputPin for Pin {
+/// #     fn is_set_high(&self) -> Result<bool, Infallible> { Ok(self.0) }
+/// #     fn is_set_low(&self) -> Result<bool, Infallible> { Ok(!self.0) }
+/// # }
+/// let mut led = Inverted::new(Pin(true));
+///
+/// led.set_high()?;
+/// assert!(led.is_set_high()?);
+/// assert!(led.inner().is_set_low()?);
+/// # Ok::<(), Infallible>(())
+/// ```
+pub struct Inverted<P> {
+    pin: P,
+}
+
+impl<P> Inverted<P> {
+    /// Create a new `Inverted` pin.
+    #[inline]
+    pub fn new(pin: P) -> Self {
+        Self { pin }
+    }
+
+    /// Returns a reference to the inner pin.
+    #[inline]
+    pub fn inner(&self) -> &P {
+        &self.pin
+    }
+
+    /// Returns the inner pin.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: ErrorType> ErrorType for Inverted<P> {
+    type Error = P::Error;
+}
+
+impl<P: OutputPin> OutputPin for Inverted<P> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_high()
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_low()
+    }
+
+    #[inline]
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        self.pin.set_state(!state)
+    }
+}
+
+impl<P: StatefulOutputPin> StatefulOutputPin for Inverted<P> {
+    #[inline]
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        self.pin.is_set_low()
+    }
+
+    #[inline]
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        self.pin.is_set_high()
+    }
+}
+
+impl<P: ToggleableOutputPin> ToggleableOutputPin for Inverted<P> {
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.pin.toggle()
+    }
+}
+
+impl<P: InputPin> InputPin for Inverted<P> {
+    #[inline]
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        self.pin.is_low()
+    }
+
+    #[inline]
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.pin.is_high()
+    }
+}
+
+/// Waits for the inverted level of the inner pin: waiting for the wrapper to go high waits for the
+/// inner pin to go low, and a rising edge of the wrapper is a falling edge of the inner pin.
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<P: embedded_hal_async::digital::Wait> embedded_hal_async::digital::Wait for Inverted<P> {
+    #[inline]
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.pin.wait_for_low().await
+    }
+
+    #[inline]
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.pin.wait_for_high().await
+    }
+
+    #[inline]
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.pin.wait_for_falling_edge().await
+    }
+
+    #[inline]
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.pin.wait_for_rising_edge().await
+    }
+
+    #[inline]
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.pin.wait_for_any_edge().await
+    }
+}
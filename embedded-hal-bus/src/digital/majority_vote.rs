@@ -0,0 +1,81 @@
+use embedded_hal::digital::{ErrorType, InputPin};
+
+/// Input pin wrapper filtering noise by majority vote.
+///
+/// Each read samples the inner pin `N` times in a row, and returns the level read most often.
+/// Unlike debouncing, this doesn't wait between samples: it rejects short glitches on a noisy
+/// signal, at the cost of `N` reads of the inner pin per read of the wrapper. `N` must be odd, so
+/// there is always a majority.
+///
+/// If a sample fails, its error is returned right away, and no more samples are taken.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::digital::InputPin;
+/// use embedded_hal_bus::digital::MajorityVote;
+/// # use core::cell::Cell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::digital;
+/// # struct Pin<'a>(&'a [bool], Cell<usize>);
+/// # impl digital::ErrorType for Pin<'_> { type Error = Infallible; }
+/// # impl InputPin for Pin<'_> {
+/// #     fn is_high(&self) -> Result<bool, Infallible> { let i = self.1.get(); self.1.set(i + 1); Ok(self.0[i]) }
+/// #     fn is_low(&self) -> Result<bool, Infallible> { self.is_high().map(|high| !high) }
+/// # }
+/// // A high signal, with a glitch on the second sample.
+/// # let pin = Pin(&[true, false, true], Cell::new(0));
+/// let pin = MajorityVote::<_, 3>::new(pin);
+/// assert!(pin.is_high()?);
+/// # Ok::<(), Infallible>(())
+/// ```
+pub struct MajorityVote<P, const N: usize> {
+    pin: P,
+}
+
+impl<P, const N: usize> MajorityVote<P, N> {
+    /// Create a new `MajorityVote` pin, sampling `pin` `N` times per read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is even.
+    #[inline]
+    pub fn new(pin: P) -> Self {
+        assert!(N % 2 == 1, "the number of samples must be odd, got {}", N);
+        Self { pin }
+    }
+
+    /// Returns a reference to the inner pin.
+    #[inline]
+    pub fn inner(&self) -> &P {
+        &self.pin
+    }
+
+    /// Returns the inner pin.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: ErrorType, const N: usize> ErrorType for MajorityVote<P, N> {
+    type Error = P::Error;
+}
+
+impl<P: InputPin, const N: usize> InputPin for MajorityVote<P, N> {
+    #[inline]
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        let mut high = 0;
+        for _ in 0..N {
+            if self.pin.is_high()? {
+                high += 1;
+            }
+        }
+        Ok(high > N / 2)
+    }
+
+    #[inline]
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.is_high().map(|high| !high)
+    }
+}
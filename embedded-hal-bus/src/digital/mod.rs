@@ -0,0 +1,30 @@
+//! Digital pin adapters.
+
+#[cfg(feature = "defmt-03")]
+mod defmt_pin;
+#[cfg(feature = "defmt-03")]
+pub use defmt_pin::*;
+mod blinker;
+pub use blinker::*;
+mod debounced;
+pub use debounced::*;
+mod group;
+pub use group::*;
+mod inverted;
+pub use inverted::*;
+mod majority_vote;
+pub use majority_vote::*;
+mod one_wire;
+pub use one_wire::*;
+mod pulse_counter;
+pub use pulse_counter::*;
+mod reset;
+pub use reset::*;
+#[cfg(feature = "async")]
+mod debounced_async;
+#[cfg(feature = "async")]
+pub use debounced_async::*;
+#[cfg(feature = "async")]
+mod polling_wait;
+#[cfg(feature = "async")]
+pub use polling_wait::*;
@@ -0,0 +1,135 @@
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{InputPin, OutputPin};
+
+/// Standard speed 1-Wire timings, in microseconds, from Maxim application note 126.
+mod timing {
+    /// Low time of a written 1, or of a read slot.
+    pub const A: u32 = 6;
+    /// Recovery after a written 1.
+    pub const B: u32 = 64;
+    /// Low time of a written 0.
+    pub const C: u32 = 60;
+    /// Recovery after a written 0.
+    pub const D: u32 = 10;
+    /// Wait before sampling a read slot.
+    pub const E: u32 = 9;
+    /// Recovery after sampling a read slot.
+    pub const F: u32 = 55;
+    /// Low time of the reset pulse.
+    pub const H: u32 = 480;
+    /// Wait before sampling the presence pulse.
+    pub const I: u32 = 70;
+    /// Recovery after sampling the presence pulse.
+    pub const J: u32 = 410;
+}
+
+/// Bidirectional data line of a 1-Wire bus, over a single open-drain pin.
+///
+/// This provides the 1-Wire time slots: the reset pulse, and writing and reading a single bit,
+/// with the standard speed timing. It is the building block of the
+/// [`OneWire`](crate::one_wire::OneWire) master.
+///
+/// The pin must be open-drain, with a pull-up: setting it low drives the line low, setting it
+/// high releases it, and reading it returns the level of the line, which devices can pull low.
+/// Delays are done with the delay passed to each method, as the slots must not be interrupted
+/// for their timing to be met.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::digital::OneWirePin;
+/// # use core::cell::Cell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{delay, digital};
+/// # struct Pin;
+/// # impl digital::ErrorType for Pin { type Error = Infallible; }
+/// # impl digital::OutputPin for Pin {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// # impl digital::InputPin for Pin {
+/// #     fn is_high(&self) -> Result<bool, Infallible> { Ok(false) }
+/// #     fn is_low(&self) -> Result<bool, Infallible> { Ok(true) }
+/// # }
+/// # struct Delay;
+/// # impl delay::DelayUs for Delay { fn delay_us(&mut self, _: u32) {} }
+/// let mut pin = OneWirePin::new(Pin);
+/// let mut delay = Delay;
+///
+/// if pin.reset_pulse(&mut delay)? {
+///     // Skip ROM, addressing the only device on the bus.
+///     for i in 0..8 {
+///         pin.write_bit(&mut delay, 0xCC >> i & 1 != 0)?;
+///     }
+/// }
+/// # Ok::<(), Infallible>(())
+/// ```
+pub struct OneWirePin<P> {
+    pin: P,
+}
+
+impl<P> OneWirePin<P> {
+    /// Create a new `OneWirePin`.
+    ///
+    /// The pin must already be released (high), so the bus is idle.
+    #[inline]
+    pub fn new(pin: P) -> Self {
+        Self { pin }
+    }
+
+    /// Returns a reference to the inner pin.
+    #[inline]
+    pub fn inner(&self) -> &P {
+        &self.pin
+    }
+
+    /// Returns the inner pin.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+}
+
+impl<P, E> OneWirePin<P>
+where
+    P: OutputPin<Error = E> + InputPin<Error = E>,
+{
+    /// Send a reset pulse, returning `true` if a device answered with a presence pulse.
+    #[inline]
+    pub fn reset_pulse(&mut self, delay: &mut impl DelayUs) -> Result<bool, E> {
+        self.pin.set_low()?;
+        delay.delay_us(timing::H);
+        self.pin.set_high()?;
+        delay.delay_us(timing::I);
+        let present = self.pin.is_low()?;
+        delay.delay_us(timing::J);
+        Ok(present)
+    }
+
+    /// Write a single bit.
+    #[inline]
+    pub fn write_bit(&mut self, delay: &mut impl DelayUs, bit: bool) -> Result<(), E> {
+        let (low_us, recovery_us) = if bit {
+            (timing::A, timing::B)
+        } else {
+            (timing::C, timing::D)
+        };
+        self.pin.set_low()?;
+        delay.delay_us(low_us);
+        self.pin.set_high()?;
+        delay.delay_us(recovery_us);
+        Ok(())
+    }
+
+    /// Read a single bit.
+    #[inline]
+    pub fn read_bit(&mut self, delay: &mut impl DelayUs) -> Result<bool, E> {
+        self.pin.set_low()?;
+        delay.delay_us(timing::A);
+        self.pin.set_high()?;
+        delay.delay_us(timing::E);
+        let bit = self.pin.is_high()?;
+        delay.delay_us(timing::F);
+        Ok(bit)
+    }
+}
@@ -0,0 +1,127 @@
+use embedded_hal::digital::{ErrorType, InputPin};
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::digital::Wait;
+
+/// [`Wait`] implementation polling an [`InputPin`].
+///
+/// This is a fallback for HALs whose pins can't wake a task on an interrupt. The pin is read
+/// every `interval_us` microseconds, waiting in between with an async delay.
+///
+/// Polling uses more power than interrupts, and pulses shorter than the interval may be missed.
+/// For example, [`wait_for_rising_edge`](Wait::wait_for_rising_edge) only resolves once the pin
+/// has been seen low, then high.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_async::digital::Wait;
+/// use embedded_hal_bus::digital::PollingWait;
+/// # use core::cell::Cell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::digital;
+/// # use embedded_hal_async::delay;
+/// // A pin going high on its fourth read.
+/// # struct Pin(Cell<u32>);
+/// # impl digital::ErrorType for Pin { type Error = Infallible; }
+/// # impl digital::InputPin for Pin {
+/// #     fn is_high(&self) -> Result<bool, Infallible> {
+/// #         self.0.set(self.0.get() + 1);
+/// #         Ok(self.0.get() >= 4)
+/// #     }
+/// #     fn is_low(&self) -> Result<bool, Infallible> { self.is_high().map(|high| !high) }
+/// # }
+/// # #[derive(Default)]
+/// # struct Delay { waited: u32 }
+/// # impl delay::DelayUs for Delay {
+/// #     async fn delay_us(&mut self, us: u32) { self.waited += us; }
+/// #     async fn delay_ms(&mut self, ms: u32) { self.waited += ms * 1000; }
+/// # }
+/// let mut pin = PollingWait::new(Pin(Cell::new(0)), Delay::default(), 100);
+///
+/// futures::executor::block_on(pin.wait_for_high()).unwrap();
+///
+/// let (_, delay) = pin.into_inner();
+/// assert_eq!(delay.waited, 300);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub struct PollingWait<P, D> {
+    pin: P,
+    delay: D,
+    interval_us: u32,
+}
+
+impl<P, D> PollingWait<P, D> {
+    /// Create a new `PollingWait`, reading `pin` every `interval_us` microseconds.
+    #[inline]
+    pub fn new(pin: P, delay: D, interval_us: u32) -> Self {
+        Self {
+            pin,
+            delay,
+            interval_us,
+        }
+    }
+
+    /// Returns a reference to the inner pin.
+    #[inline]
+    pub fn pin(&self) -> &P {
+        &self.pin
+    }
+
+    /// Destroys the wrapper, returning the pin and the delay.
+    #[inline]
+    pub fn into_inner(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+}
+
+impl<P, D> PollingWait<P, D>
+where
+    P: InputPin,
+    D: DelayUs,
+{
+    /// Polls the pin until it reads `high`.
+    async fn wait_for_level(&mut self, high: bool) -> Result<(), P::Error> {
+        while self.pin.is_high()? != high {
+            self.delay.delay_us(self.interval_us).await;
+        }
+        Ok(())
+    }
+}
+
+impl<P: ErrorType, D> ErrorType for PollingWait<P, D> {
+    type Error = P::Error;
+}
+
+impl<P, D> Wait for PollingWait<P, D>
+where
+    P: InputPin,
+    D: DelayUs,
+{
+    #[inline]
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(true).await
+    }
+
+    #[inline]
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(false).await
+    }
+
+    #[inline]
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(false).await?;
+        self.wait_for_level(true).await
+    }
+
+    #[inline]
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(true).await?;
+        self.wait_for_level(false).await
+    }
+
+    #[inline]
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        let high = self.pin.is_high()?;
+        self.wait_for_level(!high).await
+    }
+}
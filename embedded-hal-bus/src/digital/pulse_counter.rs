@@ -0,0 +1,94 @@
+use embedded_hal::digital::InputPin;
+
+/// Counter of the rising edges of an input pin, detected by polling.
+///
+/// Each [`poll`](Self::poll) reads the pin, and counts a rising edge if it is high while it was
+/// low at the previous poll. The first poll only records the level, so a pin already high at that
+/// point isn't counted.
+///
+/// Since the pin is only sampled when polled, pulses shorter than the polling period can be
+/// missed: any number of pulses between two polls counts as at most one edge. The pin must be
+/// polled more than twice per period of the fastest expected signal, or counted with a timer or
+/// an interrupt instead.
+///
+/// The count wraps around after `u32::MAX` edges.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::digital::PulseCounter;
+/// # use core::cell::Cell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::digital::{self, InputPin};
+/// # struct Pin<'a>(&'a [bool], Cell<usize>);
+/// # impl digital::ErrorType for Pin<'_> { type Error = Infallible; }
+/// # impl InputPin for Pin<'_> {
+/// #     fn is_high(&self) -> Result<bool, Infallible> { let i = self.1.get(); self.1.set(i + 1); Ok(self.0[i]) }
+/// #     fn is_low(&self) -> Result<bool, Infallible> { self.is_high().map(|high| !high) }
+/// # }
+/// # let pin = Pin(&[false, true, true, false, true], Cell::new(0));
+/// let mut counter = PulseCounter::new(pin);
+///
+/// // Low, high, high, low, high.
+/// for _ in 0..5 {
+///     counter.poll()?;
+/// }
+/// assert_eq!(counter.count(), 2);
+/// # Ok::<(), Infallible>(())
+/// ```
+pub struct PulseCounter<P> {
+    pin: P,
+    last: Option<bool>,
+    count: u32,
+}
+
+impl<P> PulseCounter<P> {
+    /// Create a new `PulseCounter`, counting the rising edges of `pin` from 0.
+    #[inline]
+    pub fn new(pin: P) -> Self {
+        Self {
+            pin,
+            last: None,
+            count: 0,
+        }
+    }
+
+    /// Returns the number of rising edges counted so far.
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Resets the count to 0, keeping the last level read.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+
+    /// Returns a reference to the inner pin.
+    #[inline]
+    pub fn inner(&self) -> &P {
+        &self.pin
+    }
+
+    /// Returns the inner pin.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: InputPin> PulseCounter<P> {
+    /// Reads the pin, counting a rising edge since the previous poll, and returns the total count.
+    ///
+    /// If the read fails, its error is returned and the state of the counter is unchanged.
+    #[inline]
+    pub fn poll(&mut self) -> Result<u32, P::Error> {
+        let high = self.pin.is_high()?;
+        if high && self.last == Some(false) {
+            self.count = self.count.wrapping_add(1);
+        }
+        self.last = Some(high);
+        Ok(self.count)
+    }
+}
@@ -0,0 +1,62 @@
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{OutputPin, PinState};
+
+/// Pulses the reset pin of a peripheral, then waits for it to settle.
+///
+/// Asserts `rst` for `low_us` microseconds, deasserts it, then waits `settle_us` microseconds
+/// before returning, so the peripheral is ready to use. Reset is asserted by driving `rst` low if
+/// `active_low` is `true`, or high otherwise; despite its name, `low_us` is the time reset is
+/// asserted in both cases.
+///
+/// If setting the pin fails, the error is returned right away, and the sequence is not
+/// completed.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::digital::reset_pulse;
+/// # use core::cell::RefCell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{delay, digital};
+/// # #[derive(Debug, PartialEq)]
+/// # enum Event { Low, High, Wait(u32) }
+/// # struct Pin<'a>(&'a RefCell<Vec<Event>>);
+/// # impl digital::ErrorType for Pin<'_> { type Error = Infallible; }
+/// # impl digital::OutputPin for Pin<'_> {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(self.0.borrow_mut().push(Event::Low)) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(self.0.borrow_mut().push(Event::High)) }
+/// # }
+/// # struct Delay<'a>(&'a RefCell<Vec<Event>>);
+/// # impl delay::DelayUs for Delay<'_> {
+/// #     fn delay_us(&mut self, us: u32) { self.0.borrow_mut().push(Event::Wait(us)); }
+/// # }
+/// let log = RefCell::new(Vec::new());
+/// # let (mut rst, mut delay) = (Pin(&log), Delay(&log));
+///
+/// // Active-low reset, held for 10 µs, then 5 ms until the peripheral is ready.
+/// reset_pulse(&mut rst, &mut delay, 10, 5_000, true)?;
+///
+/// # use Event::*;
+/// assert_eq!(*log.borrow(), [Low, Wait(10), High, Wait(5_000)]);
+///
+/// // Active-high reset.
+/// log.borrow_mut().clear();
+/// reset_pulse(&mut rst, &mut delay, 10, 5_000, false)?;
+///
+/// assert_eq!(*log.borrow(), [High, Wait(10), Low, Wait(5_000)]);
+/// # Ok::<(), Infallible>(())
+/// ```
+pub fn reset_pulse<P: OutputPin, D: DelayUs>(
+    rst: &mut P,
+    delay: &mut D,
+    low_us: u32,
+    settle_us: u32,
+    active_low: bool,
+) -> Result<(), P::Error> {
+    let asserted = PinState::from(!active_low);
+    rst.set_state(asserted)?;
+    delay.delay_us(low_us);
+    rst.set_state(!asserted)?;
+    delay.delay_us(settle_us);
+    Ok(())
+}
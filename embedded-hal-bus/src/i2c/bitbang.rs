@@ -0,0 +1,286 @@
+//! Software I2C master over two GPIO pins.
+
+use core::fmt::{self, Debug};
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{InputPin, OutputPin, PinState};
+use embedded_hal::i2c::{Error, ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+use crate::BitOrder;
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// Error type for [`BitBangI2c`] operations.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum BitBangError<E> {
+    /// Setting or reading SCL or SDA failed.
+    Pin(E),
+    /// The device didn't acknowledge its address or a data byte.
+    NoAcknowledge(NoAcknowledgeSource),
+}
+
+impl<E: Debug> Error for BitBangError<E> {
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Pin(_) => ErrorKind::Other,
+            Self::NoAcknowledge(source) => ErrorKind::NoAcknowledge(*source),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for BitBangError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pin(e) => write!(f, "Pin error: {}", e),
+            Self::NoAcknowledge(source) => write!(f, "{}", source),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Display + Debug> std::error::Error for BitBangError<E> {}
+
+/// [`I2c`] master implemented in software, by toggling the SCL and SDA pins.
+///
+/// Both pins must be open-drain with pull-ups: setting a pin high releases the line, and SDA is
+/// read back through [`InputPin`] to receive acknowledgements and data. Each half clock period is
+/// a `half_period_us` delay, so the clock runs at most at `1 / (2 * half_period_us)` MHz. Clock
+/// stretching by devices isn't supported.
+///
+/// Bytes are shifted out and in most significant bit first, as required by the I2C specification.
+/// [`set_bit_order`](Self::set_bit_order) can flip this for non-standard devices clocking bytes
+/// least significant bit first. The bit order applies to every byte on the wire, including the
+/// address byte.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::i2c::I2c;
+/// use embedded_hal_bus::i2c::{BitBangI2c, BitOrder};
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{delay, digital};
+/// # struct Pin;
+/// # impl digital::ErrorType for Pin { type Error = Infallible; }
+/// # impl digital::OutputPin for Pin {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// # impl digital::InputPin for Pin {
+/// #     fn is_high(&self) -> Result<bool, Infallible> { Ok(false) }
+/// #     fn is_low(&self) -> Result<bool, Infallible> { Ok(true) }
+/// # }
+/// # struct Delay;
+/// # impl delay::DelayUs for Delay { fn delay_us(&mut self, _: u32) {} }
+/// // 100 kHz.
+/// let mut i2c = BitBangI2c::new(Pin, Pin, Delay, 5);
+/// assert_eq!(i2c.bit_order(), BitOrder::MsbFirst);
+/// i2c.write(0x48, &[0x01, 0x60]).unwrap();
+///
+/// i2c.set_bit_order(BitOrder::LsbFirst);
+/// i2c.write(0x48, &[0x01, 0x60]).unwrap();
+/// ```
+pub struct BitBangI2c<SCL, SDA, D> {
+    scl: SCL,
+    sda: SDA,
+    delay: D,
+    half_period_us: u32,
+    bit_order: BitOrder,
+}
+
+impl<SCL, SDA, D> BitBangI2c<SCL, SDA, D> {
+    /// Create a new `BitBangI2c`, waiting `half_period_us` between clock edges.
+    ///
+    /// Both pins must already be released (high), so the bus is idle.
+    #[inline]
+    pub fn new(scl: SCL, sda: SDA, delay: D, half_period_us: u32) -> Self {
+        Self {
+            scl,
+            sda,
+            delay,
+            half_period_us,
+            bit_order: BitOrder::MsbFirst,
+        }
+    }
+
+    /// Returns the order in which the bits of each byte are shifted, MSB first by default.
+    #[inline]
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Sets the order in which the bits of each byte are shifted.
+    #[inline]
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.bit_order = bit_order;
+    }
+
+    /// Destroys the master, returning the SCL and SDA pins and the delay.
+    #[inline]
+    pub fn into_inner(self) -> (SCL, SDA, D) {
+        (self.scl, self.sda, self.delay)
+    }
+}
+
+impl<SCL, SDA, D, E> BitBangI2c<SCL, SDA, D>
+where
+    SCL: OutputPin<Error = E>,
+    SDA: OutputPin<Error = E> + InputPin<Error = E>,
+    D: DelayUs,
+{
+    #[inline]
+    fn wait(&mut self) {
+        self.delay.delay_us(self.half_period_us);
+    }
+
+    /// Sends a start condition, or a repeated start if a transaction is in progress.
+    fn start(&mut self) -> Result<(), E> {
+        self.sda.set_high()?;
+        self.scl.set_high()?;
+        self.wait();
+        self.sda.set_low()?;
+        self.wait();
+        self.scl.set_low()
+    }
+
+    fn stop(&mut self) -> Result<(), E> {
+        self.sda.set_low()?;
+        self.wait();
+        self.scl.set_high()?;
+        self.wait();
+        self.sda.set_high()?;
+        self.wait();
+        Ok(())
+    }
+
+    fn write_bit(&mut self, bit: bool) -> Result<(), E> {
+        self.sda.set_state(PinState::from(bit))?;
+        self.wait();
+        self.scl.set_high()?;
+        self.wait();
+        self.scl.set_low()
+    }
+
+    fn read_bit(&mut self) -> Result<bool, E> {
+        self.sda.set_high()?;
+        self.wait();
+        self.scl.set_high()?;
+        self.wait();
+        let bit = self.sda.is_high()?;
+        self.scl.set_low()?;
+        Ok(bit)
+    }
+
+    /// Returns the mask of the `i`th bit of a byte shifted on the wire.
+    #[inline]
+    fn bit_mask(&self, i: u32) -> u8 {
+        match self.bit_order {
+            BitOrder::MsbFirst => 0x80 >> i,
+            BitOrder::LsbFirst => 1 << i,
+        }
+    }
+
+    /// Writes `byte`, returning `true` if the device acknowledged it.
+    fn write_byte(&mut self, byte: u8) -> Result<bool, E> {
+        for i in 0..8 {
+            self.write_bit(byte & self.bit_mask(i) != 0)?;
+        }
+        Ok(!self.read_bit()?)
+    }
+
+    /// Reads a byte, then acknowledges it if `ack` is `true`.
+    fn read_byte(&mut self, ack: bool) -> Result<u8, E> {
+        let mut byte = 0;
+        for i in 0..8 {
+            if self.read_bit()? {
+                byte |= self.bit_mask(i);
+            }
+        }
+        self.write_bit(!ack)?;
+        Ok(byte)
+    }
+
+    fn write_acked(
+        &mut self,
+        byte: u8,
+        source: NoAcknowledgeSource,
+    ) -> Result<(), BitBangError<E>> {
+        if self.write_byte(byte).map_err(BitBangError::Pin)? {
+            Ok(())
+        } else {
+            Err(BitBangError::NoAcknowledge(source))
+        }
+    }
+
+    fn run_operations(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), BitBangError<E>> {
+        let mut prev_read = None;
+        let mut operations = operations.iter_mut().peekable();
+        while let Some(operation) = operations.next() {
+            let read = matches!(operation, Operation::Read(_));
+            // Adjacent operations of the same kind are merged, without a repeated start.
+            if prev_read != Some(read) {
+                self.start().map_err(BitBangError::Pin)?;
+                self.write_acked(address << 1 | read as u8, NoAcknowledgeSource::Address)?;
+            }
+            prev_read = Some(read);
+
+            match operation {
+                Operation::Read(buf) => {
+                    // The last byte read before a stop or a repeated start isn't acknowledged.
+                    let last_read = !matches!(operations.peek(), Some(Operation::Read(_)));
+                    let len = buf.len();
+                    for (i, byte) in buf.iter_mut().enumerate() {
+                        let ack = !(last_read && i == len - 1);
+                        *byte = self.read_byte(ack).map_err(BitBangError::Pin)?;
+                    }
+                }
+                Operation::Write(buf) => {
+                    for byte in buf.iter() {
+                        self.write_acked(*byte, NoAcknowledgeSource::Data)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<SCL, SDA, D, E> ErrorType for BitBangI2c<SCL, SDA, D>
+where
+    SCL: OutputPin<Error = E>,
+    SDA: OutputPin<Error = E> + InputPin<Error = E>,
+    E: Debug,
+{
+    type Error = BitBangError<E>;
+}
+
+impl<SCL, SDA, D, E> I2c for BitBangI2c<SCL, SDA, D>
+where
+    SCL: OutputPin<Error = E>,
+    SDA: OutputPin<Error = E> + InputPin<Error = E>,
+    D: DelayUs,
+    E: Debug,
+{
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if operations.is_empty() {
+            return Ok(());
+        }
+        let res = self.run_operations(address, operations);
+        // Release the bus, unless a pin failed and the bus state is unknown.
+        if let Err(BitBangError::Pin(_)) = res {
+            return res;
+        }
+        self.stop().map_err(BitBangError::Pin)?;
+        res
+    }
+}
@@ -21,6 +21,31 @@ impl<'a, T> CriticalSectionDevice<'a, T> {
     }
 }
 
+impl<'a, T> CriticalSectionDevice<'a, T>
+where
+    T: I2c,
+{
+    /// Write `write` to the device at `address`, then read into `read`, with a stop in between.
+    ///
+    /// Unlike [`I2c::write_read`], which does both in a single transaction with a repeated start
+    /// between the write and the read, this does two separate transactions, each ending with a
+    /// stop. This is needed by some devices which misbehave on repeated starts. The bus is held
+    /// for both transactions, so no other device sharing it can use it in between.
+    #[inline]
+    pub fn write_then_read_with_stop(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), T::Error> {
+        critical_section::with(|cs| {
+            let bus = &mut *self.bus.borrow_ref_mut(cs);
+            bus.write(address, write)?;
+            bus.read(address, read)
+        })
+    }
+}
+
 impl<'a, T> ErrorType for CriticalSectionDevice<'a, T>
 where
     T: I2c,
@@ -0,0 +1,172 @@
+use core::cell::RefCell;
+use critical_section::Mutex;
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+/// `critical-section`-based shared bus [`I2c`] implementation, for a device behind an I2C mux.
+///
+/// Like [`CriticalSectionDevice`](super::CriticalSectionDevice), sharing is implemented with a
+/// `critical-section` [`Mutex`](critical_section::Mutex). Before each transaction, the mux at
+/// `mux_address` is switched to the device channel by writing the `select` byte to it. A single
+/// critical section spans both the mux selection and the transaction, so no other device on the
+/// bus, even one used from an interrupt, can reselect the mux in between.
+///
+/// The `select` byte is specific to the mux: for example, channel `n` of a TCA9548A is selected
+/// by writing `1 << n`. An error writing it is returned as is, and the transaction is not done.
+///
+/// # Examples
+///
+/// ```
+/// use core::cell::RefCell;
+/// use critical_section::Mutex;
+/// use embedded_hal::i2c::I2c;
+/// use embedded_hal_bus::i2c::CsMuxDevice;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::i2c::{self, Operation};
+/// # #[derive(Default)]
+/// # struct Bus { written: Vec<(u8, Vec<u8>)> }
+/// # impl i2c::ErrorType for Bus { type Error = Infallible; }
+/// # impl i2c::I2c for Bus {
+/// #     fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Infallible> {
+/// #         for op in operations {
+/// #             if let Operation::Write(buf) = op { self.written.push((address, buf.to_vec())); }
+/// #         }
+/// #         Ok(())
+/// #     }
+/// # }
+/// # struct SingleThread;
+/// # critical_section::set_impl!(SingleThread);
+/// # unsafe impl critical_section::Impl for SingleThread {
+/// #     unsafe fn acquire() -> bool { false }
+/// #     unsafe fn release(_: bool) {}
+/// # }
+/// const MUX: u8 = 0x70;
+///
+/// let bus = Mutex::new(RefCell::new(Bus::default()));
+/// let mut sensor0 = CsMuxDevice::new(&bus, MUX, 1 << 0);
+/// let mut sensor1 = CsMuxDevice::new(&bus, MUX, 1 << 1);
+///
+/// // Both sensors have the same address, on different channels of the mux.
+/// sensor0.write(0x48, &[0x01]).unwrap();
+/// sensor1.write(0x48, &[0x02]).unwrap();
+///
+/// # critical_section::with(|cs| {
+/// assert_eq!(
+///     bus.borrow_ref(cs).written,
+///     [
+///         (MUX, vec![0b01]),
+///         (0x48, vec![0x01]),
+///         (MUX, vec![0b10]),
+///         (0x48, vec![0x02]),
+///     ]
+/// );
+/// # });
+/// ```
+pub struct CsMuxDevice<'a, T> {
+    bus: &'a Mutex<RefCell<T>>,
+    mux_address: u8,
+    select: u8,
+}
+
+impl<'a, T> CsMuxDevice<'a, T> {
+    /// Create a new `CsMuxDevice`, on the channel of the mux at `mux_address` selected by
+    /// writing `select` to it.
+    #[inline]
+    pub fn new(bus: &'a Mutex<RefCell<T>>, mux_address: u8, select: u8) -> Self {
+        Self {
+            bus,
+            mux_address,
+            select,
+        }
+    }
+
+    /// Returns the address of the mux.
+    #[inline]
+    pub fn mux_address(&self) -> u8 {
+        self.mux_address
+    }
+
+    /// Returns the byte written to the mux to select the channel of this device.
+    #[inline]
+    pub fn select(&self) -> u8 {
+        self.select
+    }
+}
+
+impl<'a, T> CsMuxDevice<'a, T>
+where
+    T: I2c,
+{
+    /// Runs `f` on the bus within a critical section, after selecting the channel of this device.
+    #[inline]
+    fn with_channel<R>(
+        &mut self,
+        f: impl FnOnce(&mut T) -> Result<R, T::Error>,
+    ) -> Result<R, T::Error> {
+        critical_section::with(|cs| {
+            let bus = &mut *self.bus.borrow_ref_mut(cs);
+            bus.write(self.mux_address, &[self.select])?;
+            f(bus)
+        })
+    }
+
+    /// Write `write` to the device at `address`, then read into `read`, with a stop in between.
+    ///
+    /// Unlike [`I2c::write_read`], which does both in a single transaction with a repeated start
+    /// between the write and the read, this does two separate transactions, each ending with a
+    /// stop. This is needed by some devices which misbehave on repeated starts. The mux channel is
+    /// selected once, and the critical section held for both transactions, so no other device
+    /// sharing the bus can use it in between.
+    #[inline]
+    pub fn write_then_read_with_stop(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), T::Error> {
+        self.with_channel(|bus| {
+            bus.write(address, write)?;
+            bus.read(address, read)
+        })
+    }
+}
+
+impl<'a, T> ErrorType for CsMuxDevice<'a, T>
+where
+    T: I2c,
+{
+    type Error = T::Error;
+}
+
+impl<'a, T> I2c for CsMuxDevice<'a, T>
+where
+    T: I2c,
+{
+    #[inline]
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.with_channel(|bus| bus.read(address, read))
+    }
+
+    #[inline]
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        self.with_channel(|bus| bus.write(address, write))
+    }
+
+    #[inline]
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.with_channel(|bus| bus.write_read(address, write, read))
+    }
+
+    #[inline]
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.with_channel(|bus| bus.transaction(address, operations))
+    }
+}
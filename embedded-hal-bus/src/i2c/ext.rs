@@ -0,0 +1,128 @@
+use embedded_hal::i2c::{AddressMode, I2c, Operation, SevenBitAddress};
+
+/// Convenience methods for common I2C access patterns.
+///
+/// This trait is implemented for every [`I2c`], including the shared bus
+/// devices in this module, so drivers can use it on whichever `I2c` they are given.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::i2c::I2cExt;
+/// # use embedded_hal::i2c::{self as hali2c, Operation, I2c};
+/// # struct Sensor;
+/// # impl hali2c::ErrorType for Sensor {
+/// #     type Error = hali2c::ErrorKind;
+/// # }
+/// # impl I2c for Sensor {
+/// #     fn transaction(&mut self, _address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+/// #         for op in operations {
+/// #             if let Operation::Read(buf) = op {
+/// #                 buf.copy_from_slice(&[0x12, 0x34][..buf.len()]);
+/// #             }
+/// #         }
+/// #         Ok(())
+/// #     }
+/// # }
+/// let mut i2c = Sensor;
+///
+/// assert_eq!(i2c.read_reg_u8(0x42, 0x0F)?, 0x12);
+/// assert_eq!(i2c.read_reg_u16_be(0x42, 0x10)?, 0x1234);
+/// assert_eq!(i2c.read_reg_u16_le(0x42, 0x10)?, 0x3412);
+/// # Ok::<(), hali2c::ErrorKind>(())
+/// ```
+pub trait I2cExt<A: AddressMode = SevenBitAddress>: I2c<A> {
+    /// Reads a single byte from register `reg` of the device at `address`.
+    ///
+    /// This writes `reg` and then reads one byte back, using [`I2c::write_read`].
+    #[inline]
+    fn read_reg_u8(&mut self, address: A, reg: u8) -> Result<u8, Self::Error> {
+        let mut buf = [0; 1];
+        self.write_read(address, &[reg], &mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Reads a big-endian `u16` starting at register `reg` of the device at `address`.
+    ///
+    /// The first byte received is the most significant one.
+    #[inline]
+    fn read_reg_u16_be(&mut self, address: A, reg: u8) -> Result<u16, Self::Error> {
+        let mut buf = [0; 2];
+        self.write_read(address, &[reg], &mut buf)?;
+        Ok(u16::from_be_bytes(buf))
+    }
+
+    /// Reads a little-endian `u16` starting at register `reg` of the device at `address`.
+    ///
+    /// The first byte received is the least significant one.
+    #[inline]
+    fn read_reg_u16_le(&mut self, address: A, reg: u8) -> Result<u16, Self::Error> {
+        let mut buf = [0; 2];
+        self.write_read(address, &[reg], &mut buf)?;
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    /// Writes `data` to the general call address (0x00), addressing all devices on the bus.
+    ///
+    /// The meaning of `data` is defined by the I2C specification, for example `[0x06]` requests
+    /// a reset, and by the devices supporting it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_bus::i2c::I2cExt;
+    /// # use embedded_hal::i2c::{self as hali2c, Operation, I2c};
+    /// # #[derive(Debug, PartialEq)]
+    /// # enum Event { Write(u8, Vec<u8>), Read(u8, usize) }
+    /// # #[derive(Default)]
+    /// # struct Bus { events: Vec<Event> }
+    /// # impl hali2c::ErrorType for Bus {
+    /// #     type Error = hali2c::ErrorKind;
+    /// # }
+    /// # impl I2c for Bus {
+    /// #     fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+    /// #         for op in operations {
+    /// #             self.events.push(match op {
+    /// #                 Operation::Write(buf) => Event::Write(address, buf.to_vec()),
+    /// #                 Operation::Read(buf) => Event::Read(address, buf.len()),
+    /// #             });
+    /// #         }
+    /// #         Ok(())
+    /// #     }
+    /// # }
+    /// let mut i2c = Bus::default();
+    ///
+    /// i2c.general_call(&[0x06])?;
+    /// i2c.quick_command(0x42, false)?;
+    /// i2c.quick_command(0x42, true)?;
+    ///
+    /// # use Event::*;
+    /// assert_eq!(i2c.events, [Write(0x00, vec![0x06]), Write(0x42, vec![]), Read(0x42, 0)]);
+    /// # Ok::<(), hali2c::ErrorKind>(())
+    /// ```
+    #[inline]
+    fn general_call(&mut self, data: &[u8]) -> Result<(), Self::Error>
+    where
+        Self: I2c<SevenBitAddress>,
+    {
+        I2c::<SevenBitAddress>::write(self, 0x00, data)
+    }
+
+    /// Sends an SMBus quick command to the device at `address`.
+    ///
+    /// A quick command only consists of the address, with the R/W bit set if `read` is `true`,
+    /// and no data bytes. Some devices use it, for example, to be switched on or off.
+    ///
+    /// This is an empty write or read [transaction](I2c::transaction). Not all I2C
+    /// implementations support zero-length reads.
+    #[inline]
+    fn quick_command(&mut self, address: A, read: bool) -> Result<(), Self::Error> {
+        if read {
+            self.transaction(address, &mut [Operation::Read(&mut [])])
+        } else {
+            self.transaction(address, &mut [Operation::Write(&[])])
+        }
+    }
+}
+
+impl<A: AddressMode, T: I2c<A> + ?Sized> I2cExt<A> for T {}
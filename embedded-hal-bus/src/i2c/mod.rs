@@ -1,10 +1,21 @@
 //! `I2c` shared bus implementations.
 
 mod refcell;
+pub use crate::BitOrder;
 pub use refcell::*;
+mod bitbang;
+pub use bitbang::*;
 #[cfg(feature = "std")]
 mod mutex;
 #[cfg(feature = "std")]
 pub use mutex::*;
 mod critical_section;
 pub use self::critical_section::*;
+mod cs_mux;
+pub use cs_mux::*;
+mod ext;
+pub use ext::*;
+mod remapped;
+pub use remapped::*;
+mod scan;
+pub use scan::*;
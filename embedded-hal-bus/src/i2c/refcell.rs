@@ -75,6 +75,70 @@ impl<'a, T> RefCellDevice<'a, T> {
     }
 }
 
+impl<'a, T> RefCellDevice<'a, T>
+where
+    T: I2c,
+{
+    /// Write `write` to the device at `address`, then read into `read`, with a stop in between.
+    ///
+    /// Unlike [`I2c::write_read`], which does both in a single transaction with a repeated start
+    /// between the write and the read, this does two separate transactions, each ending with a
+    /// stop. This is needed by some devices which misbehave on repeated starts. The bus is held
+    /// for both transactions, so no other device sharing it can use it in between.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::cell::RefCell;
+    /// use embedded_hal_bus::i2c::RefCellDevice;
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::i2c::{self, Operation};
+    /// # #[derive(Debug, PartialEq)]
+    /// # enum Event { Write(u8, Vec<u8>), Read(u8, usize), Stop }
+    /// # use Event::*;
+    /// # #[derive(Default)]
+    /// # struct Bus { events: Vec<Event> }
+    /// # impl i2c::ErrorType for Bus { type Error = Infallible; }
+    /// # impl i2c::I2c for Bus {
+    /// #     fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Infallible> {
+    /// #         for op in operations {
+    /// #             match op {
+    /// #                 Operation::Write(buf) => self.events.push(Write(address, buf.to_vec())),
+    /// #                 Operation::Read(buf) => {
+    /// #                     buf.fill(0x42);
+    /// #                     self.events.push(Read(address, buf.len()));
+    /// #                 }
+    /// #             }
+    /// #         }
+    /// #         self.events.push(Stop);
+    /// #         Ok(())
+    /// #     }
+    /// # }
+    /// let bus = RefCell::new(Bus::default());
+    /// let mut device = RefCellDevice::new(&bus);
+    ///
+    /// let mut buf = [0; 2];
+    /// device.write_then_read_with_stop(0x48, &[0x0F], &mut buf).unwrap();
+    ///
+    /// assert_eq!(buf, [0x42; 2]);
+    /// assert_eq!(
+    ///     bus.borrow().events,
+    ///     [Write(0x48, vec![0x0F]), Stop, Read(0x48, 2), Stop]
+    /// );
+    /// ```
+    #[inline]
+    pub fn write_then_read_with_stop(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), T::Error> {
+        let bus = &mut *self.bus.borrow_mut();
+        bus.write(address, write)?;
+        bus.read(address, read)
+    }
+}
+
 impl<'a, T> ErrorType for RefCellDevice<'a, T>
 where
     T: I2c,
@@ -0,0 +1,103 @@
+use embedded_hal::i2c::{AddressMode, ErrorType, I2c, Operation};
+
+/// [`I2c`] wrapper translating device addresses before forwarding to the inner bus.
+///
+/// This allows handling board-level quirks, such as a device whose address pins are strapped
+/// differently than its driver expects, without changing the driver. Every address passed to
+/// the wrapper goes through the `remap` function, and the result is used on the inner bus.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::i2c::I2c;
+/// use embedded_hal_bus::i2c::Remapped;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::i2c::{self as hali2c, Operation};
+/// # #[derive(Default)]
+/// # struct Bus { addresses: Vec<u8> }
+/// # impl hali2c::ErrorType for Bus { type Error = Infallible; }
+/// # impl I2c for Bus {
+/// #     fn transaction(&mut self, address: u8, _: &mut [Operation<'_>]) -> Result<(), Infallible> {
+/// #         self.addresses.push(address);
+/// #         Ok(())
+/// #     }
+/// # }
+/// // The sensor answers at 0x49 instead of its datasheet address 0x48.
+/// let mut i2c = Remapped::new(Bus::default(), |address| match address {
+///     0x48 => 0x49,
+///     other => other,
+/// });
+///
+/// i2c.write(0x48, &[0x01])?;
+/// i2c.write(0x20, &[0x02])?;
+///
+/// assert_eq!(i2c.inner().addresses, [0x49, 0x20]);
+/// # Ok::<(), Infallible>(())
+/// ```
+pub struct Remapped<I, F> {
+    i2c: I,
+    remap: F,
+}
+
+impl<I, F> Remapped<I, F> {
+    /// Create a new `Remapped` bus, translating addresses with `remap`.
+    #[inline]
+    pub fn new(i2c: I, remap: F) -> Self {
+        Self { i2c, remap }
+    }
+
+    /// Returns a reference to the inner bus.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.i2c
+    }
+
+    /// Returns a mutable reference to the inner bus.
+    ///
+    /// Addresses are not translated when using it directly.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.i2c
+    }
+
+    /// Destroys the wrapper, returning the inner bus.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.i2c
+    }
+}
+
+impl<I: ErrorType, F> ErrorType for Remapped<I, F> {
+    type Error = I::Error;
+}
+
+impl<A, I, F> I2c<A> for Remapped<I, F>
+where
+    A: AddressMode,
+    I: I2c<A>,
+    F: FnMut(A) -> A,
+{
+    #[inline]
+    fn read(&mut self, address: A, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.read((self.remap)(address), read)
+    }
+
+    #[inline]
+    fn write(&mut self, address: A, write: &[u8]) -> Result<(), Self::Error> {
+        self.i2c.write((self.remap)(address), write)
+    }
+
+    #[inline]
+    fn write_read(&mut self, address: A, write: &[u8], read: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.write_read((self.remap)(address), write, read)
+    }
+
+    #[inline]
+    fn transaction(
+        &mut self,
+        address: A,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.i2c.transaction((self.remap)(address), operations)
+    }
+}
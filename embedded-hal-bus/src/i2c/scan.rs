@@ -0,0 +1,167 @@
+use core::ops::RangeInclusive;
+
+#[cfg(feature = "async")]
+use embedded_hal::i2c::{Error, ErrorKind, Operation};
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+use super::I2cExt;
+
+/// Returns `true` if `address` is reserved by the I2C specification.
+///
+/// These are the addresses `0x00..=0x07` (general call, START byte, CBUS, ...) and
+/// `0x78..=0x7F` (10-bit addressing, device ID, ...), which are not assigned to devices.
+#[inline]
+pub const fn is_reserved_address(address: SevenBitAddress) -> bool {
+    address <= 0x07 || address >= 0x78
+}
+
+/// Probes all the non-reserved 7-bit addresses, yielding those acknowledged by a device.
+///
+/// This is [`scan_range`] over `0x00..=0x7F`, skipping the [reserved](is_reserved_address)
+/// addresses.
+#[inline]
+pub fn scan<I>(i2c: &mut I) -> impl Iterator<Item = SevenBitAddress> + '_
+where
+    I: I2c<SevenBitAddress>,
+{
+    scan_range(i2c, 0x00..=0x7F, false)
+}
+
+/// Probes the 7-bit addresses in `range`, yielding those acknowledged by a device.
+///
+/// Each address is probed with an empty write, an SMBus [quick command](I2cExt::quick_command).
+/// Any error, not only a missing acknowledge, is taken as no device answering at that address.
+/// The [reserved](is_reserved_address) addresses are skipped unless `include_reserved` is `true`,
+/// and addresses above `0x7F` are always skipped.
+///
+/// The addresses are probed lazily, as the iterator is advanced.
+///
+/// Probing some devices can change their state, for example the quick command is used by some
+/// devices as an on/off switch. Only scan buses whose devices are known to tolerate it.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::i2c::{scan, scan_range};
+/// # use embedded_hal::i2c::{self as hali2c, ErrorKind, NoAcknowledgeSource, Operation};
+/// # struct Bus(&'static [u8]);
+/// # impl hali2c::ErrorType for Bus { type Error = ErrorKind; }
+/// # impl hali2c::I2c for Bus {
+/// #     fn transaction(&mut self, address: u8, _: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+/// #         match self.0.contains(&address) {
+/// #             true => Ok(()),
+/// #             false => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+/// #         }
+/// #     }
+/// # }
+/// // Devices answer at 0x00 (general call), 0x48 and 0x78 (10-bit addressing).
+/// let mut i2c = Bus(&[0x00, 0x48, 0x78]);
+///
+/// assert!(scan(&mut i2c).eq([0x48]));
+/// assert!(scan_range(&mut i2c, 0x00..=0x7F, true).eq([0x00, 0x48, 0x78]));
+/// assert!(scan_range(&mut i2c, 0x40..=0xFF, true).eq([0x48, 0x78]));
+/// ```
+#[inline]
+pub fn scan_range<I>(
+    i2c: &mut I,
+    range: RangeInclusive<SevenBitAddress>,
+    include_reserved: bool,
+) -> impl Iterator<Item = SevenBitAddress> + '_
+where
+    I: I2c<SevenBitAddress>,
+{
+    range
+        .filter(|&address| address <= 0x7F)
+        .filter(move |&address| include_reserved || !is_reserved_address(address))
+        .filter(move |&address| i2c.quick_command(address, false).is_ok())
+}
+
+/// Probes all the non-reserved 7-bit addresses of an async bus, like [`scan`].
+///
+/// The returned [`AsyncScan`] yields the addresses acknowledged by a device as
+/// [`next`](AsyncScan::next) is awaited.
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[inline]
+pub fn scan_async<I>(i2c: &mut I) -> AsyncScan<'_, I>
+where
+    I: AsyncI2c<SevenBitAddress>,
+{
+    AsyncScan { i2c, next: 0x08 }
+}
+
+/// Scan of an async I2C bus, returned by [`scan_async`].
+///
+/// Each address is probed with an empty write, like [`scan_range`] does. Unlike it, a missing
+/// acknowledge is told apart from other errors: the latter are yielded, and the scan can go on
+/// with the next address.
+///
+/// The scan is cancellation-safe: the address being probed is only skipped once its probe
+/// completes, so if a [`next`](Self::next) future is dropped, the next call probes that address
+/// again. The bus itself is left as usable as the I2C implementation leaves it after a dropped
+/// transaction.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::i2c::scan_async;
+/// # use embedded_hal_async::i2c::{self as hali2c, ErrorKind, NoAcknowledgeSource, Operation};
+/// # struct Bus;
+/// # impl hali2c::ErrorType for Bus { type Error = ErrorKind; }
+/// # impl hali2c::I2c for Bus {
+/// #     async fn transaction(&mut self, address: u8, _: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+/// #         match address {
+/// #             0x48 | 0x68 => Ok(()),
+/// #             0x50 => Err(ErrorKind::ArbitrationLoss),
+/// #             _ => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+/// #         }
+/// #     }
+/// # }
+/// // Devices answer at 0x48 and 0x68, and probing 0x50 fails.
+/// let mut i2c = Bus;
+///
+/// futures::executor::block_on(async {
+///     let mut scan = scan_async(&mut i2c);
+///     assert_eq!(scan.next().await, Some(Ok(0x48)));
+///     assert_eq!(scan.next().await, Some(Err(ErrorKind::ArbitrationLoss)));
+///     assert_eq!(scan.next().await, Some(Ok(0x68)));
+///     assert_eq!(scan.next().await, None);
+/// });
+/// ```
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub struct AsyncScan<'a, I> {
+    i2c: &'a mut I,
+    /// Next address to probe, past the last one when the scan is over.
+    next: SevenBitAddress,
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<I> AsyncScan<'_, I>
+where
+    I: AsyncI2c<SevenBitAddress>,
+{
+    /// Probes the addresses up to the next one acknowledged by a device, and returns it.
+    ///
+    /// Returns the error if probing an address fails other than by a missing acknowledge, or
+    /// `None` once all the addresses are probed.
+    pub async fn next(&mut self) -> Option<Result<SevenBitAddress, I::Error>> {
+        while !is_reserved_address(self.next) {
+            let address = self.next;
+            let res = self
+                .i2c
+                .transaction(address, &mut [Operation::Write(&[])])
+                .await;
+            self.next += 1;
+            match res {
+                Ok(()) => return Some(Ok(address)),
+                Err(e) if matches!(e.kind(), ErrorKind::NoAcknowledge(_)) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+}
@@ -8,5 +8,14 @@
 #[cfg(feature = "defmt-03")]
 use defmt_03 as defmt;
 
+mod bit_order;
+pub use bit_order::BitOrder;
+
+pub mod delay;
+pub mod digital;
 pub mod i2c;
+pub mod one_wire;
 pub mod spi;
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod test_util;
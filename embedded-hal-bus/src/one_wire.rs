@@ -0,0 +1,330 @@
+//! 1-Wire bus master.
+
+use core::fmt::{self, Debug};
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{InputPin, OutputPin};
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+use crate::digital::OneWirePin;
+
+/// Read ROM command, reading the ROM of the only device on the bus.
+const READ_ROM: u8 = 0x33;
+/// Match ROM command, addressing a single device by its ROM.
+const MATCH_ROM: u8 = 0x55;
+/// Skip ROM command, addressing all the devices on the bus.
+const SKIP_ROM: u8 = 0xCC;
+/// Search ROM command, running one pass of the ROM search.
+const SEARCH_ROM: u8 = 0xF0;
+
+/// Error type for [`OneWire`] operations.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum OneWireError<E> {
+    /// Setting or reading the pin failed.
+    Pin(E),
+    /// No device answered the reset pulse, or took part in a ROM search.
+    NoPresence,
+    /// The CRC of a ROM read from the bus is invalid.
+    Crc,
+}
+
+impl<E> From<E> for OneWireError<E> {
+    #[inline]
+    fn from(error: E) -> Self {
+        Self::Pin(error)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for OneWireError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pin(e) => write!(f, "Pin error: {}", e),
+            Self::NoPresence => write!(f, "No device is present on the bus"),
+            Self::Crc => write!(f, "Invalid ROM CRC"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Display + Debug> std::error::Error for OneWireError<E> {}
+
+/// Computes the Dallas/Maxim CRC-8 of `data`, as used by 1-Wire ROMs and device memories.
+///
+/// The CRC of data followed by its own CRC is 0.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::one_wire::crc8;
+///
+/// // The ROM example of Maxim application note 27: family code, serial number, then CRC.
+/// let rom = [0x02, 0x1C, 0xB8, 0x01, 0x00, 0x00, 0x00, 0xA2];
+/// assert_eq!(crc8(&rom[..7]), rom[7]);
+/// assert_eq!(crc8(&rom), 0);
+/// ```
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                crc >> 1 ^ 0x8C
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// 1-Wire bus master, over a [`OneWirePin`].
+///
+/// This implements the reset and presence sequence, reading and writing bytes, and the ROM
+/// commands addressing devices, with the standard speed timing.
+///
+/// ROMs are represented as `u64`s in the order they are sent on the bus, least significant bit
+/// first: the family code is the least significant byte, and the CRC the most significant one.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::digital::OneWirePin;
+/// use embedded_hal_bus::one_wire::OneWire;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{delay, digital};
+/// # struct Pin;
+/// # impl digital::ErrorType for Pin { type Error = Infallible; }
+/// # impl digital::OutputPin for Pin {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// # impl digital::InputPin for Pin {
+/// #     fn is_high(&self) -> Result<bool, Infallible> { Ok(true) }
+/// #     fn is_low(&self) -> Result<bool, Infallible> { Ok(false) }
+/// # }
+/// # struct Delay;
+/// # impl delay::DelayUs for Delay { fn delay_us(&mut self, _: u32) {} }
+/// let mut bus = OneWire::new(OneWirePin::new(Pin), Delay);
+///
+/// // Start a temperature conversion on every device found.
+/// let mut roms = Vec::new();
+/// for rom in bus.search() {
+///     roms.push(rom?);
+/// }
+/// for rom in roms {
+///     bus.match_rom(rom)?;
+///     bus.write_byte(0x44)?;
+/// }
+/// # Ok::<(), embedded_hal_bus::one_wire::OneWireError<Infallible>>(())
+/// ```
+pub struct OneWire<P, D> {
+    pin: OneWirePin<P>,
+    delay: D,
+}
+
+impl<P, D> OneWire<P, D> {
+    /// Create a new `OneWire` master.
+    #[inline]
+    pub fn new(pin: OneWirePin<P>, delay: D) -> Self {
+        Self { pin, delay }
+    }
+
+    /// Destroys the master, returning the pin and the delay.
+    #[inline]
+    pub fn into_inner(self) -> (OneWirePin<P>, D) {
+        (self.pin, self.delay)
+    }
+}
+
+impl<P, D, E> OneWire<P, D>
+where
+    P: OutputPin<Error = E> + InputPin<Error = E>,
+    D: DelayUs,
+{
+    /// Send a reset pulse, returning `true` if a device answered with a presence pulse.
+    #[inline]
+    pub fn reset(&mut self) -> Result<bool, E> {
+        self.pin.reset_pulse(&mut self.delay)
+    }
+
+    /// Send a reset pulse, failing with [`OneWireError::NoPresence`] if no device answered.
+    #[inline]
+    fn reset_present(&mut self) -> Result<(), OneWireError<E>> {
+        if self.reset()? {
+            Ok(())
+        } else {
+            Err(OneWireError::NoPresence)
+        }
+    }
+
+    /// Write a byte, least significant bit first.
+    #[inline]
+    pub fn write_byte(&mut self, byte: u8) -> Result<(), E> {
+        for i in 0..8 {
+            self.pin.write_bit(&mut self.delay, byte >> i & 1 != 0)?;
+        }
+        Ok(())
+    }
+
+    /// Read a byte, least significant bit first.
+    #[inline]
+    pub fn read_byte(&mut self) -> Result<u8, E> {
+        let mut byte = 0;
+        for i in 0..8 {
+            if self.pin.read_bit(&mut self.delay)? {
+                byte |= 1 << i;
+            }
+        }
+        Ok(byte)
+    }
+
+    /// Write all the bytes of `bytes`.
+    #[inline]
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), E> {
+        bytes.iter().try_for_each(|&byte| self.write_byte(byte))
+    }
+
+    /// Read bytes into `bytes`, filling it.
+    #[inline]
+    pub fn read_bytes(&mut self, bytes: &mut [u8]) -> Result<(), E> {
+        for byte in bytes {
+            *byte = self.read_byte()?;
+        }
+        Ok(())
+    }
+
+    /// Reset the bus and read the ROM of the only device on it.
+    ///
+    /// If several devices are on the bus, their ROMs collide, and this most likely fails with
+    /// [`OneWireError::Crc`]. Use [`search`](Self::search) instead.
+    #[inline]
+    pub fn read_rom(&mut self) -> Result<u64, OneWireError<E>> {
+        self.reset_present()?;
+        self.write_byte(READ_ROM)?;
+        let mut rom = [0; 8];
+        self.read_bytes(&mut rom)?;
+        if crc8(&rom) != 0 {
+            return Err(OneWireError::Crc);
+        }
+        Ok(u64::from_le_bytes(rom))
+    }
+
+    /// Reset the bus and address the device with the given ROM. The device function command
+    /// must follow.
+    #[inline]
+    pub fn match_rom(&mut self, rom: u64) -> Result<(), OneWireError<E>> {
+        self.reset_present()?;
+        self.write_byte(MATCH_ROM)?;
+        self.write_bytes(&rom.to_le_bytes())?;
+        Ok(())
+    }
+
+    /// Reset the bus and address all the devices on it. The device function command must follow.
+    #[inline]
+    pub fn skip_rom(&mut self) -> Result<(), OneWireError<E>> {
+        self.reset_present()?;
+        self.write_byte(SKIP_ROM)?;
+        Ok(())
+    }
+
+    /// Returns an iterator over the ROMs of all the devices on the bus, found with the ROM search
+    /// algorithm.
+    ///
+    /// Each ROM is found by a separate search pass, starting with a reset. The iterator ends
+    /// after the last device, or after the first error. It is empty if no device answers the
+    /// first reset.
+    #[inline]
+    pub fn search(&mut self) -> Search<'_, P, D> {
+        Search {
+            bus: self,
+            rom: 0,
+            last_discrepancy: 0,
+            done: false,
+        }
+    }
+}
+
+/// Iterator over the ROMs of the devices on a 1-Wire bus, returned by [`OneWire::search`].
+pub struct Search<'a, P, D> {
+    bus: &'a mut OneWire<P, D>,
+    /// ROM found by the previous pass.
+    rom: u64,
+    /// Position of the last bit of the previous pass where the zero branch was taken at a
+    /// discrepancy, starting at 1, or 0 if there was none.
+    last_discrepancy: u32,
+    done: bool,
+}
+
+impl<P, D, E> Search<'_, P, D>
+where
+    P: OutputPin<Error = E> + InputPin<Error = E>,
+    D: DelayUs,
+{
+    /// Runs one pass of the search, returning the ROM found, or `None` if no device is present.
+    fn next_rom(&mut self) -> Result<Option<u64>, OneWireError<E>> {
+        let bus = &mut *self.bus;
+        if !bus.reset()? {
+            return Ok(None);
+        }
+        bus.write_byte(SEARCH_ROM)?;
+
+        let mut last_zero = 0;
+        for bit_number in 1..=64 {
+            let mask = 1 << (bit_number - 1);
+            // All the devices still taking part send the bit, then its complement.
+            let bit = bus.pin.read_bit(&mut bus.delay)?;
+            let complement = bus.pin.read_bit(&mut bus.delay)?;
+            let direction = match (bit, complement) {
+                (true, true) => return Err(OneWireError::NoPresence),
+                (bit, complement) if bit != complement => bit,
+                // Discrepancy: devices with both values are taking part. Follow the previous
+                // pass up to its last discrepancy, take the one branch there, and the zero
+                // branch after it.
+                _ => {
+                    let direction = match bit_number.cmp(&self.last_discrepancy) {
+                        core::cmp::Ordering::Less => self.rom & mask != 0,
+                        core::cmp::Ordering::Equal => true,
+                        core::cmp::Ordering::Greater => false,
+                    };
+                    if !direction {
+                        last_zero = bit_number;
+                    }
+                    direction
+                }
+            };
+            if direction {
+                self.rom |= mask;
+            } else {
+                self.rom &= !mask;
+            }
+            // Devices whose bit differs stop taking part.
+            bus.pin.write_bit(&mut bus.delay, direction)?;
+        }
+
+        self.last_discrepancy = last_zero;
+        if crc8(&self.rom.to_le_bytes()) != 0 {
+            return Err(OneWireError::Crc);
+        }
+        Ok(Some(self.rom))
+    }
+}
+
+impl<P, D, E> Iterator for Search<'_, P, D>
+where
+    P: OutputPin<Error = E> + InputPin<Error = E>,
+    D: DelayUs,
+{
+    type Item = Result<u64, OneWireError<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let res = self.next_rom().transpose();
+        // The search is over after the last device, or after an error.
+        self.done = self.last_discrepancy == 0 || !matches!(res, Some(Ok(_)));
+        res
+    }
+}
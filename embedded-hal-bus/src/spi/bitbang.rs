@@ -0,0 +1,405 @@
+//! Software SPI bus over three GPIO pins.
+
+use core::fmt::{self, Debug};
+use core::marker::PhantomData;
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{InputPin, OutputPin, PinState};
+use embedded_hal::spi::{
+    Error, ErrorKind, ErrorType, Mode, Phase, Polarity, SpiBus, MODE_0, MODE_1, MODE_2, MODE_3,
+};
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+use super::{BitOrder, ConfigurableDevice, SpiConfig};
+
+/// Error type for [`BitBangSpi`] operations.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum BitBangSpiError<E> {
+    /// Setting SCK or MOSI, or reading MISO failed.
+    Pin(E),
+    /// The requested clock frequency is 0.
+    InvalidFrequency,
+    /// The requested mode isn't the mode of the bus type.
+    UnsupportedMode,
+}
+
+impl<E: Debug> Error for BitBangSpiError<E> {
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for BitBangSpiError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pin(e) => write!(f, "Pin error: {}", e),
+            Self::InvalidFrequency => f.write_str("Invalid clock frequency"),
+            Self::UnsupportedMode => f.write_str("Unsupported SPI mode"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Display + Debug> std::error::Error for BitBangSpiError<E> {}
+
+/// SPI mode of a [`BitBangSpi`], given at the type level.
+///
+/// Implemented by the [`Mode0`], [`Mode1`], [`Mode2`] and [`Mode3`] markers. Since the mode is a
+/// constant, the clock polarity and phase checks of each bit are resolved at compile time.
+pub trait BitBangMode {
+    /// Clock polarity and phase of the mode.
+    const MODE: Mode;
+}
+
+/// SPI mode 0 marker for [`BitBangSpi`]: SCK idle low, data captured on the rising edge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Mode0;
+
+/// SPI mode 1 marker for [`BitBangSpi`]: SCK idle low, data captured on the falling edge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Mode1;
+
+/// SPI mode 2 marker for [`BitBangSpi`]: SCK idle high, data captured on the falling edge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Mode2;
+
+/// SPI mode 3 marker for [`BitBangSpi`]: SCK idle high, data captured on the rising edge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Mode3;
+
+impl BitBangMode for Mode0 {
+    const MODE: Mode = MODE_0;
+}
+
+impl BitBangMode for Mode1 {
+    const MODE: Mode = MODE_1;
+}
+
+impl BitBangMode for Mode2 {
+    const MODE: Mode = MODE_2;
+}
+
+impl BitBangMode for Mode3 {
+    const MODE: Mode = MODE_3;
+}
+
+/// [`BitBangSpi`] in SPI mode 0.
+pub type BitBangSpiMode0<SCK, MOSI, MISO, D> = BitBangSpi<SCK, MOSI, MISO, D, Mode0>;
+/// [`BitBangSpi`] in SPI mode 1.
+pub type BitBangSpiMode1<SCK, MOSI, MISO, D> = BitBangSpi<SCK, MOSI, MISO, D, Mode1>;
+/// [`BitBangSpi`] in SPI mode 2.
+pub type BitBangSpiMode2<SCK, MOSI, MISO, D> = BitBangSpi<SCK, MOSI, MISO, D, Mode2>;
+/// [`BitBangSpi`] in SPI mode 3.
+pub type BitBangSpiMode3<SCK, MOSI, MISO, D> = BitBangSpi<SCK, MOSI, MISO, D, Mode3>;
+
+/// [`SpiBus`] implemented in software, by toggling the SCK and MOSI pins and reading MISO.
+///
+/// The SPI mode is the type parameter `M`, one of [`Mode0`] to [`Mode3`], so that each mode is
+/// compiled separately without checking the clock polarity and phase at runtime. The
+/// [`BitBangSpiMode0`] to [`BitBangSpiMode3`] aliases name the bus of each mode.
+///
+/// The clock frequency and bit order are given on creation, and can be changed with
+/// [`ConfigurableDevice::set_config`], which rejects any other mode than `M`. Each half clock
+/// period is a delay of `500_000 / frequency_hz` µs, rounded up, so the clock never runs faster
+/// than requested. Words written during reads are 0x00.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::{SpiBus, MODE_0, MODE_3};
+/// use embedded_hal_bus::spi::{
+///     BitBangSpiError, BitBangSpiMode0, BitOrder, ConfigurableDevice, SpiConfig,
+/// };
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{delay, digital};
+/// # struct Pin;
+/// # impl digital::ErrorType for Pin { type Error = Infallible; }
+/// # impl digital::OutputPin for Pin {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// # impl digital::InputPin for Pin {
+/// #     fn is_high(&self) -> Result<bool, Infallible> { Ok(false) }
+/// #     fn is_low(&self) -> Result<bool, Infallible> { Ok(true) }
+/// # }
+/// # struct Delay;
+/// # impl delay::DelayUs for Delay { fn delay_us(&mut self, _: u32) {} }
+/// let mut spi = BitBangSpiMode0::new(Pin, Pin, Pin, Delay, 100_000, BitOrder::MsbFirst);
+/// spi.write(&[0x9F]).unwrap();
+///
+/// let config = SpiConfig {
+///     mode: MODE_0,
+///     frequency_hz: 1_000_000,
+///     bit_order: BitOrder::LsbFirst,
+/// };
+/// spi.set_config(config).unwrap();
+/// assert_eq!(spi.config().frequency_hz, 1_000_000);
+///
+/// let res = spi.set_config(SpiConfig { mode: MODE_3, ..config });
+/// assert_eq!(res, Err(BitBangSpiError::UnsupportedMode));
+/// ```
+pub struct BitBangSpi<SCK, MOSI, MISO, D, M> {
+    sck: SCK,
+    mosi: MOSI,
+    miso: MISO,
+    delay: D,
+    config: SpiConfig,
+    half_period_us: u32,
+    _mode: PhantomData<M>,
+}
+
+impl<SCK, MOSI, MISO, D, M: BitBangMode> BitBangSpi<SCK, MOSI, MISO, D, M> {
+    /// Create a new `BitBangSpi` with the given clock frequency and bit order.
+    ///
+    /// SCK must already be at its idle level for the mode `M`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frequency_hz` is 0.
+    #[inline]
+    pub fn new(
+        sck: SCK,
+        mosi: MOSI,
+        miso: MISO,
+        delay: D,
+        frequency_hz: u32,
+        bit_order: BitOrder,
+    ) -> Self {
+        Self {
+            sck,
+            mosi,
+            miso,
+            delay,
+            config: SpiConfig {
+                mode: M::MODE,
+                frequency_hz,
+                bit_order,
+            },
+            half_period_us: half_period_us(frequency_hz).expect("frequency must not be 0"),
+            _mode: PhantomData,
+        }
+    }
+}
+
+impl<SCK, MOSI, MISO, D, M> BitBangSpi<SCK, MOSI, MISO, D, M> {
+    /// Returns the current configuration: the mode `M`, and the frequency and bit order given to
+    /// [`new`](Self::new) or the last successful [`set_config`](ConfigurableDevice::set_config).
+    #[inline]
+    pub fn config(&self) -> &SpiConfig {
+        &self.config
+    }
+
+    /// Destroys the bus, returning the SCK, MOSI and MISO pins and the delay.
+    #[inline]
+    pub fn into_inner(self) -> (SCK, MOSI, MISO, D) {
+        (self.sck, self.mosi, self.miso, self.delay)
+    }
+
+    /// Swaps the roles of the MOSI and MISO pins, to work around a board with the two lines
+    /// crossed.
+    ///
+    /// The MISO pin becomes the output, and the MOSI pin the input, so all operations see the
+    /// device as if it was wired correctly. This requires pins that can be used in both
+    /// directions, such as open-drain pins, whose output must be released high to be read. With
+    /// a hardware SPI peripheral, the directions of the lines are fixed, and crossed lines can't
+    /// be fixed in software.
+    ///
+    /// This is a method of the bit-banged bus rather than a `SwapMisoMosi<B>` wrapper over any
+    /// `SpiBus`: a wrapper only sees the words of each operation, not the lines they are clocked
+    /// on, so it can't route data to the other line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal::spi::SpiBus;
+    /// use embedded_hal_bus::spi::{BitBangSpiMode0, BitOrder};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{delay, digital};
+    /// # struct Pin;
+    /// # impl digital::ErrorType for Pin { type Error = Infallible; }
+    /// # impl digital::OutputPin for Pin {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # impl digital::InputPin for Pin {
+    /// #     fn is_high(&self) -> Result<bool, Infallible> { Ok(false) }
+    /// #     fn is_low(&self) -> Result<bool, Infallible> { Ok(true) }
+    /// # }
+    /// # struct Delay;
+    /// # impl delay::DelayUs for Delay { fn delay_us(&mut self, _: u32) {} }
+    /// # let (sck, mosi, miso) = (Pin, Pin, Pin);
+    /// // `mosi` is wired to the data output of the device, and `miso` to its data input.
+    /// let spi = BitBangSpiMode0::new(sck, mosi, miso, Delay, 100_000, BitOrder::MsbFirst);
+    /// let mut spi = spi.swap_data_pins();
+    /// spi.write(&[0x9F]).unwrap();
+    /// ```
+    #[inline]
+    pub fn swap_data_pins(self) -> BitBangSpi<SCK, MISO, MOSI, D, M> {
+        BitBangSpi {
+            sck: self.sck,
+            mosi: self.miso,
+            miso: self.mosi,
+            delay: self.delay,
+            config: self.config,
+            half_period_us: self.half_period_us,
+            _mode: PhantomData,
+        }
+    }
+}
+
+/// Returns the half clock period for `frequency_hz`, rounded up, or `None` for 0.
+#[inline]
+// `u32::div_ceil` is newer than the MSRV.
+#[allow(clippy::manual_div_ceil)]
+fn half_period_us(frequency_hz: u32) -> Option<u32> {
+    match frequency_hz {
+        0 => None,
+        f => Some((500_000 + f - 1) / f),
+    }
+}
+
+impl<SCK, MOSI, MISO, D, M, E> BitBangSpi<SCK, MOSI, MISO, D, M>
+where
+    SCK: OutputPin<Error = E>,
+    MOSI: OutputPin<Error = E>,
+    MISO: InputPin<Error = E>,
+    D: DelayUs,
+    M: BitBangMode,
+{
+    #[inline]
+    fn wait(&mut self) {
+        self.delay.delay_us(self.half_period_us);
+    }
+
+    /// Sets SCK to its idle level if `idle` is `true`, or to its active level otherwise.
+    #[inline]
+    fn set_sck(&mut self, idle: bool) -> Result<(), E> {
+        let idle_high = M::MODE.polarity == Polarity::IdleHigh;
+        self.sck.set_state(PinState::from(idle == idle_high))
+    }
+
+    /// Shifts out `bit` while shifting in the returned one.
+    fn transfer_bit(&mut self, bit: bool) -> Result<bool, E> {
+        match M::MODE.phase {
+            Phase::CaptureOnFirstTransition => {
+                self.mosi.set_state(PinState::from(bit))?;
+                self.wait();
+                self.set_sck(false)?;
+                let read = self.miso.is_high()?;
+                self.wait();
+                self.set_sck(true)?;
+                Ok(read)
+            }
+            Phase::CaptureOnSecondTransition => {
+                self.set_sck(false)?;
+                self.mosi.set_state(PinState::from(bit))?;
+                self.wait();
+                self.set_sck(true)?;
+                let read = self.miso.is_high()?;
+                self.wait();
+                Ok(read)
+            }
+        }
+    }
+
+    /// Shifts out `word` while shifting in the returned one.
+    fn transfer_word(&mut self, word: u8) -> Result<u8, BitBangSpiError<E>> {
+        let mut read = 0;
+        for i in 0..8 {
+            let mask = match self.config.bit_order {
+                BitOrder::MsbFirst => 0x80 >> i,
+                BitOrder::LsbFirst => 1 << i,
+            };
+            if self
+                .transfer_bit(word & mask != 0)
+                .map_err(BitBangSpiError::Pin)?
+            {
+                read |= mask;
+            }
+        }
+        Ok(read)
+    }
+}
+
+impl<SCK, MOSI, MISO, D, M, E> ErrorType for BitBangSpi<SCK, MOSI, MISO, D, M>
+where
+    SCK: OutputPin<Error = E>,
+    MOSI: OutputPin<Error = E>,
+    MISO: InputPin<Error = E>,
+    E: Debug,
+{
+    type Error = BitBangSpiError<E>;
+}
+
+impl<SCK, MOSI, MISO, D, M, E> SpiBus<u8> for BitBangSpi<SCK, MOSI, MISO, D, M>
+where
+    SCK: OutputPin<Error = E>,
+    MOSI: OutputPin<Error = E>,
+    MISO: InputPin<Error = E>,
+    D: DelayUs,
+    M: BitBangMode,
+    E: Debug,
+{
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        for word in words {
+            *word = self.transfer_word(0x00)?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        for &word in words {
+            self.transfer_word(word)?;
+        }
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        for i in 0..read.len().max(write.len()) {
+            let word = self.transfer_word(write.get(i).copied().unwrap_or(0x00))?;
+            if let Some(r) = read.get_mut(i) {
+                *r = word;
+            }
+        }
+        Ok(())
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        for word in words {
+            *word = self.transfer_word(*word)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<SCK, MOSI, MISO, D, M, E> ConfigurableDevice for BitBangSpi<SCK, MOSI, MISO, D, M>
+where
+    SCK: OutputPin<Error = E>,
+    MOSI: OutputPin<Error = E>,
+    MISO: InputPin<Error = E>,
+    D: DelayUs,
+    M: BitBangMode,
+    E: Debug,
+{
+    /// Applies the frequency and bit order of `config`.
+    ///
+    /// Returns [`BitBangSpiError::UnsupportedMode`] if its mode isn't `M`.
+    fn set_config(&mut self, config: SpiConfig) -> Result<(), Self::Error> {
+        if config.mode != M::MODE {
+            return Err(BitBangSpiError::UnsupportedMode);
+        }
+        self.half_period_us =
+            half_period_us(config.frequency_hz).ok_or(BitBangSpiError::InvalidFrequency)?;
+        self.config = config;
+        Ok(())
+    }
+}
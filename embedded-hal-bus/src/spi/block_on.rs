@@ -0,0 +1,112 @@
+//! Blocking SPI device adapter over an async one.
+
+use core::future::Future;
+use core::pin::Pin;
+
+use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+use embedded_hal_async::spi::SpiDevice as AsyncSpiDevice;
+
+/// Future type passed to the `block_on` function of [`BlockOn`].
+pub type TransactionFuture<'a, E> = Pin<&'a mut (dyn Future<Output = Result<(), E>> + 'a)>;
+
+/// Blocking [`SpiDevice`] adapter over an async [`SpiDevice`](AsyncSpiDevice).
+///
+/// Each transaction is run to completion by passing its future to the user-supplied `block_on`
+/// function, for example `futures::executor::block_on` or the equivalent function of the
+/// executor in use.
+///
+/// # Reentrancy
+///
+/// Blocking on a future stalls the calling context until the transaction is done, so:
+///
+/// - If the async device relies on an executor or an interrupt to make progress, for example to
+///   wake the future on DMA completion, that executor or interrupt must be able to run while
+///   blocked. Blocking from a task of that same executor, or from that interrupt, deadlocks.
+/// - Some `block_on` implementations panic when called from within another `block_on` or async
+///   runtime. Only use this adapter from blocking code.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::SpiDevice;
+/// use embedded_hal_bus::spi::BlockOn;
+/// # use core::convert::Infallible;
+/// # use embedded_hal_async::spi;
+/// # #[derive(Default)]
+/// # struct AsyncDevice { written: Vec<u8> }
+/// # impl spi::ErrorType for AsyncDevice { type Error = Infallible; }
+/// # impl spi::SpiDevice for AsyncDevice {
+/// #     async fn transaction(&mut self, operations: &mut [spi::Operation<'_, u8>]) -> Result<(), Infallible> {
+/// #         for op in operations {
+/// #             match op {
+/// #                 spi::Operation::Write(buf) => self.written.extend_from_slice(buf),
+/// #                 spi::Operation::Read(buf) => buf.fill(0x42),
+/// #                 _ => unimplemented!(),
+/// #             }
+/// #         }
+/// #         Ok(())
+/// #     }
+/// # }
+/// let mut device = BlockOn::new(AsyncDevice::default(), |fut| futures::executor::block_on(fut));
+///
+/// let mut buf = [0; 2];
+/// device.write(&[0x01, 0x02]).unwrap();
+/// device.read(&mut buf).unwrap();
+///
+/// assert_eq!(device.inner().written, [0x01, 0x02]);
+/// assert_eq!(buf, [0x42, 0x42]);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub struct BlockOn<D, F> {
+    device: D,
+    block_on: F,
+}
+
+impl<D, F> BlockOn<D, F>
+where
+    D: ErrorType,
+    F: FnMut(TransactionFuture<'_, D::Error>) -> Result<(), D::Error>,
+{
+    /// Create a new `BlockOn`, running the transactions of `device` with `block_on`.
+    #[inline]
+    pub fn new(device: D, block_on: F) -> Self {
+        Self { device, block_on }
+    }
+
+    /// Returns a reference to the inner device.
+    #[inline]
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Returns a mutable reference to the inner device.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+
+    /// Destroys the adapter, returning the inner device.
+    #[inline]
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+}
+
+impl<D, F> ErrorType for BlockOn<D, F>
+where
+    D: ErrorType,
+{
+    type Error = D::Error;
+}
+
+impl<Word: Copy + 'static, D, F> SpiDevice<Word> for BlockOn<D, F>
+where
+    D: AsyncSpiDevice<Word>,
+    F: FnMut(TransactionFuture<'_, D::Error>) -> Result<(), D::Error>,
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        let fut = core::pin::pin!(self.device.transaction(operations));
+        (self.block_on)(fut)
+    }
+}
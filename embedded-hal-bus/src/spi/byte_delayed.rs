@@ -0,0 +1,193 @@
+//! SPI bus adapter inserting a delay after each word.
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::spi::{ErrorType, SpiBus};
+
+/// [`SpiBus`] adapter that inserts a delay after each word transferred.
+///
+/// Every buffer is split into single-word transfers, and the bus is flushed and the delay
+/// executed after each of them. This is slow, but some links, such as opto-isolated ones, need a
+/// gap between consecutive words and not only between operations.
+///
+/// The adapter wraps the bus rather than the device, so that CS stays asserted for the whole
+/// transaction: use it as the bus of any of the [`SpiDevice`](embedded_hal::spi::SpiDevice)
+/// implementations in this crate. A delay of 0 disables the splitting entirely.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::SpiDevice;
+/// use embedded_hal_bus::spi::{ByteDelayed, ExclusiveDevice};
+/// # use core::cell::RefCell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{delay, digital, spi};
+/// # #[derive(Debug, PartialEq)]
+/// # enum Event { Wrote(u8), Waited(u32) }
+/// # struct Bus<'a>(&'a RefCell<Vec<Event>>);
+/// # impl spi::ErrorType for Bus<'_> { type Error = Infallible; }
+/// # impl spi::SpiBus for Bus<'_> {
+/// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+/// #         self.0.borrow_mut().extend(words.iter().map(|w| Event::Wrote(*w)));
+/// #         Ok(())
+/// #     }
+/// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// # struct Delay<'a>(&'a RefCell<Vec<Event>>);
+/// # impl delay::DelayUs for Delay<'_> {
+/// #     fn delay_us(&mut self, us: u32) { self.0.borrow_mut().push(Event::Waited(us)); }
+/// # }
+/// # struct Cs;
+/// # impl digital::ErrorType for Cs { type Error = Infallible; }
+/// # impl digital::OutputPin for Cs {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// let log = RefCell::new(Vec::new());
+/// let bus = ByteDelayed::new(Bus(&log), Delay(&log), 5);
+/// let mut device = ExclusiveDevice::new_no_delay(bus, Cs);
+///
+/// device.write(&[1, 2, 3, 4]).unwrap();
+///
+/// # use Event::*;
+/// assert_eq!(
+///     *log.borrow(),
+///     [Wrote(1), Waited(5), Wrote(2), Waited(5), Wrote(3), Waited(5), Wrote(4), Waited(5)]
+/// );
+/// ```
+pub struct ByteDelayed<BUS, D> {
+    bus: BUS,
+    delay: D,
+    delay_us: u32,
+}
+
+impl<BUS, D> ByteDelayed<BUS, D> {
+    /// Create a new `ByteDelayed`, waiting `delay_us` microseconds after each word.
+    #[inline]
+    pub fn new(bus: BUS, delay: D, delay_us: u32) -> Self {
+        Self {
+            bus,
+            delay,
+            delay_us,
+        }
+    }
+
+    /// Returns the delay after each word, in microseconds.
+    #[inline]
+    pub fn delay_us(&self) -> u32 {
+        self.delay_us
+    }
+
+    /// Sets the delay after each word, in microseconds. 0 disables the delay.
+    #[inline]
+    pub fn set_delay_us(&mut self, delay_us: u32) {
+        self.delay_us = delay_us;
+    }
+
+    /// Returns a reference to the underlying bus object.
+    #[inline]
+    pub fn bus(&self) -> &BUS {
+        &self.bus
+    }
+
+    /// Returns a mutable reference to the underlying bus object.
+    #[inline]
+    pub fn bus_mut(&mut self) -> &mut BUS {
+        &mut self.bus
+    }
+
+    /// Destroys the adapter, returning the bus and the delay.
+    #[inline]
+    pub fn into_inner(self) -> (BUS, D) {
+        (self.bus, self.delay)
+    }
+}
+
+impl<BUS, D> ByteDelayed<BUS, D>
+where
+    D: DelayUs,
+{
+    /// Flushes the bus and waits, after a single-word transfer.
+    #[inline]
+    fn gap<Word: Copy + 'static>(&mut self) -> Result<(), BUS::Error>
+    where
+        BUS: SpiBus<Word>,
+    {
+        self.bus.flush()?;
+        self.delay.delay_us(self.delay_us);
+        Ok(())
+    }
+}
+
+impl<BUS, D> ErrorType for ByteDelayed<BUS, D>
+where
+    BUS: ErrorType,
+{
+    type Error = BUS::Error;
+}
+
+impl<Word: Copy + 'static, BUS, D> SpiBus<Word> for ByteDelayed<BUS, D>
+where
+    BUS: SpiBus<Word>,
+    D: DelayUs,
+{
+    #[inline]
+    fn read(&mut self, words: &mut [Word]) -> Result<(), Self::Error> {
+        if self.delay_us == 0 {
+            return self.bus.read(words);
+        }
+        for word in words.chunks_mut(1) {
+            self.bus.read(word)?;
+            self.gap::<Word>()?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn write(&mut self, words: &[Word]) -> Result<(), Self::Error> {
+        if self.delay_us == 0 {
+            return self.bus.write(words);
+        }
+        for word in words.chunks(1) {
+            self.bus.write(word)?;
+            self.gap::<Word>()?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn transfer(&mut self, read: &mut [Word], write: &[Word]) -> Result<(), Self::Error> {
+        if self.delay_us == 0 {
+            return self.bus.transfer(read, write);
+        }
+        for i in 0..read.len().max(write.len()) {
+            match (read.get_mut(i..=i), write.get(i..=i)) {
+                (Some(r), Some(w)) => self.bus.transfer(r, w)?,
+                (Some(r), None) => self.bus.read(r)?,
+                (None, Some(w)) => self.bus.write(w)?,
+                (None, None) => unreachable!(),
+            }
+            self.gap::<Word>()?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn transfer_in_place(&mut self, words: &mut [Word]) -> Result<(), Self::Error> {
+        if self.delay_us == 0 {
+            return self.bus.transfer_in_place(words);
+        }
+        for word in words.chunks_mut(1) {
+            self.bus.transfer_in_place(word)?;
+            self.gap::<Word>()?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.bus.flush()
+    }
+}
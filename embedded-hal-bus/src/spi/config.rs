@@ -0,0 +1,98 @@
+//! Bus configuration requested by drivers.
+
+use embedded_hal::spi::{ErrorType, Mode};
+
+use crate::BitOrder;
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// SPI bus configuration, passed to [`ConfigurableDevice::set_config`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SpiConfig {
+    /// Clock polarity and phase.
+    pub mode: Mode,
+    /// Maximum clock frequency, in Hz. Implementations may run the clock slower, but not faster.
+    pub frequency_hz: u32,
+    /// Order in which the bits of each word are shifted.
+    pub bit_order: BitOrder,
+}
+
+/// SPI bus or device whose configuration can be changed at runtime.
+///
+/// [`SpiBus`](embedded_hal::spi::SpiBus) and [`SpiDevice`](embedded_hal::spi::SpiDevice) have
+/// no way to express the mode or frequency a device needs, which is usually left to the code
+/// creating the bus. HALs can implement this trait next to `SpiBus`, so that drivers can request
+/// their configuration, typically at initialization. [`ExclusiveDevice`](super::ExclusiveDevice)
+/// forwards it to its bus. Shared bus devices don't implement it, since the configuration would
+/// also apply to the other devices on the bus.
+///
+/// Buses that can't be reconfigured can implement [`FixedConfig`] instead, making `set_config`
+/// do nothing.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::{SpiDevice, MODE_3};
+/// use embedded_hal_bus::spi::{BitOrder, ConfigurableDevice, SpiConfig};
+///
+/// /// Driver of a device requiring SPI mode 3 at up to 2 MHz.
+/// struct Driver<SPI> {
+///     spi: SPI,
+/// }
+///
+/// impl<SPI: SpiDevice + ConfigurableDevice> Driver<SPI> {
+///     fn new(mut spi: SPI) -> Result<Self, SPI::Error> {
+///         spi.set_config(SpiConfig {
+///             mode: MODE_3,
+///             frequency_hz: 2_000_000,
+///             bit_order: BitOrder::MsbFirst,
+///         })?;
+///         Ok(Self { spi })
+///     }
+/// }
+/// ```
+pub trait ConfigurableDevice: ErrorType {
+    /// Configures the bus for the following transactions.
+    ///
+    /// Returns an error if the configuration isn't supported, for example if the frequency is
+    /// too low.
+    fn set_config(&mut self, config: SpiConfig) -> Result<(), Self::Error>;
+}
+
+/// Marker for SPI buses and devices whose configuration can't be changed at runtime.
+///
+/// Implementing it implements [`ConfigurableDevice`] with a `set_config` ignoring the requested
+/// configuration, so drivers requiring `ConfigurableDevice` can still be used. The configuration
+/// then has to be right from the start.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::{ErrorKind, ErrorType, MODE_0};
+/// use embedded_hal_bus::spi::{BitOrder, ConfigurableDevice, FixedConfig, SpiConfig};
+///
+/// struct FixedBus;
+///
+/// impl ErrorType for FixedBus {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl FixedConfig for FixedBus {}
+///
+/// let config = SpiConfig {
+///     mode: MODE_0,
+///     frequency_hz: 1_000_000,
+///     bit_order: BitOrder::MsbFirst,
+/// };
+/// assert_eq!(FixedBus.set_config(config), Ok(()));
+/// ```
+pub trait FixedConfig: ErrorType {}
+
+impl<T: FixedConfig> ConfigurableDevice for T {
+    #[inline]
+    fn set_config(&mut self, _config: SpiConfig) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
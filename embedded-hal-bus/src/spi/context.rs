@@ -0,0 +1,145 @@
+//! Errors with the context of the failed operation.
+
+use core::fmt::{self, Debug};
+
+use embedded_hal::spi::{Error, ErrorKind, Operation};
+
+use super::DeviceError;
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// Kind of an SPI [`Operation`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[must_use]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum OperationKind {
+    /// [`Operation::Read`].
+    Read,
+    /// [`Operation::Write`].
+    Write,
+    /// [`Operation::Transfer`].
+    Transfer,
+    /// [`Operation::TransferInPlace`].
+    TransferInPlace,
+    /// [`Operation::DelayUs`].
+    DelayUs,
+}
+
+impl OperationKind {
+    /// Returns the kind of `operation`.
+    #[inline]
+    pub fn of<Word>(operation: &Operation<'_, Word>) -> Self {
+        match operation {
+            Operation::Read(_) => Self::Read,
+            Operation::Write(_) => Self::Write,
+            Operation::Transfer(_, _) => Self::Transfer,
+            Operation::TransferInPlace(_) => Self::TransferInPlace,
+            Operation::DelayUs(_) => Self::DelayUs,
+        }
+    }
+}
+
+/// [`DeviceError`] with the index and kind of the operation that failed, if any.
+///
+/// Returned by the `transaction_with_context` methods of the `SpiDevice` implementations, so that
+/// logged errors tell where in the transaction they happened. Errors happening outside of an
+/// operation, such as CS errors or an error flushing the bus at the end of the transaction, have
+/// no operation.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[must_use]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct TransactionError<BUS, CS> {
+    error: DeviceError<BUS, CS>,
+    operation: Option<(usize, OperationKind)>,
+}
+
+impl<BUS, CS> TransactionError<BUS, CS> {
+    /// Create a new `TransactionError`, for a failure of the operation at `index` of `operations`.
+    ///
+    /// `index` is `None` if the failure didn't happen in any of the operations.
+    #[inline]
+    pub(super) fn new<Word>(
+        error: DeviceError<BUS, CS>,
+        index: Option<usize>,
+        operations: &[Operation<'_, Word>],
+    ) -> Self {
+        Self {
+            error,
+            operation: index.map(|i| (i, OperationKind::of(&operations[i]))),
+        }
+    }
+
+    /// Returns the error.
+    #[inline]
+    pub fn error(&self) -> &DeviceError<BUS, CS> {
+        &self.error
+    }
+
+    /// Returns the index of the operation that failed, if the error happened in one.
+    #[inline]
+    pub fn operation_index(&self) -> Option<usize> {
+        self.operation.map(|(index, _)| index)
+    }
+
+    /// Returns the kind of the operation that failed, if the error happened in one.
+    #[inline]
+    pub fn operation_kind(&self) -> Option<OperationKind> {
+        self.operation.map(|(_, kind)| kind)
+    }
+
+    /// Returns the error, discarding the context.
+    #[inline]
+    pub fn into_inner(self) -> DeviceError<BUS, CS> {
+        self.error
+    }
+}
+
+/// The error happened outside of any operation.
+impl<BUS, CS> From<DeviceError<BUS, CS>> for TransactionError<BUS, CS> {
+    #[inline]
+    fn from(error: DeviceError<BUS, CS>) -> Self {
+        Self {
+            error,
+            operation: None,
+        }
+    }
+}
+
+impl<BUS, CS> Error for TransactionError<BUS, CS>
+where
+    BUS: Error + Debug,
+    CS: Debug,
+{
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        self.error.kind()
+    }
+}
+
+impl<BUS, CS> fmt::Display for TransactionError<BUS, CS>
+where
+    BUS: fmt::Display,
+    CS: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operation {
+            Some((index, kind)) => {
+                write!(f, "operation {} ({:?}) failed: {}", index, kind, self.error)
+            }
+            None => self.error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<BUS, CS> std::error::Error for TransactionError<BUS, CS>
+where
+    BUS: std::error::Error + 'static,
+    CS: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
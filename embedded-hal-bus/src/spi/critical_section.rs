@@ -4,7 +4,7 @@ use embedded_hal::delay::DelayUs;
 use embedded_hal::digital::OutputPin;
 use embedded_hal::spi::{ErrorType, Operation, SpiBus, SpiDevice};
 
-use super::DeviceError;
+use super::{DeviceError, DeviceStats, TransactionError};
 
 /// `critical-section`-based shared bus [`SpiDevice`] implementation.
 ///
@@ -20,13 +20,44 @@ pub struct CriticalSectionDevice<'a, BUS, CS, D> {
     bus: &'a Mutex<RefCell<BUS>>,
     cs: CS,
     delay: D,
+    max_transaction_bytes: Option<usize>,
+    stats: DeviceStats,
 }
 
 impl<'a, BUS, CS, D> CriticalSectionDevice<'a, BUS, CS, D> {
     /// Create a new ExclusiveDevice.
     #[inline]
     pub fn new(bus: &'a Mutex<RefCell<BUS>>, cs: CS, delay: D) -> Self {
-        Self { bus, cs, delay }
+        Self {
+            bus,
+            cs,
+            delay,
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
+        }
+    }
+
+    /// Reject transactions longer than `max` words with [`DeviceError::TooLong`].
+    ///
+    /// The length of a transaction is counted like [`operations_byte_count`](super::operations_byte_count)
+    /// does, in words (bytes for the usual 8-bit words). Longer transactions are rejected before
+    /// touching the bus or CS, which helps catching driver bugs that would otherwise stall the
+    /// bus with a huge transfer. By default, transactions are not limited.
+    #[inline]
+    pub fn with_max_transaction_bytes(mut self, max: usize) -> Self {
+        self.max_transaction_bytes = Some(max);
+        self
+    }
+
+    /// Returns the statistics of the transactions performed by this device.
+    ///
+    /// All the transactions of this device are counted, including the ones of helper methods such
+    /// as [`write_chunks`](super::SpiDeviceExt::write_chunks).
+    #[cfg(feature = "stats")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    #[inline]
+    pub fn stats(&self) -> super::Stats {
+        self.stats.get()
     }
 }
 
@@ -43,10 +74,50 @@ impl<'a, BUS, CS> CriticalSectionDevice<'a, BUS, CS, super::NoDelay> {
             bus,
             cs,
             delay: super::NoDelay,
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
         }
     }
 }
 
+impl<'a, BUS, CS, D> CriticalSectionDevice<'a, BUS, CS, D>
+where
+    CS: OutputPin,
+{
+    /// Perform a transaction, returning the index and kind of the failed operation on error.
+    ///
+    /// This is the same as [`transaction`](SpiDevice::transaction), but the returned
+    /// [`TransactionError`] also tells which operation failed, which makes logged errors more
+    /// informative.
+    #[inline]
+    pub fn transaction_with_context<Word: Copy + 'static>(
+        &mut self,
+        operations: &mut [Operation<'_, Word>],
+    ) -> Result<(), TransactionError<BUS::Error, CS::Error>>
+    where
+        BUS: SpiBus<Word>,
+        D: DelayUs,
+    {
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.stats.record(super::operations_len(operations), || {
+            super::check_len(operations, self.max_transaction_bytes)?;
+
+            let mut failed_at = None;
+            critical_section::with(|cs| {
+                let bus = &mut *self.bus.borrow_ref_mut(cs);
+
+                let delay = &mut self.delay;
+                let res = super::transaction_with(bus, &mut self.cs, |bus| {
+                    super::run_operations(bus, delay, operations, &mut failed_at)
+                });
+                res.map_err(|e| TransactionError::new(e, failed_at, operations))
+            })
+        })
+    }
+}
+
 impl<'a, BUS, CS, D> ErrorType for CriticalSectionDevice<'a, BUS, CS, D>
 where
     BUS: ErrorType,
@@ -55,6 +126,33 @@ where
     type Error = DeviceError<BUS::Error, CS::Error>;
 }
 
+impl<'a, BUS, CS, D> super::ext::sealed::Device for CriticalSectionDevice<'a, BUS, CS, D>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+    type Cs = CS;
+
+    #[inline]
+    fn max_transaction_bytes(&self) -> Option<usize> {
+        self.max_transaction_bytes
+    }
+
+    #[inline]
+    fn lock_bus<R>(
+        &mut self,
+        f: impl FnOnce(&mut BUS, &mut CS) -> Result<R, Self::Error>,
+    ) -> Result<R, Self::Error> {
+        critical_section::with(|cs| f(&mut self.bus.borrow_ref_mut(cs), &mut self.cs))
+    }
+
+    #[inline]
+    fn record<R>(&mut self, words: usize, res: Result<R, Self::Error>) -> Result<R, Self::Error> {
+        self.stats.record(words, || res)
+    }
+}
+
 impl<'a, Word: Copy + 'static, BUS, CS, D> SpiDevice<Word> for CriticalSectionDevice<'a, BUS, CS, D>
 where
     BUS: SpiBus<Word>,
@@ -63,32 +161,40 @@ where
 {
     #[inline]
     fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
-        critical_section::with(|cs| {
-            let bus = &mut *self.bus.borrow_ref_mut(cs);
-
-            self.cs.set_low().map_err(DeviceError::Cs)?;
-
-            let op_res = operations.iter_mut().try_for_each(|op| match op {
-                Operation::Read(buf) => bus.read(buf),
-                Operation::Write(buf) => bus.write(buf),
-                Operation::Transfer(read, write) => bus.transfer(read, write),
-                Operation::TransferInPlace(buf) => bus.transfer_in_place(buf),
-                Operation::DelayUs(us) => {
-                    bus.flush()?;
-                    self.delay.delay_us(*us);
-                    Ok(())
-                }
-            });
-
-            // On failure, it's important to still flush and deassert CS.
-            let flush_res = bus.flush();
-            let cs_res = self.cs.set_high();
-
-            op_res.map_err(DeviceError::Spi)?;
-            flush_res.map_err(DeviceError::Spi)?;
-            cs_res.map_err(DeviceError::Cs)?;
-
-            Ok(())
+        // An empty transaction does nothing, without even asserting CS.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.stats.record(super::operations_len(operations), || {
+            super::check_len(operations, self.max_transaction_bytes)?;
+
+            critical_section::with(|cs| {
+                let bus = &mut *self.bus.borrow_ref_mut(cs);
+
+                self.cs.set_low().map_err(DeviceError::Cs)?;
+
+                let op_res = operations.iter_mut().try_for_each(|op| match op {
+                    Operation::Read(buf) => bus.read(buf),
+                    Operation::Write(buf) => bus.write(buf),
+                    Operation::Transfer(read, write) => bus.transfer(read, write),
+                    Operation::TransferInPlace(buf) => bus.transfer_in_place(buf),
+                    Operation::DelayUs(us) => {
+                        bus.flush()?;
+                        self.delay.delay_us(*us);
+                        Ok(())
+                    }
+                });
+
+                // On failure, it's important to still flush and deassert CS.
+                let flush_res = bus.flush();
+                let cs_res = self.cs.set_high();
+
+                op_res.map_err(DeviceError::Spi)?;
+                flush_res.map_err(DeviceError::Spi)?;
+                cs_res.map_err(DeviceError::Cs)?;
+
+                Ok(())
+            })
         })
     }
 }
@@ -0,0 +1,89 @@
+//! Compact debug formatting of SPI operations.
+
+use core::fmt::{self, Debug};
+
+use embedded_hal::spi::Operation;
+
+/// [`Debug`] formatting of a slice of [`Operation`]s, showing the written words in hex.
+///
+/// Each operation is printed compactly:
+///
+/// - [`Operation::Write`] as its words, `W[AA BB]`.
+/// - [`Operation::Read`] as its length, `R[..4..]`, since the buffer has yet to be read into.
+/// - [`Operation::Transfer`] as the written words and the read length, `X[AA BB/..2..]`.
+/// - [`Operation::TransferInPlace`] as the written words, `XI[AA BB]`.
+/// - [`Operation::DelayUs`] as its duration, `D(5us)`.
+///
+/// This is meant for logging transactions while debugging, for example from a wrapping
+/// [`SpiDevice`](embedded_hal::spi::SpiDevice).
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::Operation;
+/// use embedded_hal_bus::spi::DebugOperations;
+///
+/// let mut read = [0; 4];
+/// let mut transfer = [0; 2];
+/// let mut in_place = [0x01, 0x02];
+/// let operations = [
+///     Operation::Write(&[0xAA, 0xBB]),
+///     Operation::Read(&mut read),
+///     Operation::Transfer(&mut transfer, &[0x9F]),
+///     Operation::TransferInPlace(&mut in_place),
+///     Operation::DelayUs(5),
+/// ];
+///
+/// assert_eq!(
+///     format!("{:?}", DebugOperations(&operations)),
+///     "[W[AA BB], R[..4..], X[9F/..2..], XI[01 02], D(5us)]",
+/// );
+/// ```
+#[derive(Clone, Copy)]
+pub struct DebugOperations<'a>(pub &'a [Operation<'a, u8>]);
+
+impl Debug for DebugOperations<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(DebugOperation))
+            .finish()
+    }
+}
+
+/// A single operation of [`DebugOperations`].
+struct DebugOperation<'a, 'b>(&'b Operation<'a, u8>);
+
+impl Debug for DebugOperation<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Operation::Read(words) => write!(f, "R[..{}..]", words.len()),
+            Operation::Write(words) => {
+                f.write_str("W[")?;
+                hex(f, words)?;
+                f.write_str("]")
+            }
+            Operation::Transfer(read, write) => {
+                f.write_str("X[")?;
+                hex(f, write)?;
+                write!(f, "/..{}..]", read.len())
+            }
+            Operation::TransferInPlace(words) => {
+                f.write_str("XI[")?;
+                hex(f, words)?;
+                f.write_str("]")
+            }
+            Operation::DelayUs(us) => write!(f, "D({}us)", us),
+        }
+    }
+}
+
+/// Writes `words` in hex, separated by spaces.
+fn hex(f: &mut fmt::Formatter<'_>, words: &[u8]) -> fmt::Result {
+    for (i, word) in words.iter().enumerate() {
+        if i != 0 {
+            f.write_str(" ")?;
+        }
+        write!(f, "{:02X}", word)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! CS pins multiplexed through an address decoder.
+
+use core::cell::RefCell;
+
+use embedded_hal::digital::{ErrorType, OutputPin, PinState};
+
+/// Address decoder driving the CS lines of several SPI devices, such as a 74HC138.
+///
+/// The decoder has `BITS` address pins, selecting one of up to `2^BITS` outputs, and an enable
+/// pin: while enabled, the selected output is low, and all others are high. This matches the
+/// active-low CS of SPI devices, with one output wired to the CS of each device.
+///
+/// The enable pin is set high to enable the decoder. If it is active-low, wrap it in
+/// [`Inverted`](crate::digital::Inverted).
+///
+/// The decoder is shared by the [`DecodedCs`] pins of the devices through a `RefCell`, like
+/// [`RefCellDevice`](super::RefCellDevice) shares a bus, so it can only be used within a single
+/// thread.
+pub struct CsDecoder<P, E, const BITS: usize> {
+    address: [P; BITS],
+    enable: E,
+}
+
+impl<P, E, const BITS: usize> CsDecoder<P, E, BITS> {
+    /// Create a new `CsDecoder`, from its address pins, least significant first, and its enable
+    /// pin.
+    ///
+    /// The decoder must already be disabled, otherwise a device is selected before the first
+    /// transaction starts.
+    #[inline]
+    pub fn new(address: [P; BITS], enable: E) -> Self {
+        Self { address, enable }
+    }
+
+    /// Destroys the decoder, returning its address and enable pins.
+    #[inline]
+    pub fn into_inner(self) -> ([P; BITS], E) {
+        (self.address, self.enable)
+    }
+}
+
+/// CS pin of a device behind a [`CsDecoder`].
+///
+/// Setting the pin low (asserting CS) writes the channel of the device to the address pins of the
+/// decoder, then enables it. Setting it high (deasserting CS) disables the decoder. This can be
+/// used as the CS of any of the [`SpiDevice`](embedded_hal::spi::SpiDevice) implementations in
+/// this crate.
+///
+/// # Examples
+///
+/// ```
+/// use core::cell::RefCell;
+/// use embedded_hal::spi::SpiDevice;
+/// use embedded_hal_bus::spi::{CsDecoder, DecodedCs, RefCellDevice};
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{digital, spi};
+/// # struct Bus;
+/// # impl spi::ErrorType for Bus { type Error = Infallible; }
+/// # impl spi::SpiBus for Bus {
+/// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     fn write(&mut self, _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// # #[derive(Default)]
+/// # struct Pin { high: bool }
+/// # impl digital::ErrorType for Pin { type Error = Infallible; }
+/// # impl digital::OutputPin for Pin {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { self.high = false; Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { self.high = true; Ok(()) }
+/// # }
+/// // A 3-to-8 decoder, selecting the CS of up to 8 devices.
+/// let address = [Pin::default(), Pin::default(), Pin::default()];
+/// let decoder = RefCell::new(CsDecoder::new(address, Pin::default()));
+///
+/// let bus = RefCell::new(Bus);
+/// let mut flash = RefCellDevice::new_no_delay(&bus, DecodedCs::new(&decoder, 0));
+/// let mut sensor = RefCellDevice::new_no_delay(&bus, DecodedCs::new(&decoder, 5));
+///
+/// flash.write(&[0x06]).unwrap();
+/// sensor.write(&[0x80]).unwrap();
+///
+/// // The decoder is disabled, with the address of the last device selected.
+/// let (address, enable) = decoder.into_inner().into_inner();
+/// assert!(!enable.high);
+/// assert_eq!(address.map(|pin| pin.high), [true, false, true]);
+/// ```
+pub struct DecodedCs<'a, P, E, const BITS: usize> {
+    decoder: &'a RefCell<CsDecoder<P, E, BITS>>,
+    channel: usize,
+}
+
+impl<'a, P, E, const BITS: usize> DecodedCs<'a, P, E, BITS> {
+    /// Create a new `DecodedCs`, selecting the output `channel` of `decoder`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` doesn't fit in `BITS` bits.
+    #[inline]
+    pub fn new(decoder: &'a RefCell<CsDecoder<P, E, BITS>>, channel: usize) -> Self {
+        assert!(
+            BITS >= usize::BITS as usize || channel >> BITS == 0,
+            "channel {} doesn't fit in {} address bits",
+            channel,
+            BITS
+        );
+        Self { decoder, channel }
+    }
+
+    /// Returns the decoder output selected by this pin.
+    #[inline]
+    pub fn channel(&self) -> usize {
+        self.channel
+    }
+}
+
+impl<P, E, const BITS: usize> ErrorType for DecodedCs<'_, P, E, BITS>
+where
+    P: OutputPin,
+    E: OutputPin<Error = P::Error>,
+{
+    type Error = P::Error;
+}
+
+impl<P, E, const BITS: usize> OutputPin for DecodedCs<'_, P, E, BITS>
+where
+    P: OutputPin,
+    E: OutputPin<Error = P::Error>,
+{
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        let decoder = &mut *self.decoder.borrow_mut();
+        for (bit, pin) in decoder.address.iter_mut().enumerate() {
+            pin.set_state(PinState::from(self.channel >> bit & 1 != 0))?;
+        }
+        decoder.enable.set_high()
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.decoder.borrow_mut().enable.set_low()
+    }
+}
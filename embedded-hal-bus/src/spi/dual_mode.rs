@@ -0,0 +1,213 @@
+//! SPI device usable from both blocking and async code.
+
+use core::future::Future;
+use core::marker::PhantomData;
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::spi::{ErrorType, Operation, SpiBus, SpiDevice};
+use embedded_hal_async::{
+    delay::DelayUs as AsyncDelayUs,
+    spi::{SpiBus as AsyncSpiBus, SpiDevice as AsyncSpiDevice},
+};
+
+use super::{DeviceError, ExclusiveDevice};
+
+/// Mode of a [`DualMode`] device implementing the blocking [`SpiDevice`] trait.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Blocking;
+
+/// Mode of a [`DualMode`] device implementing the async [`SpiDevice`](AsyncSpiDevice) trait.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Async;
+
+/// [`SpiDevice`] with exclusive access to a bus, switching between blocking and async operation.
+///
+/// This is meant for buses implementing both the blocking and async [`SpiBus`] traits, when
+/// the same hardware is driven by blocking code during initialization, then by async code. The
+/// device starts in [`Blocking`] mode, and [`into_async`](DualMode::into_async) and
+/// [`into_blocking`](DualMode::into_blocking) switch between the modes without reconstructing
+/// it: the bus, CS pin and delay are kept as they are, and CS isn't toggled.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::SpiDevice;
+/// use embedded_hal_async::spi::SpiDevice as _;
+/// use embedded_hal_bus::spi::DualMode;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{digital, spi};
+/// # use embedded_hal_async::spi as async_spi;
+/// # #[derive(Default)]
+/// # struct Bus { written: Vec<u8> }
+/// # impl spi::ErrorType for Bus { type Error = Infallible; }
+/// # impl spi::SpiBus for Bus {
+/// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.written.extend_from_slice(words); Ok(()) }
+/// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// # impl async_spi::SpiBus for Bus {
+/// #     async fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.written.extend_from_slice(words); Ok(()) }
+/// #     async fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     async fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+/// #     async fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// # struct Cs;
+/// # impl digital::ErrorType for Cs { type Error = Infallible; }
+/// # impl digital::OutputPin for Cs {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// let mut device = DualMode::new_no_delay(Bus::default(), Cs);
+///
+/// // Blocking initialization at boot.
+/// device.write(&[0x01]).unwrap();
+///
+/// // Then async operation.
+/// let mut device = device.into_async();
+/// futures::executor::block_on(device.write(&[0x02])).unwrap();
+///
+/// assert_eq!(device.bus().written, [0x01, 0x02]);
+/// ```
+pub struct DualMode<BUS, CS, D, MODE = Blocking> {
+    device: ExclusiveDevice<BUS, CS, D>,
+    mode: PhantomData<MODE>,
+}
+
+impl<BUS, CS, D> DualMode<BUS, CS, D, Blocking> {
+    /// Create a new `DualMode` device, in blocking mode.
+    ///
+    /// CS must already be deasserted (high), like for [`ExclusiveDevice::new`].
+    #[inline]
+    pub fn new(bus: BUS, cs: CS, delay: D) -> Self {
+        Self::from_device(ExclusiveDevice::new(bus, cs, delay))
+    }
+
+    /// Switch to async mode.
+    #[inline]
+    pub fn into_async(self) -> DualMode<BUS, CS, D, Async> {
+        DualMode::from_device(self.device)
+    }
+}
+
+impl<BUS, CS> DualMode<BUS, CS, super::NoDelay, Blocking> {
+    /// Create a new `DualMode` device without support for in-transaction delays, in blocking mode.
+    ///
+    /// # Panics
+    ///
+    /// The returned device will panic if you try to execute a transaction
+    /// that contains any operations of type `Operation::DelayUs`.
+    #[inline]
+    pub fn new_no_delay(bus: BUS, cs: CS) -> Self {
+        Self::from_device(ExclusiveDevice::new_no_delay(bus, cs))
+    }
+}
+
+impl<BUS, CS, D> DualMode<BUS, CS, D, Async> {
+    /// Switch to blocking mode.
+    #[inline]
+    pub fn into_blocking(self) -> DualMode<BUS, CS, D, Blocking> {
+        DualMode::from_device(self.device)
+    }
+
+    /// Perform a transaction, cancelling it if it doesn't complete before `timeout`.
+    ///
+    /// See [`ExclusiveDevice::transaction_timeout`].
+    #[inline]
+    pub async fn transaction_timeout<Word: Copy + 'static, T>(
+        &mut self,
+        operations: &mut [Operation<'_, Word>],
+        timeout: T,
+    ) -> Result<(), DeviceError<BUS::Error, CS::Error>>
+    where
+        BUS: AsyncSpiBus<Word>,
+        CS: OutputPin,
+        D: AsyncDelayUs,
+        T: Future,
+    {
+        self.device.transaction_timeout(operations, timeout).await
+    }
+
+    /// Write `data` in chunks of `chunk` bytes, letting other tasks run between chunks.
+    ///
+    /// See [`ExclusiveDevice::write_chunked`].
+    #[inline]
+    pub async fn write_chunked(
+        &mut self,
+        data: &[u8],
+        chunk: usize,
+    ) -> Result<(), DeviceError<BUS::Error, CS::Error>>
+    where
+        BUS: AsyncSpiBus,
+        CS: OutputPin,
+    {
+        self.device.write_chunked(data, chunk).await
+    }
+}
+
+impl<BUS, CS, D, MODE> DualMode<BUS, CS, D, MODE> {
+    /// Wrap `device`, in the mode chosen by the caller.
+    #[inline]
+    pub fn from_device(device: ExclusiveDevice<BUS, CS, D>) -> Self {
+        Self {
+            device,
+            mode: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying bus object.
+    #[inline]
+    pub fn bus(&self) -> &BUS {
+        self.device.bus()
+    }
+
+    /// Returns a mutable reference to the underlying bus object.
+    #[inline]
+    pub fn bus_mut(&mut self) -> &mut BUS {
+        self.device.bus_mut()
+    }
+
+    /// Returns the underlying [`ExclusiveDevice`].
+    #[inline]
+    pub fn into_inner(self) -> ExclusiveDevice<BUS, CS, D> {
+        self.device
+    }
+}
+
+impl<BUS, CS, D, MODE> ErrorType for DualMode<BUS, CS, D, MODE>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Error = DeviceError<BUS::Error, CS::Error>;
+}
+
+impl<Word: Copy + 'static, BUS, CS, D> SpiDevice<Word> for DualMode<BUS, CS, D, Blocking>
+where
+    BUS: SpiBus<Word>,
+    CS: OutputPin,
+    D: DelayUs,
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        SpiDevice::transaction(&mut self.device, operations)
+    }
+}
+
+impl<Word: Copy + 'static, BUS, CS, D> AsyncSpiDevice<Word> for DualMode<BUS, CS, D, Async>
+where
+    BUS: AsyncSpiBus<Word>,
+    CS: OutputPin,
+    D: AsyncDelayUs,
+{
+    #[inline]
+    async fn transaction(
+        &mut self,
+        operations: &mut [Operation<'_, Word>],
+    ) -> Result<(), Self::Error> {
+        AsyncSpiDevice::transaction(&mut self.device, operations).await
+    }
+}
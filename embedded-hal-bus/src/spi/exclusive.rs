@@ -2,6 +2,7 @@
 
 use embedded_hal::delay::DelayUs;
 use embedded_hal::digital::OutputPin;
+use embedded_hal::qspi::{Command, Data, QspiBus, QspiDevice};
 use embedded_hal::spi::{ErrorType, Operation, SpiBus, SpiDevice};
 #[cfg(feature = "async")]
 use embedded_hal_async::{
@@ -9,7 +10,15 @@ use embedded_hal_async::{
     spi::{SpiBus as AsyncSpiBus, SpiDevice as AsyncSpiDevice},
 };
 
-use super::DeviceError;
+#[cfg(feature = "async")]
+use super::ScopedTransaction;
+use super::{ConfigurableDevice, DeviceError, DeviceStats, SpiConfig, TransactionError};
+#[cfg(feature = "async")]
+use core::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
 
 /// [`SpiDevice`] implementation with exclusive access to the bus (not shared).
 ///
@@ -19,13 +28,80 @@ pub struct ExclusiveDevice<BUS, CS, D> {
     bus: BUS,
     cs: CS,
     delay: D,
+    max_transaction_bytes: Option<usize>,
+    stats: DeviceStats,
+    /// `false` if transactions containing delays are rejected.
+    has_delay: bool,
 }
 
 impl<BUS, CS, D> ExclusiveDevice<BUS, CS, D> {
     /// Create a new ExclusiveDevice.
+    ///
+    /// CS must already be deasserted (high), otherwise the device is selected before the first
+    /// transaction starts. See [`try_new`](Self::try_new) to deassert it on construction.
     #[inline]
     pub fn new(bus: BUS, cs: CS, delay: D) -> Self {
-        Self { bus, cs, delay }
+        Self {
+            bus,
+            cs,
+            delay,
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
+            has_delay: true,
+        }
+    }
+
+    /// Reject transactions longer than `max` words with [`DeviceError::TooLong`].
+    ///
+    /// The length of a transaction is counted like [`operations_byte_count`](super::operations_byte_count)
+    /// does, in words (bytes for the usual 8-bit words). Longer transactions are rejected before
+    /// touching the bus or CS, which helps catching driver bugs that would otherwise stall the
+    /// bus with a huge transfer. By default, transactions are not limited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal::spi::SpiDevice;
+    /// use embedded_hal_bus::spi::{DeviceError, ExclusiveDevice};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # struct Bus;
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { unreachable!() }
+    /// #     fn write(&mut self, _: &[u8]) -> Result<(), Infallible> { unreachable!() }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { unreachable!() }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { unreachable!() }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { unreachable!() }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { unreachable!() }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { unreachable!() }
+    /// # }
+    /// // The bus and CS panic if they're used.
+    /// let mut device = ExclusiveDevice::new_no_delay(Bus, Cs).with_max_transaction_bytes(256);
+    ///
+    /// assert_eq!(device.write(&[0; 257]), Err(DeviceError::TooLong));
+    /// ```
+    #[inline]
+    pub fn with_max_transaction_bytes(mut self, max: usize) -> Self {
+        self.max_transaction_bytes = Some(max);
+        self
+    }
+
+    /// Returns the statistics of the transactions performed by this device.
+    ///
+    /// All the transactions of this device are counted, including the ones of helper methods such
+    /// as [`write_chunks`](super::SpiDeviceExt::write_chunks).
+    /// The only exception is `transaction_scoped`, whose transactions are driven by the caller.
+    /// [`QspiDevice`] transactions count the bytes of their data phases.
+    #[cfg(feature = "stats")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    #[inline]
+    pub fn stats(&self) -> super::Stats {
+        self.stats.get()
     }
 
     /// Returns a reference to the underlying bus object.
@@ -47,17 +123,205 @@ impl<BUS, CS> ExclusiveDevice<BUS, CS, super::NoDelay> {
     /// # Panics
     ///
     /// The returned device will panic if you try to execute a transaction
-    /// that contains any operations of type `Operation::DelayUs`.
+    /// that contains any operations of type `Operation::DelayUs`. See
+    /// [`new_maybe_delay`](ExclusiveDevice::new_maybe_delay) to return an error instead.
     #[inline]
     pub fn new_no_delay(bus: BUS, cs: CS) -> Self {
         Self {
             bus,
             cs,
             delay: super::NoDelay,
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
+            has_delay: true,
+        }
+    }
+}
+
+impl<BUS, CS, D> ExclusiveDevice<BUS, CS, super::MaybeDelay<D>> {
+    /// Create a new ExclusiveDevice, with or without support for in-transaction delays.
+    ///
+    /// With `Some` delay, this behaves like [`new`](ExclusiveDevice::new). With `None`, it
+    /// behaves like [`new_no_delay`](ExclusiveDevice::new_no_delay), except that transactions
+    /// containing any `Operation::DelayUs` fail with [`DeviceError::DelayUnsupported`] instead
+    /// of panicking. They are rejected before touching the bus or CS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal::spi::{Operation, SpiDevice};
+    /// use embedded_hal_bus::spi::{DeviceError, ExclusiveDevice};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{delay, digital, spi};
+    /// # struct Bus;
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn write(&mut self, _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Delay;
+    /// # impl delay::DelayUs for Delay { fn delay_us(&mut self, _: u32) {} }
+    /// let delay: Option<Delay> = None;
+    /// let mut device = ExclusiveDevice::new_maybe_delay(Bus, Cs, delay);
+    ///
+    /// // Transactions without delays work.
+    /// device.write(&[0x01, 0x02]).unwrap();
+    ///
+    /// let res = device.transaction(&mut [Operation::Write(&[0x01]), Operation::DelayUs(10)]);
+    /// assert_eq!(res, Err(DeviceError::DelayUnsupported));
+    /// ```
+    #[inline]
+    pub fn new_maybe_delay(bus: BUS, cs: CS, delay: Option<D>) -> Self {
+        Self {
+            bus,
+            cs,
+            has_delay: delay.is_some(),
+            delay: super::MaybeDelay(delay),
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
         }
     }
 }
 
+impl<BUS, CS, D> ExclusiveDevice<BUS, CS, D>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    /// Create a new ExclusiveDevice, deasserting CS first.
+    ///
+    /// This sets CS high, so the device starts deselected whatever the initial state of the pin,
+    /// and returns an error if this fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal::digital::PinState;
+    /// use embedded_hal_bus::spi::ExclusiveDevice;
+    /// # use core::cell::Cell;
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # struct Bus;
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # struct Cs<'a>(&'a Cell<PinState>);
+    /// # impl digital::ErrorType for Cs<'_> { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs<'_> {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(self.0.set(PinState::Low)) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(self.0.set(PinState::High)) }
+    /// # }
+    /// // CS was left asserted by a previous user.
+    /// let cs_state = Cell::new(PinState::Low);
+    ///
+    /// let _device = ExclusiveDevice::try_new(Bus, Cs(&cs_state), ()).unwrap();
+    ///
+    /// assert_eq!(cs_state.get(), PinState::High);
+    /// ```
+    #[inline]
+    pub fn try_new(
+        bus: BUS,
+        mut cs: CS,
+        delay: D,
+    ) -> Result<Self, DeviceError<BUS::Error, CS::Error>> {
+        cs.set_high().map_err(DeviceError::Cs)?;
+        Ok(Self::new(bus, cs, delay))
+    }
+}
+
+impl<BUS, CS, D> ExclusiveDevice<BUS, CS, D>
+where
+    CS: OutputPin,
+{
+    /// Perform a transaction, returning the index and kind of the failed operation on error.
+    ///
+    /// This is the same as [`transaction`](SpiDevice::transaction), but the returned
+    /// [`TransactionError`] also tells which operation failed, which makes logged errors more
+    /// informative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal::spi::{ErrorKind, Operation};
+    /// use embedded_hal_bus::spi::{DeviceError, ExclusiveDevice, OperationKind};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # struct Bus;
+    /// # impl spi::ErrorType for Bus { type Error = ErrorKind; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), ErrorKind> { Err(ErrorKind::Overrun) }
+    /// #     fn write(&mut self, _: &[u8]) -> Result<(), ErrorKind> { Ok(()) }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), ErrorKind> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), ErrorKind> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), ErrorKind> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// let mut device = ExclusiveDevice::new_no_delay(Bus, Cs);
+    ///
+    /// let mut buf = [0; 2];
+    /// let error = device
+    ///     .transaction_with_context(&mut [Operation::Write(&[0x01]), Operation::Read(&mut buf)])
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(error.operation_index(), Some(1));
+    /// assert_eq!(error.operation_kind(), Some(OperationKind::Read));
+    /// assert_eq!(error.error(), &DeviceError::Spi(ErrorKind::Overrun));
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "operation 1 (Read) failed: SPI bus error: The peripheral receive buffer was overrun"
+    /// );
+    /// ```
+    ///
+    /// Like the `Result` of a transaction, the `TransactionError` it contains must be used:
+    ///
+    /// ```compile_fail
+    /// #![deny(unused_must_use)]
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::spi::ErrorKind;
+    /// use embedded_hal_bus::spi::TransactionError;
+    ///
+    /// fn check(res: Result<(), TransactionError<ErrorKind, Infallible>>) {
+    ///     res.unwrap_err();
+    /// }
+    /// ```
+    #[inline]
+    pub fn transaction_with_context<Word: Copy + 'static>(
+        &mut self,
+        operations: &mut [Operation<'_, Word>],
+    ) -> Result<(), TransactionError<BUS::Error, CS::Error>>
+    where
+        BUS: SpiBus<Word>,
+        D: DelayUs,
+    {
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.stats.record(super::operations_len(operations), || {
+            super::check_len(operations, self.max_transaction_bytes)?;
+            super::check_delay(operations, self.has_delay)?;
+
+            let mut failed_at = None;
+            let delay = &mut self.delay;
+            let res = super::transaction_with(&mut self.bus, &mut self.cs, |bus| {
+                super::run_operations(bus, delay, operations, &mut failed_at)
+            });
+            res.map_err(|e| TransactionError::new(e, failed_at, operations))
+        })
+    }
+}
+
 impl<BUS, CS, D> ErrorType for ExclusiveDevice<BUS, CS, D>
 where
     BUS: ErrorType,
@@ -66,6 +330,45 @@ where
     type Error = DeviceError<BUS::Error, CS::Error>;
 }
 
+impl<BUS, CS, D> super::ext::sealed::Device for ExclusiveDevice<BUS, CS, D>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+    type Cs = CS;
+
+    #[inline]
+    fn max_transaction_bytes(&self) -> Option<usize> {
+        self.max_transaction_bytes
+    }
+
+    #[inline]
+    fn lock_bus<R>(
+        &mut self,
+        f: impl FnOnce(&mut BUS, &mut CS) -> Result<R, Self::Error>,
+    ) -> Result<R, Self::Error> {
+        f(&mut self.bus, &mut self.cs)
+    }
+
+    #[inline]
+    fn record<R>(&mut self, words: usize, res: Result<R, Self::Error>) -> Result<R, Self::Error> {
+        self.stats.record(words, || res)
+    }
+}
+
+impl<BUS, CS, D> ConfigurableDevice for ExclusiveDevice<BUS, CS, D>
+where
+    BUS: ConfigurableDevice,
+    CS: OutputPin,
+{
+    /// Configures the bus, with CS deasserted.
+    #[inline]
+    fn set_config(&mut self, config: SpiConfig) -> Result<(), Self::Error> {
+        self.bus.set_config(config).map_err(DeviceError::Spi)
+    }
+}
+
 impl<Word: Copy + 'static, BUS, CS, D> SpiDevice<Word> for ExclusiveDevice<BUS, CS, D>
 where
     BUS: SpiBus<Word>,
@@ -74,29 +377,386 @@ where
 {
     #[inline]
     fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
-        self.cs.set_low().map_err(DeviceError::Cs)?;
+        // An empty transaction does nothing, without even asserting CS.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.stats.record(super::operations_len(operations), || {
+            super::check_len(operations, self.max_transaction_bytes)?;
+            super::check_delay(operations, self.has_delay)?;
 
-        let op_res = operations.iter_mut().try_for_each(|op| match op {
-            Operation::Read(buf) => self.bus.read(buf),
-            Operation::Write(buf) => self.bus.write(buf),
-            Operation::Transfer(read, write) => self.bus.transfer(read, write),
-            Operation::TransferInPlace(buf) => self.bus.transfer_in_place(buf),
-            Operation::DelayUs(us) => {
-                self.bus.flush()?;
-                self.delay.delay_us(*us);
-                Ok(())
-            }
-        });
+            self.cs.set_low().map_err(DeviceError::Cs)?;
+
+            let op_res = operations.iter_mut().try_for_each(|op| match op {
+                Operation::Read(buf) => self.bus.read(buf),
+                Operation::Write(buf) => self.bus.write(buf),
+                Operation::Transfer(read, write) => self.bus.transfer(read, write),
+                Operation::TransferInPlace(buf) => self.bus.transfer_in_place(buf),
+                Operation::DelayUs(us) => {
+                    self.bus.flush()?;
+                    self.delay.delay_us(*us);
+                    Ok(())
+                }
+            });
+
+            // On failure, it's important to still flush and deassert CS.
+            let flush_res = self.bus.flush();
+            let cs_res = self.cs.set_high();
+
+            op_res.map_err(DeviceError::Spi)?;
+            flush_res.map_err(DeviceError::Spi)?;
+            cs_res.map_err(DeviceError::Cs)?;
+
+            Ok(())
+        })
+    }
+
+    // The single operation methods skip the operations loop of `transaction`, with the same
+    // behavior: the length check, then CS asserted, the bus call, a flush and CS deasserted.
+
+    #[inline]
+    fn read(&mut self, buf: &mut [Word]) -> Result<(), Self::Error> {
+        self.stats.record(buf.len(), || {
+            super::check_words(buf.len(), self.max_transaction_bytes)?;
+            super::transaction_with(&mut self.bus, &mut self.cs, |bus| bus.read(buf))
+        })
+    }
+
+    #[inline]
+    fn write(&mut self, buf: &[Word]) -> Result<(), Self::Error> {
+        self.stats.record(buf.len(), || {
+            super::check_words(buf.len(), self.max_transaction_bytes)?;
+            super::transaction_with(&mut self.bus, &mut self.cs, |bus| bus.write(buf))
+        })
+    }
+
+    #[inline]
+    fn transfer(&mut self, read: &mut [Word], write: &[Word]) -> Result<(), Self::Error> {
+        let len = read.len().max(write.len());
+        self.stats.record(len, || {
+            super::check_words(len, self.max_transaction_bytes)?;
+            super::transaction_with(&mut self.bus, &mut self.cs, |bus| bus.transfer(read, write))
+        })
+    }
+
+    #[inline]
+    fn transfer_in_place(&mut self, buf: &mut [Word]) -> Result<(), Self::Error> {
+        self.stats.record(buf.len(), || {
+            super::check_words(buf.len(), self.max_transaction_bytes)?;
+            super::transaction_with(&mut self.bus, &mut self.cs, |bus| {
+                bus.transfer_in_place(buf)
+            })
+        })
+    }
+}
+
+impl<BUS, CS, D> QspiDevice for ExclusiveDevice<BUS, CS, D>
+where
+    BUS: QspiBus,
+    CS: OutputPin,
+{
+    #[inline]
+    fn transaction(&mut self, commands: &mut [Command<'_>]) -> Result<(), Self::Error> {
+        // An empty transaction does nothing, without even asserting CS.
+        if commands.is_empty() {
+            return Ok(());
+        }
+        self.stats.record(data_len(commands), || {
+            self.cs.set_low().map_err(DeviceError::Cs)?;
+
+            let op_res = commands
+                .iter_mut()
+                .try_for_each(|command| self.bus.command(command));
 
-        // On failure, it's important to still flush and deassert CS.
-        let flush_res = self.bus.flush();
-        let cs_res = self.cs.set_high();
+            // On failure, it's important to still flush and deassert CS.
+            let flush_res = self.bus.flush();
+            let cs_res = self.cs.set_high();
 
-        op_res.map_err(DeviceError::Spi)?;
-        flush_res.map_err(DeviceError::Spi)?;
-        cs_res.map_err(DeviceError::Cs)?;
+            op_res.map_err(DeviceError::Spi)?;
+            flush_res.map_err(DeviceError::Spi)?;
+            cs_res.map_err(DeviceError::Cs)?;
 
-        Ok(())
+            Ok(())
+        })
+    }
+}
+
+/// Returns the number of bytes of the data phases of `commands`.
+#[inline]
+fn data_len(commands: &[Command<'_>]) -> usize {
+    commands
+        .iter()
+        .map(|command| match &command.data {
+            Data::None => 0,
+            Data::Read(buf, _) => buf.len(),
+            Data::Write(buf, _) => buf.len(),
+        })
+        .sum()
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<BUS, CS, D> ExclusiveDevice<BUS, CS, D>
+where
+    CS: OutputPin,
+{
+    /// Asynchronously wait until all previously started operations on the bus have completed.
+    ///
+    /// This only [flushes](AsyncSpiBus::flush) the bus. CS is not asserted or deasserted.
+    #[inline]
+    pub async fn wait_idle<Word: Copy + 'static>(
+        &mut self,
+    ) -> Result<(), DeviceError<BUS::Error, CS::Error>>
+    where
+        BUS: AsyncSpiBus<Word>,
+    {
+        self.bus.flush().await.map_err(DeviceError::Spi)
+    }
+
+    /// Assert CS and start a transaction giving direct access to the bus.
+    ///
+    /// This is more flexible than [`transaction`](AsyncSpiDevice::transaction) for drivers
+    /// implemented as state machines: any bus method can be called on the returned
+    /// [`ScopedTransaction`], with CS asserted until [`ScopedTransaction::finish`]. CS is
+    /// deasserted even if the transaction is dropped without finishing it, for example if the
+    /// future running it is cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_async::spi::SpiBus;
+    /// use embedded_hal_bus::spi::ExclusiveDevice;
+    /// # use core::cell::Cell;
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::digital;
+    /// # use embedded_hal_async::spi;
+    /// # use futures::FutureExt;
+    /// # #[derive(Default)]
+    /// # struct Bus { written: Vec<u8> }
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     async fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.written.extend_from_slice(words); Ok(()) }
+    /// #     async fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     async fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     async fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs<'a>(&'a Cell<bool>);
+    /// # impl digital::ErrorType for Cs<'_> { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs<'_> {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { self.0.set(false); Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { self.0.set(true); Ok(()) }
+    /// # }
+    /// # let cs_high = Cell::new(true);
+    /// let mut device = ExclusiveDevice::new_no_delay(Bus::default(), Cs(&cs_high));
+    ///
+    /// futures::executor::block_on(async {
+    ///     let mut transaction = device.transaction_scoped().unwrap();
+    ///     transaction.write(&[0x01]).await.unwrap();
+    ///     transaction.write(&[0x02, 0x03]).await.unwrap();
+    ///     transaction.finish().await.unwrap();
+    /// });
+    /// assert!(cs_high.get());
+    /// assert_eq!(device.bus().written, [0x01, 0x02, 0x03]);
+    ///
+    /// // Cancel a transaction while it's waiting.
+    /// let cancelled = async {
+    ///     let mut transaction = device.transaction_scoped().unwrap();
+    ///     transaction.write(&[0x04]).await.unwrap();
+    ///     core::future::pending::<()>().await;
+    /// }
+    /// .now_or_never();
+    /// assert!(cancelled.is_none());
+    /// assert!(cs_high.get());
+    /// ```
+    #[inline]
+    pub fn transaction_scoped(
+        &mut self,
+    ) -> Result<ScopedTransaction<'_, BUS, CS>, DeviceError<BUS::Error, CS::Error>>
+    where
+        BUS: ErrorType,
+    {
+        ScopedTransaction::new(&mut self.bus, &mut self.cs).map_err(DeviceError::Cs)
+    }
+
+    /// Perform a transaction, cancelling it if it doesn't complete before `timeout`.
+    ///
+    /// `timeout` is a future completing when the transaction should be cancelled, typically a
+    /// timer of the executor in use, such as `Timer::after(Duration::from_millis(10))`. It is
+    /// raced against the transaction, which returns [`DeviceError::Timeout`] if `timeout`
+    /// completes first.
+    ///
+    /// CS is deasserted when the transaction is cancelled, without flushing the bus. Like for
+    /// [`ScopedTransaction`], this also happens if the future returned by this method is
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_async::spi::Operation;
+    /// use embedded_hal_bus::spi::{DeviceError, ExclusiveDevice};
+    /// # use core::cell::Cell;
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::digital;
+    /// # use embedded_hal_async::spi;
+    /// # struct Bus;
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     async fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { core::future::pending().await }
+    /// #     async fn write(&mut self, _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     async fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     async fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     async fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs<'a>(&'a Cell<bool>);
+    /// # impl digital::ErrorType for Cs<'_> { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs<'_> {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { self.0.set(false); Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { self.0.set(true); Ok(()) }
+    /// # }
+    /// # let cs_high = Cell::new(true);
+    /// // Reads on this bus never complete.
+    /// let mut device = ExclusiveDevice::new_no_delay(Bus, Cs(&cs_high));
+    ///
+    /// // A timeout expiring immediately, and one never expiring.
+    /// let expired = async {};
+    /// let never = core::future::pending::<()>();
+    ///
+    /// futures::executor::block_on(async {
+    ///     let res = device
+    ///         .transaction_timeout(&mut [Operation::Write(&[0x01])], never)
+    ///         .await;
+    ///     assert_eq!(res, Ok(()));
+    ///
+    ///     let mut buf = [0; 2];
+    ///     let res = device
+    ///         .transaction_timeout(&mut [Operation::Read(&mut buf)], expired)
+    ///         .await;
+    ///     assert_eq!(res, Err(DeviceError::Timeout));
+    /// });
+    /// assert!(cs_high.get());
+    /// ```
+    #[inline]
+    pub async fn transaction_timeout<Word: Copy + 'static, T>(
+        &mut self,
+        operations: &mut [Operation<'_, Word>],
+        timeout: T,
+    ) -> Result<(), DeviceError<BUS::Error, CS::Error>>
+    where
+        BUS: AsyncSpiBus<Word>,
+        D: AsyncDelayUs,
+        T: Future,
+    {
+        // An empty transaction does nothing, without even asserting CS.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        let words = super::operations_len(operations);
+        let res = async {
+            super::check_len(operations, self.max_transaction_bytes)?;
+            super::check_delay(operations, self.has_delay)?;
+
+            let mut transaction =
+                ScopedTransaction::new(&mut self.bus, &mut self.cs).map_err(DeviceError::Cs)?;
+
+            let op_res = with_timeout(
+                run_operations(&mut *transaction, &mut self.delay, operations),
+                timeout,
+            )
+            .await;
+
+            // On timeout, dropping the transaction deasserts CS.
+            let op_res = op_res.ok_or(DeviceError::Timeout)?;
+
+            // On failure, it's important to still flush and deassert CS.
+            let finish_res = transaction.finish().await;
+
+            op_res.map_err(DeviceError::Spi)?;
+            finish_res
+        }
+        .await;
+        self.stats.record(words, || res)
+    }
+
+    /// Write `data` in chunks of `chunk` bytes, letting other tasks run between chunks.
+    ///
+    /// Writing a large buffer, such as a framebuffer, in a single operation can keep the executor
+    /// busy for as long as the bus takes to send it, if the bus completes writes without
+    /// suspending. This instead writes it one chunk at a time, yielding to the executor after
+    /// each chunk so other tasks run in between. The whole write is still a single transaction:
+    /// CS stays asserted across the yields, and is deasserted after the last chunk. Like for
+    /// [`ScopedTransaction`], it is also deasserted if the future returned by this method is
+    /// dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_bus::spi::ExclusiveDevice;
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::digital;
+    /// # use embedded_hal_async::spi;
+    /// # #[derive(Default)]
+    /// # struct Bus { writes: Vec<Vec<u8>> }
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     async fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.writes.push(words.to_vec()); Ok(()) }
+    /// #     async fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     async fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     async fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// let mut device = ExclusiveDevice::new_no_delay(Bus::default(), Cs);
+    ///
+    /// let framebuffer = [0xFF; 10];
+    /// futures::executor::block_on(device.write_chunked(&framebuffer, 4)).unwrap();
+    ///
+    /// assert_eq!(device.bus().writes, [&[0xFF; 4][..], &[0xFF; 4], &[0xFF; 2]]);
+    /// ```
+    #[inline]
+    pub async fn write_chunked(
+        &mut self,
+        data: &[u8],
+        chunk: usize,
+    ) -> Result<(), DeviceError<BUS::Error, CS::Error>>
+    where
+        BUS: AsyncSpiBus,
+    {
+        assert!(chunk > 0, "chunk size must be non-zero");
+        let res = async {
+            super::check_len(&[Operation::Write(data)], self.max_transaction_bytes)?;
+
+            let mut transaction =
+                ScopedTransaction::new(&mut self.bus, &mut self.cs).map_err(DeviceError::Cs)?;
+
+            let mut op_res = Ok(());
+            for (i, chunk) in data.chunks(chunk).enumerate() {
+                if i > 0 {
+                    yield_now().await;
+                }
+                op_res = transaction.write(chunk).await;
+                if op_res.is_err() {
+                    break;
+                }
+            }
+
+            // On failure, it's important to still flush and deassert CS.
+            let finish_res = transaction.finish::<u8>().await;
+
+            op_res.map_err(DeviceError::Spi)?;
+            finish_res
+        }
+        .await;
+        self.stats.record(data.len(), || res)
     }
 }
 
@@ -113,38 +773,87 @@ where
         &mut self,
         operations: &mut [Operation<'_, Word>],
     ) -> Result<(), Self::Error> {
-        self.cs.set_low().map_err(DeviceError::Cs)?;
-
-        let op_res = 'ops: {
-            for op in operations {
-                let res = match op {
-                    Operation::Read(buf) => self.bus.read(buf).await,
-                    Operation::Write(buf) => self.bus.write(buf).await,
-                    Operation::Transfer(read, write) => self.bus.transfer(read, write).await,
-                    Operation::TransferInPlace(buf) => self.bus.transfer_in_place(buf).await,
-                    Operation::DelayUs(us) => match self.bus.flush().await {
-                        Err(e) => Err(e),
-                        Ok(()) => {
-                            self.delay.delay_us(*us).await;
-                            Ok(())
-                        }
-                    },
-                };
-                if let Err(e) = res {
-                    break 'ops Err(e);
-                }
-            }
-            Ok(())
-        };
+        // An empty transaction does nothing, without even asserting CS.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        let words = super::operations_len(operations);
+        let res = async {
+            super::check_len(operations, self.max_transaction_bytes)?;
+            super::check_delay(operations, self.has_delay)?;
+
+            self.cs.set_low().map_err(DeviceError::Cs)?;
 
-        // On failure, it's important to still flush and deassert CS.
-        let flush_res = self.bus.flush().await;
-        let cs_res = self.cs.set_high();
+            let op_res = run_operations(&mut self.bus, &mut self.delay, operations).await;
 
-        op_res.map_err(DeviceError::Spi)?;
-        flush_res.map_err(DeviceError::Spi)?;
-        cs_res.map_err(DeviceError::Cs)?;
+            // On failure, it's important to still flush and deassert CS.
+            let flush_res = self.bus.flush().await;
+            let cs_res = self.cs.set_high();
 
-        Ok(())
+            op_res.map_err(DeviceError::Spi)?;
+            flush_res.map_err(DeviceError::Spi)?;
+            cs_res.map_err(DeviceError::Cs)?;
+
+            Ok(())
+        }
+        .await;
+        self.stats.record(words, || res)
     }
 }
+
+/// Runs `operations` on `bus`, stopping at the first error.
+#[cfg(feature = "async")]
+#[inline]
+async fn run_operations<Word: Copy + 'static, BUS, D>(
+    bus: &mut BUS,
+    delay: &mut D,
+    operations: &mut [Operation<'_, Word>],
+) -> Result<(), BUS::Error>
+where
+    BUS: AsyncSpiBus<Word>,
+    D: AsyncDelayUs,
+{
+    for op in operations {
+        match op {
+            Operation::Read(buf) => bus.read(buf).await?,
+            Operation::Write(buf) => bus.write(buf).await?,
+            Operation::Transfer(read, write) => bus.transfer(read, write).await?,
+            Operation::TransferInPlace(buf) => bus.transfer_in_place(buf).await?,
+            Operation::DelayUs(us) => {
+                bus.flush().await?;
+                delay.delay_us(*us).await;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns `Pending` once, after waking the task, so the executor can run other tasks.
+#[cfg(feature = "async")]
+#[inline]
+async fn yield_now() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+/// Polls `fut` until it completes, or until `timeout` does, returning `None` in that case.
+#[cfg(feature = "async")]
+#[inline]
+async fn with_timeout<F: Future, T: Future>(fut: F, timeout: T) -> Option<F::Output> {
+    let mut fut = pin!(fut);
+    let mut timeout = pin!(timeout);
+    poll_fn(|cx| match fut.as_mut().poll(cx) {
+        Poll::Ready(output) => Poll::Ready(Some(output)),
+        Poll::Pending => timeout.as_mut().poll(cx).map(|_| None),
+    })
+    .await
+}
@@ -0,0 +1,539 @@
+//! Transaction helpers of the `SpiDevice` implementations.
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::spi::{ErrorType, SpiBus};
+
+use super::{DeviceError, MaxTransferSize, MixedOperation};
+
+/// Transaction helpers of the shared and exclusive bus [`SpiDevice`](embedded_hal::spi::SpiDevice)
+/// implementations of this module.
+///
+/// This trait is implemented by [`ExclusiveDevice`](super::ExclusiveDevice),
+/// [`RefCellDevice`](super::RefCellDevice), [`CriticalSectionDevice`](super::CriticalSectionDevice),
+/// `MutexDevice` and `ParkingLotDevice`, and can't be implemented outside of this crate. Except
+/// for [`flush`](Self::flush), each helper runs a single transaction, checked against the maximum
+/// transaction length of the device and counted in its statistics like any other transaction.
+pub trait SpiDeviceExt: sealed::Device {
+    /// Write `data` in chunks of at most `N` words, within a single transaction.
+    ///
+    /// CS is asserted once for the whole write, and each chunk is issued as a separate
+    /// [`SpiBus::write`] call. This is useful when the HAL limits the size of a single
+    /// transfer, for example due to DMA constraints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_bus::spi::{ExclusiveDevice, SpiDeviceExt};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # #[derive(Default)]
+    /// # struct Bus { writes: Vec<usize> }
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.writes.push(words.len()); Ok(()) }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// let mut device = ExclusiveDevice::new_no_delay(Bus::default(), Cs);
+    ///
+    /// let image = [0u8; 1000];
+    /// device.write_chunks::<256, _>(&image).unwrap();
+    ///
+    /// assert_eq!(device.bus().writes, [256, 256, 256, 232]);
+    /// ```
+    #[inline]
+    fn write_chunks<const N: usize, Word: Copy + 'static>(
+        &mut self,
+        data: &[Word],
+    ) -> Result<(), Self::Error>
+    where
+        Self::Bus: SpiBus<Word>,
+    {
+        run(self, data.len(), |bus| {
+            super::write_chunks::<N, _, _>(bus, data)
+        })
+    }
+
+    /// Write `data` in chunks of at most the bus's maximum transfer size, within a single
+    /// transaction.
+    ///
+    /// This is like [`write_chunks`](Self::write_chunks), with chunks of
+    /// [`MaxTransferSize::max_transfer_words`] words, or of `N` words if the bus reports no
+    /// limit. A reported limit of 0 words is treated as no limit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chunk size is 0, that is if `N` is 0 and the bus reports no limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_bus::spi::{ExclusiveDevice, MaxTransferSize, SpiDeviceExt};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # #[derive(Default)]
+    /// # struct Bus { writes: Vec<usize> }
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.writes.push(words.len()); Ok(()) }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// impl MaxTransferSize for Bus {
+    ///     fn max_transfer_words(&self) -> Option<usize> {
+    ///         Some(256)
+    ///     }
+    /// }
+    ///
+    /// let mut device = ExclusiveDevice::new_no_delay(Bus::default(), Cs);
+    ///
+    /// let image = [0u8; 1000];
+    /// device.write_bus_chunks::<64, _>(&image).unwrap();
+    ///
+    /// assert_eq!(device.bus().writes, [256, 256, 256, 232]);
+    /// ```
+    #[inline]
+    fn write_bus_chunks<const N: usize, Word: Copy + 'static>(
+        &mut self,
+        data: &[Word],
+    ) -> Result<(), Self::Error>
+    where
+        Self::Bus: SpiBus<Word> + MaxTransferSize,
+    {
+        run(self, data.len(), |bus| {
+            super::write_bus_chunks::<N, _, _>(bus, data)
+        })
+    }
+
+    /// Perform a write-only transaction mixing 8-bit and 16-bit words.
+    ///
+    /// This drives an 8-bit bus, sending the [`MixedOperation::Words16`] parts as pairs of bytes,
+    /// most significant byte first. CS is asserted once for the whole transaction, which is useful
+    /// for example for displays expecting an 8-bit command followed by 16-bit pixel data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_bus::spi::{ExclusiveDevice, MixedOperation, SpiDeviceExt};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # #[derive(Default)]
+    /// # struct Bus { written: Vec<u8> }
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.written.extend_from_slice(words); Ok(()) }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// let mut display = ExclusiveDevice::new_no_delay(Bus::default(), Cs);
+    ///
+    /// display
+    ///     .mixed_transaction(&[
+    ///         MixedOperation::Bytes(&[0x2C]),
+    ///         MixedOperation::Words16(&[0xF800, 0x07E0]),
+    ///     ])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(display.bus().written, [0x2C, 0xF8, 0x00, 0x07, 0xE0]);
+    /// ```
+    #[inline]
+    fn mixed_transaction(&mut self, operations: &[MixedOperation<'_>]) -> Result<(), Self::Error>
+    where
+        Self::Bus: SpiBus<u8>,
+    {
+        run(self, super::mixed::len(operations), |bus| {
+            super::mixed::write_mixed(bus, operations)
+        })
+    }
+
+    /// Wait until all previously started operations on the bus have completed.
+    ///
+    /// This only [flushes](SpiBus::flush) the bus. CS is not asserted or deasserted.
+    #[inline]
+    fn flush<Word: Copy + 'static>(&mut self) -> Result<(), Self::Error>
+    where
+        Self::Bus: SpiBus<Word>,
+    {
+        self.lock_bus(|bus, _| bus.flush().map_err(DeviceError::Spi))
+    }
+
+    /// Write `cmd`, clock `dummy` bytes, then read into `buf`, within a single transaction.
+    ///
+    /// Many sensors need one or more dummy bytes after the register address before sending the
+    /// data back. The dummy bytes are clocked like a [read](SpiBus::read), so the value sent on
+    /// MOSI is implementation-defined, and the received bytes are discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_bus::spi::{ExclusiveDevice, SpiDeviceExt};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # #[derive(Debug, PartialEq)]
+    /// # enum Event { Write(Vec<u8>), Read(usize) }
+    /// # #[derive(Default)]
+    /// # struct Bus { events: Vec<Event> }
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+    /// #         self.events.push(Event::Read(words.len()));
+    /// #         words.fill(0xA5);
+    /// #         Ok(())
+    /// #     }
+    /// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+    /// #         self.events.push(Event::Write(words.to_vec()));
+    /// #         Ok(())
+    /// #     }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// let mut sensor = ExclusiveDevice::new_no_delay(Bus::default(), Cs);
+    ///
+    /// let mut data = [0; 2];
+    /// sensor.read_register_with_dummy(&[0x80 | 0x0F], 1, &mut data).unwrap();
+    ///
+    /// # use Event::*;
+    /// assert_eq!(sensor.bus().events, [Write(vec![0x8F]), Read(1), Read(2)]);
+    /// assert_eq!(data, [0xA5, 0xA5]);
+    /// ```
+    #[inline]
+    fn read_register_with_dummy(
+        &mut self,
+        cmd: &[u8],
+        dummy: usize,
+        buf: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        Self::Bus: SpiBus<u8>,
+    {
+        let len = cmd.len() + dummy + buf.len();
+        run(self, len, |bus| {
+            super::read_with_dummy(bus, cmd, dummy, buf)
+        })
+    }
+
+    /// Read one byte with `read_cmd`, and return whether it is `expected`.
+    ///
+    /// This is meant for probing "who am I" identification registers, for example during board
+    /// bring-up. The command and the read are done within a single transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_bus::spi::{ExclusiveDevice, SpiDeviceExt};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # #[derive(Default)]
+    /// # struct Bus { written: Vec<u8> }
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> { words.fill(0x6A); Ok(()) }
+    /// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.written.extend_from_slice(words); Ok(()) }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// // The bus reads back 0x6A, the ID of the sensor.
+    /// let mut sensor = ExclusiveDevice::new_no_delay(Bus::default(), Cs);
+    ///
+    /// assert_eq!(sensor.probe_register(&[0x80 | 0x0F], 0x6A), Ok(true));
+    /// assert_eq!(sensor.probe_register(&[0x80 | 0x0F], 0x33), Ok(false));
+    /// assert_eq!(sensor.bus().written, [0x8F, 0x8F]);
+    /// ```
+    #[inline]
+    fn probe_register(&mut self, read_cmd: &[u8], expected: u8) -> Result<bool, Self::Error>
+    where
+        Self::Bus: SpiBus<u8>,
+    {
+        let mut id = [0];
+        self.read_register_with_dummy(read_cmd, 0, &mut id)?;
+        Ok(id[0] == expected)
+    }
+
+    /// Write `data` followed by its CRC byte, within a single transaction.
+    ///
+    /// The CRC is computed over `data` by the `crc` function, so any algorithm or polynomial
+    /// can be used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_bus::spi::{ExclusiveDevice, SpiDeviceExt};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # #[derive(Default)]
+    /// # struct Bus { written: Vec<u8> }
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.written.extend_from_slice(words); Ok(()) }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// let mut device = ExclusiveDevice::new_no_delay(Bus::default(), Cs);
+    ///
+    /// let xor = |data: &[u8]| data.iter().fold(0, |crc, byte| crc ^ byte);
+    /// device.write_with_crc(&[0x12, 0x34, 0x0F], xor).unwrap();
+    ///
+    /// assert_eq!(device.bus().written, [0x12, 0x34, 0x0F, 0x12 ^ 0x34 ^ 0x0F]);
+    /// ```
+    #[inline]
+    fn write_with_crc<C>(&mut self, data: &[u8], crc: C) -> Result<(), Self::Error>
+    where
+        Self::Bus: SpiBus<u8>,
+        C: FnOnce(&[u8]) -> u8,
+    {
+        run(self, data.len() + 1, |bus| {
+            super::write_with_crc(bus, data, crc)
+        })
+    }
+
+    /// Write the words produced by `words`, within a single transaction.
+    ///
+    /// The words are collected in a stack buffer of `N` words, and written in chunks of at most
+    /// `N` words each. This allows writing procedurally generated data without a backing buffer.
+    ///
+    /// If the device has a maximum transaction length, the upper bound of the
+    /// [size hint](Iterator::size_hint) of the iterator must be within it. Iterators that may be
+    /// longer, including the ones without an upper bound, are rejected with
+    /// [`DeviceError::TooLong`] before touching the bus or CS, even if they would have produced
+    /// fewer words. Only an iterator producing more words than its size hint allows can still be
+    /// cut short: the words up to the chunk going over the limit are written, and the error is
+    /// returned then.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_bus::spi::{ExclusiveDevice, SpiDeviceExt};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # #[derive(Default)]
+    /// # struct Bus { written: Vec<u8>, writes: usize }
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+    /// #         self.written.extend_from_slice(words);
+    /// #         self.writes += 1;
+    /// #         Ok(())
+    /// #     }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # #[derive(Default)]
+    /// # struct Cs { selections: usize }
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { self.selections += 1; Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # let mut cs = Cs::default();
+    /// let mut device = ExclusiveDevice::new_no_delay(Bus::default(), &mut cs);
+    ///
+    /// let ramp = (0..500u32).map(|i| (i * 255 / 499) as u8);
+    /// device.write_iter::<64, _>(ramp.clone()).unwrap();
+    ///
+    /// assert_eq!(device.bus().written, ramp.collect::<Vec<_>>());
+    /// assert_eq!(device.bus().writes, 8);
+    /// # drop(device);
+    /// # assert_eq!(cs.selections, 1);
+    /// ```
+    #[inline]
+    fn write_iter<const N: usize, I>(&mut self, words: I) -> Result<(), Self::Error>
+    where
+        Self::Bus: SpiBus<u8>,
+        I: IntoIterator<Item = u8>,
+    {
+        let words = words.into_iter();
+        let max = self.max_transaction_bytes();
+        let upper = words.size_hint().1.unwrap_or(usize::MAX);
+        let res = super::check_words(upper, max).and_then(|()| {
+            let len = self.lock_bus(|bus, cs| {
+                super::transaction_with(bus, cs, |bus| {
+                    super::write_iter::<N, _, _>(bus, words, max)
+                })
+            })?;
+            super::check_words(len, max)?;
+            Ok(len)
+        });
+        let len = *res.as_ref().unwrap_or(&0);
+        self.record(len, res).map(|_| ())
+    }
+
+    /// Transfer `write`, discarding the first `skip` received bytes and storing the following
+    /// ones in `read`, within a single transaction.
+    ///
+    /// This covers the common "command then response" pattern without needing a read buffer
+    /// as long as the whole transfer. The transfer runs for `max(write.len(), skip + read.len())`
+    /// bytes. As for [`SpiBus::transfer`], the value of the bytes sent after `write` is
+    /// implementation-defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal_bus::spi::{ExclusiveDevice, SpiDeviceExt};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// // A bus with MISO looped back to MOSI, sending 0x00 when reading.
+    /// # struct Echo;
+    /// # impl spi::ErrorType for Echo { type Error = Infallible; }
+    /// # impl spi::SpiBus for Echo {
+    /// #     fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> { words.fill(0); Ok(()) }
+    /// #     fn write(&mut self, _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Infallible> {
+    /// #         for (i, r) in read.iter_mut().enumerate() {
+    /// #             *r = write.get(i).copied().unwrap_or(0);
+    /// #         }
+    /// #         Ok(())
+    /// #     }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// let mut device = ExclusiveDevice::new_no_delay(Echo, Cs);
+    ///
+    /// // Skip the byte received while sending the command.
+    /// let mut read = [0xFF; 3];
+    /// device.transfer_tail(&[0x9F, 0x01, 0x02, 0x03], 1, &mut read).unwrap();
+    /// assert_eq!(read, [0x01, 0x02, 0x03]);
+    ///
+    /// // `read` may extend past the end of `write`, and `skip` too.
+    /// let mut read = [0xFF; 3];
+    /// device.transfer_tail(&[0x9F, 0x01, 0x02], 2, &mut read).unwrap();
+    /// assert_eq!(read, [0x02, 0x00, 0x00]);
+    ///
+    /// let mut read = [0xFF; 2];
+    /// device.transfer_tail(&[0x9F], 3, &mut read).unwrap();
+    /// assert_eq!(read, [0x00, 0x00]);
+    /// ```
+    #[inline]
+    fn transfer_tail(
+        &mut self,
+        write: &[u8],
+        skip: usize,
+        read: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        Self::Bus: SpiBus<u8>,
+    {
+        let len = write.len().max(skip + read.len());
+        run(self, len, |bus| {
+            super::transfer_tail(bus, write, skip, read)
+        })
+    }
+}
+
+impl<T: sealed::Device> SpiDeviceExt for T {}
+
+/// Runs `f` on the bus of `device` within a transaction of `len` words.
+///
+/// The transaction is rejected with [`DeviceError::TooLong`] before touching the bus or CS if it
+/// is too long, and is recorded in the statistics of the device.
+#[inline]
+fn run<D, Word, R>(
+    device: &mut D,
+    len: usize,
+    f: impl FnOnce(&mut D::Bus) -> Result<R, <D::Bus as ErrorType>::Error>,
+) -> Result<R, D::Error>
+where
+    D: sealed::Device + ?Sized,
+    D::Bus: SpiBus<Word>,
+    Word: Copy + 'static,
+{
+    let res = super::check_words(len, device.max_transaction_bytes())
+        .and_then(|()| device.lock_bus(|bus, cs| super::transaction_with(bus, cs, f)));
+    device.record(len, res)
+}
+
+pub(super) mod sealed {
+    use super::*;
+
+    /// Access to the bus and CS pin of a device, for [`SpiDeviceExt`].
+    pub trait Device:
+        ErrorType<
+        Error = DeviceError<
+            <Self::Bus as ErrorType>::Error,
+            <Self::Cs as digital::ErrorType>::Error,
+        >,
+    >
+    {
+        /// Type of the bus.
+        type Bus: ErrorType;
+        /// Type of the CS pin.
+        type Cs: OutputPin;
+
+        /// Returns the maximum transaction length, if any.
+        fn max_transaction_bytes(&self) -> Option<usize>;
+
+        /// Runs `f` with the bus, locked if it is shared, and the CS pin.
+        fn lock_bus<R>(
+            &mut self,
+            f: impl FnOnce(&mut Self::Bus, &mut Self::Cs) -> Result<R, Self::Error>,
+        ) -> Result<R, Self::Error>;
+
+        /// Records the result `res` of a transaction of `words` words in the statistics.
+        fn record<R>(
+            &mut self,
+            words: usize,
+            res: Result<R, Self::Error>,
+        ) -> Result<R, Self::Error>;
+    }
+}
@@ -0,0 +1,188 @@
+//! SPI bus adapter failing over between two buses.
+
+use embedded_hal::spi::{ErrorType, SpiBus};
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// Bus in use by a [`Failover`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ActiveBus {
+    /// The primary bus, `A`.
+    A,
+    /// The secondary bus, `B`.
+    B,
+}
+
+/// [`SpiBus`] adapter over two redundant buses to the same peripherals, failing over from one to
+/// the other on errors.
+///
+/// Operations are done on the [active bus](Self::active_bus), initially `A`. If an operation
+/// fails, the other bus becomes active, and the whole operation is retried on it. If it fails
+/// again, the error of the retry is returned, and the active bus is not switched back.
+///
+/// Failover only happens at operation boundaries: the words the failed bus already clocked out
+/// can't be taken back, and the operation is replayed from its start on the other bus. As the
+/// write data of an in-place transfer is overwritten while it is read, a failed
+/// [`transfer_in_place`](SpiBus::transfer_in_place) can't be replayed: the other bus becomes
+/// active for the next operations, but the error is returned.
+///
+/// Both buses must have the same error type.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::{ErrorKind, SpiBus};
+/// use embedded_hal_bus::spi::{ActiveBus, Failover};
+/// # use embedded_hal::spi;
+/// # #[derive(Default)]
+/// # struct Bus { broken: bool, written: Vec<u8> }
+/// # impl Bus {
+/// #     fn check(&self) -> Result<(), ErrorKind> { if self.broken { Err(ErrorKind::Other) } else { Ok(()) } }
+/// # }
+/// # impl spi::ErrorType for Bus { type Error = ErrorKind; }
+/// # impl spi::SpiBus for Bus {
+/// #     fn read(&mut self, _: &mut [u8]) -> Result<(), ErrorKind> { self.check() }
+/// #     fn write(&mut self, words: &[u8]) -> Result<(), ErrorKind> { self.check()?; self.written.extend_from_slice(words); Ok(()) }
+/// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), ErrorKind> { self.check() }
+/// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), ErrorKind> { self.check() }
+/// #     fn flush(&mut self) -> Result<(), ErrorKind> { self.check() }
+/// # }
+/// let primary = Bus { broken: true, ..Default::default() };
+/// let secondary = Bus::default();
+/// let mut bus = Failover::new(primary, secondary);
+///
+/// assert_eq!(bus.active_bus(), ActiveBus::A);
+/// bus.write(&[0x01, 0x02]).unwrap();
+/// assert_eq!(bus.active_bus(), ActiveBus::B);
+///
+/// let (primary, secondary) = bus.into_inner();
+/// assert_eq!(primary.written, []);
+/// assert_eq!(secondary.written, [0x01, 0x02]);
+/// ```
+pub struct Failover<A, B> {
+    a: A,
+    b: B,
+    active: ActiveBus,
+}
+
+impl<A, B> Failover<A, B> {
+    /// Create a new `Failover`, using `a` until it fails.
+    #[inline]
+    pub fn new(a: A, b: B) -> Self {
+        Self {
+            a,
+            b,
+            active: ActiveBus::A,
+        }
+    }
+
+    /// Returns the bus the next operation will be done on.
+    #[inline]
+    pub fn active_bus(&self) -> ActiveBus {
+        self.active
+    }
+
+    /// Sets the bus the next operation will be done on, for example to switch back to `A` once
+    /// it is known to work again.
+    #[inline]
+    pub fn set_active_bus(&mut self, active: ActiveBus) {
+        self.active = active;
+    }
+
+    /// Destroys the adapter, returning both buses.
+    #[inline]
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A, B> Failover<A, B>
+where
+    A: ErrorType,
+    B: ErrorType<Error = A::Error>,
+{
+    /// Runs `f` on the active bus, then on the other one if it failed and `retry` is `true`.
+    #[inline]
+    fn with_failover(
+        &mut self,
+        retry: bool,
+        mut f: impl FnMut(Bus<'_, A, B>) -> Result<(), A::Error>,
+    ) -> Result<(), A::Error> {
+        let res = f(self.active());
+        if res.is_err() {
+            self.active = match self.active {
+                ActiveBus::A => ActiveBus::B,
+                ActiveBus::B => ActiveBus::A,
+            };
+            if retry {
+                return f(self.active());
+            }
+        }
+        res
+    }
+
+    #[inline]
+    fn active(&mut self) -> Bus<'_, A, B> {
+        match self.active {
+            ActiveBus::A => Bus::A(&mut self.a),
+            ActiveBus::B => Bus::B(&mut self.b),
+        }
+    }
+}
+
+/// Mutable reference to one of the buses of a [`Failover`].
+enum Bus<'a, A, B> {
+    A(&'a mut A),
+    B(&'a mut B),
+}
+
+/// Runs the same method on whichever bus `$bus` refers to.
+macro_rules! on_bus {
+    ($bus:expr, |$b:ident| $body:expr) => {
+        match $bus {
+            Bus::A($b) => $body,
+            Bus::B($b) => $body,
+        }
+    };
+}
+
+impl<A, B> ErrorType for Failover<A, B>
+where
+    A: ErrorType,
+    B: ErrorType<Error = A::Error>,
+{
+    type Error = A::Error;
+}
+
+impl<Word: Copy + 'static, A, B> SpiBus<Word> for Failover<A, B>
+where
+    A: SpiBus<Word>,
+    B: SpiBus<Word, Error = A::Error>,
+{
+    #[inline]
+    fn read(&mut self, words: &mut [Word]) -> Result<(), Self::Error> {
+        self.with_failover(true, |bus| on_bus!(bus, |b| b.read(words)))
+    }
+
+    #[inline]
+    fn write(&mut self, words: &[Word]) -> Result<(), Self::Error> {
+        self.with_failover(true, |bus| on_bus!(bus, |b| b.write(words)))
+    }
+
+    #[inline]
+    fn transfer(&mut self, read: &mut [Word], write: &[Word]) -> Result<(), Self::Error> {
+        self.with_failover(true, |bus| on_bus!(bus, |b| b.transfer(read, write)))
+    }
+
+    #[inline]
+    fn transfer_in_place(&mut self, words: &mut [Word]) -> Result<(), Self::Error> {
+        self.with_failover(false, |bus| on_bus!(bus, |b| b.transfer_in_place(words)))
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.with_failover(true, |bus| on_bus!(bus, |b| b.flush()))
+    }
+}
@@ -0,0 +1,104 @@
+//! SPI device wrapper delaying every transaction but the first.
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+
+/// [`SpiDevice`] wrapper waiting a fixed time before each transaction, except the first.
+///
+/// Some devices need the bus to stay idle for a minimum time between any two transactions, for
+/// example to process a command. This wrapper unconditionally waits `delay_us` microseconds
+/// before starting each transaction, so unlike [`MinDeselect`](super::MinDeselect) it doesn't
+/// need a clock, but it also waits when enough time already elapsed since the previous
+/// transaction.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::SpiDevice;
+/// use embedded_hal_bus::spi::InterTransactionDelay;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{delay, spi};
+/// # struct Device;
+/// # impl spi::ErrorType for Device { type Error = Infallible; }
+/// # impl spi::SpiDevice for Device {
+/// #     fn transaction(&mut self, _: &mut [spi::Operation<'_, u8>]) -> Result<(), Infallible> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// # struct Delay;
+/// # impl delay::DelayUs for Delay { fn delay_us(&mut self, _: u32) {} }
+/// let mut device = InterTransactionDelay::new(Device, Delay, 20);
+///
+/// // Starts immediately.
+/// device.write(&[0x01]).unwrap();
+/// // Starts 20 µs after the previous transaction ended.
+/// device.write(&[0x02]).unwrap();
+/// ```
+pub struct InterTransactionDelay<D, DL> {
+    device: D,
+    delay: DL,
+    delay_us: u32,
+    started: bool,
+}
+
+impl<D, DL> InterTransactionDelay<D, DL> {
+    /// Create a new `InterTransactionDelay`, waiting `delay_us` microseconds before each
+    /// transaction but the first.
+    #[inline]
+    pub fn new(device: D, delay: DL, delay_us: u32) -> Self {
+        Self {
+            device,
+            delay,
+            delay_us,
+            started: false,
+        }
+    }
+
+    /// Returns a reference to the inner device.
+    #[inline]
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Returns a mutable reference to the inner device.
+    ///
+    /// Transactions done directly on the inner device are not delayed, and don't delay the
+    /// following ones.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+
+    /// Destroys the wrapper, returning the inner device and the delay.
+    #[inline]
+    pub fn into_inner(self) -> (D, DL) {
+        (self.device, self.delay)
+    }
+}
+
+impl<D, DL> ErrorType for InterTransactionDelay<D, DL>
+where
+    D: ErrorType,
+{
+    type Error = D::Error;
+}
+
+impl<Word: Copy + 'static, D, DL> SpiDevice<Word> for InterTransactionDelay<D, DL>
+where
+    D: SpiDevice<Word>,
+    DL: DelayUs,
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        // An empty transaction does nothing, so there is nothing to wait for.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        if self.started {
+            self.delay.delay_us(self.delay_us);
+        }
+        // The bus was used even if the transaction failed.
+        self.started = true;
+        self.device.transaction(operations)
+    }
+}
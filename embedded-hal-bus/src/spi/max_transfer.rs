@@ -0,0 +1,34 @@
+/// SPI bus limiting the number of words of a single transfer.
+///
+/// [`SpiBus`](embedded_hal::spi::SpiBus) has no way to report such a limit, for example due to
+/// the maximum length of a DMA transfer, so HALs can implement this trait next to it.
+/// [`SpiDeviceExt::write_bus_chunks`](super::SpiDeviceExt::write_bus_chunks) consults it to split
+/// large writes.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_bus::spi::MaxTransferSize;
+///
+/// struct DmaBus;
+///
+/// impl MaxTransferSize for DmaBus {
+///     fn max_transfer_words(&self) -> Option<usize> {
+///         // 16-bit DMA transfer counter.
+///         Some(65535)
+///     }
+/// }
+/// ```
+pub trait MaxTransferSize {
+    /// Returns the maximum number of words of a single transfer, or `None` if it is unlimited.
+    ///
+    /// `Some(0)` is treated like `None`.
+    fn max_transfer_words(&self) -> Option<usize>;
+}
+
+impl<T: MaxTransferSize + ?Sized> MaxTransferSize for &mut T {
+    #[inline]
+    fn max_transfer_words(&self) -> Option<usize> {
+        T::max_transfer_words(self)
+    }
+}
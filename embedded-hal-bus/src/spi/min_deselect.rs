@@ -0,0 +1,127 @@
+//! SPI device wrapper enforcing a minimum CS deselect time.
+
+use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+
+/// [`SpiDevice`] wrapper ensuring CS stays deasserted for a minimum time between transactions.
+///
+/// Some devices require CS to stay high for a minimum time between two selections. This wrapper
+/// records when the last transaction ended, and waits at the start of the next one until at
+/// least `min_deselect_us` microseconds have elapsed before starting it.
+///
+/// Time is read from `clock`, a closure returning a monotonic timestamp in microseconds that
+/// wraps around at `u32::MAX`. Waiting busy-polls the clock, so no time is wasted if enough time
+/// already elapsed since the previous transaction. If more than `u32::MAX` microseconds (about
+/// 71 minutes) pass between two transactions, the wrap-around may cause an unneeded wait of at
+/// most `min_deselect_us`.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::SpiDevice;
+/// use embedded_hal_bus::spi::MinDeselect;
+/// # use core::cell::Cell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::spi;
+/// # struct Device<'a> { now: &'a Cell<u32>, starts: Vec<u32> }
+/// # impl spi::ErrorType for Device<'_> { type Error = Infallible; }
+/// # impl spi::SpiDevice for Device<'_> {
+/// #     fn transaction(&mut self, _: &mut [spi::Operation<'_, u8>]) -> Result<(), Infallible> {
+/// #         self.starts.push(self.now.get());
+/// #         Ok(())
+/// #     }
+/// # }
+/// // A fake clock, advancing by 1 µs every time it is read.
+/// let now = Cell::new(0);
+/// let clock = || {
+///     now.set(now.get() + 1);
+///     now.get()
+/// };
+/// # let device = Device { now: &now, starts: Vec::new() };
+///
+/// let mut device = MinDeselect::new(device, clock, 50);
+///
+/// device.write(&[1]).unwrap();
+/// device.write(&[2]).unwrap();
+///
+/// let starts = &device.inner().starts;
+/// assert!(starts[1] - starts[0] >= 50);
+/// ```
+pub struct MinDeselect<D, C> {
+    device: D,
+    clock: C,
+    min_deselect_us: u32,
+    last_deselect: Option<u32>,
+}
+
+impl<D, C> MinDeselect<D, C>
+where
+    C: FnMut() -> u32,
+{
+    /// Create a new `MinDeselect`, keeping CS deasserted for at least `min_deselect_us`
+    /// microseconds between transactions.
+    ///
+    /// The first transaction is started immediately.
+    #[inline]
+    pub fn new(device: D, clock: C, min_deselect_us: u32) -> Self {
+        Self {
+            device,
+            clock,
+            min_deselect_us,
+            last_deselect: None,
+        }
+    }
+
+    /// Returns a reference to the inner device.
+    #[inline]
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Returns a mutable reference to the inner device.
+    ///
+    /// Transactions done directly on the inner device are not tracked.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+
+    /// Destroys the wrapper, returning the inner device.
+    #[inline]
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+    /// Waits until the minimum deselect time since the end of the last transaction has elapsed.
+    #[inline]
+    fn wait_deselect(&mut self) {
+        if let Some(last) = self.last_deselect {
+            while (self.clock)().wrapping_sub(last) < self.min_deselect_us {}
+        }
+    }
+}
+
+impl<D, C> ErrorType for MinDeselect<D, C>
+where
+    D: ErrorType,
+{
+    type Error = D::Error;
+}
+
+impl<Word: Copy + 'static, D, C> SpiDevice<Word> for MinDeselect<D, C>
+where
+    D: SpiDevice<Word>,
+    C: FnMut() -> u32,
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        // An empty transaction does nothing, so there is nothing to wait for.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.wait_deselect();
+        let res = self.device.transaction(operations);
+        // CS is deasserted even if the transaction failed.
+        self.last_deselect = Some((self.clock)());
+        res
+    }
+}
@@ -0,0 +1,56 @@
+use embedded_hal::spi::SpiBus;
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// Write operation of a transaction mixing 8-bit and 16-bit words.
+///
+/// See [`SpiDeviceExt::mixed_transaction`](super::SpiDeviceExt::mixed_transaction).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum MixedOperation<'a> {
+    /// Write 8-bit words.
+    Bytes(&'a [u8]),
+    /// Write 16-bit words.
+    ///
+    /// Each word is sent as two bytes, most significant byte first.
+    Words16(&'a [u16]),
+}
+
+/// Writes `operations` to an 8-bit `bus`, converting 16-bit words to big-endian bytes.
+#[inline]
+pub(super) fn write_mixed<BUS>(
+    bus: &mut BUS,
+    operations: &[MixedOperation<'_>],
+) -> Result<(), BUS::Error>
+where
+    BUS: SpiBus<u8>,
+{
+    for op in operations {
+        match op {
+            MixedOperation::Bytes(bytes) => bus.write(bytes)?,
+            MixedOperation::Words16(words) => {
+                let mut buf = [0; 32];
+                for chunk in words.chunks(buf.len() / 2) {
+                    for (bytes, word) in buf.chunks_exact_mut(2).zip(chunk) {
+                        bytes.copy_from_slice(&word.to_be_bytes());
+                    }
+                    bus.write(&buf[..chunk.len() * 2])?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the number of bytes written by `operations`.
+#[inline]
+pub(super) fn len(operations: &[MixedOperation<'_>]) -> usize {
+    operations
+        .iter()
+        .map(|op| match op {
+            MixedOperation::Bytes(bytes) => bytes.len(),
+            MixedOperation::Words16(words) => 2 * words.len(),
+        })
+        .sum()
+}
@@ -1,7 +1,15 @@
 //! `SpiDevice` implementations.
+//!
+//! In all the implementations of this module, a transaction with an empty slice of operations
+//! does nothing: the bus isn't locked or flushed, CS isn't asserted, and `Ok(())` is returned.
+//! A transaction of empty operations, such as a write of no words, isn't empty, and still asserts
+//! and deasserts CS.
 
-use core::fmt::Debug;
-use embedded_hal::spi::{Error, ErrorKind};
+use core::convert::Infallible;
+use core::fmt::{self, Debug};
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::spi::{Error, ErrorKind, Operation, SpiBus};
 
 mod exclusive;
 pub use exclusive::*;
@@ -11,20 +19,259 @@ pub use refcell::*;
 mod mutex;
 #[cfg(feature = "std")]
 pub use mutex::*;
+#[cfg(feature = "parking-lot")]
+mod parking_lot;
+#[cfg(feature = "parking-lot")]
+pub use self::parking_lot::*;
 mod critical_section;
 pub use self::critical_section::*;
+mod mixed;
+pub use mixed::*;
+mod ext;
+pub use ext::SpiDeviceExt;
+mod byte_delayed;
+pub use byte_delayed::*;
+mod read_fill;
+pub use read_fill::*;
+mod debug_operations;
+pub use debug_operations::*;
+mod min_deselect;
+pub use min_deselect::*;
+mod inter_transaction_delay;
+pub use inter_transaction_delay::*;
+mod retry;
+pub use retry::*;
+mod throttled;
+pub use throttled::*;
+mod context;
+pub use context::*;
+mod multi_bus;
+pub use multi_bus::*;
+mod timed;
+pub use timed::*;
+mod decoded_cs;
+pub use decoded_cs::*;
+mod failover;
+pub use failover::*;
+mod max_transfer;
+pub use crate::BitOrder;
+pub use max_transfer::*;
+mod config;
+pub use config::*;
+mod bitbang;
+pub use bitbang::*;
+mod stats;
+use stats::DeviceStats;
+#[cfg(feature = "stats")]
+pub use stats::Stats;
+#[cfg(feature = "async")]
+mod block_on;
+#[cfg(feature = "async")]
+pub use block_on::*;
+#[cfg(feature = "async")]
+mod scoped;
+#[cfg(feature = "async")]
+pub use scoped::*;
+#[cfg(feature = "async")]
+mod dual_mode;
+#[cfg(feature = "async")]
+pub use dual_mode::*;
 
 #[cfg(feature = "defmt-03")]
 use crate::defmt;
 
 /// Error type for [`ExclusiveDevice`] operations.
+///
+/// It is `Clone` and `Copy` whenever the bus and CS error types are, so it can for example be
+/// stored in a driver's state.
+///
+/// New variants can be added without a breaking release, so matches on it need a wildcard arm.
+///
+/// ```
+/// use embedded_hal::spi::ErrorKind;
+/// use embedded_hal_bus::spi::DeviceError;
+///
+/// let error: DeviceError<ErrorKind, ()> = DeviceError::Spi(ErrorKind::Overrun);
+/// let last_error = error.clone();
+/// let copied = error;
+///
+/// assert_eq!(last_error, DeviceError::Spi(ErrorKind::Overrun));
+/// assert_eq!(copied, error);
+/// ```
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum DeviceError<BUS, CS> {
     /// An inner SPI bus operation failed.
     Spi(BUS),
     /// Asserting or deasserting CS failed.
     Cs(CS),
+    /// The shared bus was already in use, so the transaction was not started.
+    ///
+    /// Only returned by non-panicking methods such as [`RefCellDevice::try_transaction`].
+    BusBusy,
+    /// The shared bus mutex was poisoned by a thread that panicked while holding it.
+    ///
+    /// Only returned by `MutexDevice`, see `MutexDevice::ignore_poison`.
+    Poisoned,
+    /// The transaction is longer than the maximum set with `with_max_transaction_bytes`.
+    TooLong,
+    /// The transaction didn't complete before its timeout, and was cancelled.
+    ///
+    /// Only returned by async methods such as `ExclusiveDevice::transaction_timeout`.
+    Timeout,
+    /// The transaction contains a delay operation, but the device has no delay, so the
+    /// transaction was not started.
+    ///
+    /// Only returned by devices created without a delay by [`ExclusiveDevice::new_maybe_delay`].
+    DelayUnsupported,
+}
+
+impl<BUS, CS> DeviceError<BUS, CS> {
+    /// Returns `true` if asserting or deasserting CS failed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal::spi::ErrorKind;
+    /// use embedded_hal_bus::spi::DeviceError;
+    ///
+    /// let bus_error: DeviceError<ErrorKind, u8> = DeviceError::Spi(ErrorKind::Overrun);
+    /// assert!(bus_error.is_bus_fault());
+    /// assert!(!bus_error.is_cs_fault());
+    /// assert_eq!(bus_error.as_bus(), Some(&ErrorKind::Overrun));
+    /// assert_eq!(bus_error.as_cs(), None);
+    ///
+    /// let cs_error: DeviceError<ErrorKind, u8> = DeviceError::Cs(42);
+    /// assert!(cs_error.is_cs_fault());
+    /// assert!(!cs_error.is_bus_fault());
+    /// assert_eq!(cs_error.as_cs(), Some(&42));
+    /// assert_eq!(cs_error.as_bus(), None);
+    /// ```
+    #[inline]
+    pub fn is_cs_fault(&self) -> bool {
+        matches!(self, Self::Cs(_))
+    }
+
+    /// Returns `true` if an inner SPI bus operation failed.
+    #[inline]
+    pub fn is_bus_fault(&self) -> bool {
+        matches!(self, Self::Spi(_))
+    }
+
+    /// Returns the CS error, if asserting or deasserting CS failed.
+    #[inline]
+    pub fn as_cs(&self) -> Option<&CS> {
+        match self {
+            Self::Cs(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Returns the bus error, if an inner SPI bus operation failed.
+    #[inline]
+    pub fn as_bus(&self) -> Option<&BUS> {
+        match self {
+            Self::Spi(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Wraps a bus error, so that `?` can be used on bus operations in functions returning a
+/// `DeviceError`.
+///
+/// # Examples
+///
+/// With a CS pin that can't fail, such as an [`ExclusiveDevice`] with a pin using
+/// [`Infallible`] as its error type, bus errors are the only errors:
+///
+/// ```
+/// use core::convert::Infallible;
+/// use embedded_hal::spi::{ErrorKind, SpiBus, SpiDevice};
+/// use embedded_hal_bus::spi::{DeviceError, ExclusiveDevice};
+/// # use embedded_hal::{digital, spi};
+/// # struct Bus;
+/// # impl spi::ErrorType for Bus { type Error = ErrorKind; }
+/// # impl SpiBus for Bus {
+/// #     fn read(&mut self, _: &mut [u8]) -> Result<(), ErrorKind> { Err(ErrorKind::Overrun) }
+/// #     fn write(&mut self, _: &[u8]) -> Result<(), ErrorKind> { Ok(()) }
+/// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), ErrorKind> { Ok(()) }
+/// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), ErrorKind> { Ok(()) }
+/// #     fn flush(&mut self) -> Result<(), ErrorKind> { Ok(()) }
+/// # }
+/// # struct Cs;
+/// # impl digital::ErrorType for Cs { type Error = Infallible; }
+/// # impl digital::OutputPin for Cs {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// fn reset(bus: &mut impl SpiBus<Error = ErrorKind>) -> Result<(), DeviceError<ErrorKind, Infallible>> {
+///     bus.write(&[0xFF; 8])?;
+///     Ok(bus.flush()?)
+/// }
+///
+/// let mut device = ExclusiveDevice::new_no_delay(Bus, Cs);
+/// assert_eq!(reset(device.bus_mut()), Ok(()));
+///
+/// let error = device.read(&mut [0; 2]).unwrap_err();
+/// assert_eq!(error, DeviceError::Spi(ErrorKind::Overrun));
+/// assert!(!error.is_cs_fault());
+/// assert_eq!(spi::Error::kind(&error), ErrorKind::Overrun);
+/// ```
+impl<BUS, CS> From<BUS> for DeviceError<BUS, CS> {
+    #[inline]
+    fn from(error: BUS) -> Self {
+        Self::Spi(error)
+    }
+}
+
+impl<BUS> DeviceError<BUS, Infallible> {
+    /// Converts the error back to a bus error, for devices whose CS pin can't fail.
+    ///
+    /// Bus errors are returned as is, and the errors raised by the device itself, such as
+    /// [`TooLong`](Self::TooLong), are converted from [`ErrorKind::Other`].
+    ///
+    /// # Examples
+    ///
+    /// Drivers using the bus error type can propagate device errors with `?`:
+    ///
+    /// ```
+    /// use core::convert::Infallible;
+    /// use embedded_hal::spi::{ErrorKind, SpiDevice};
+    /// use embedded_hal_bus::spi::DeviceError;
+    ///
+    /// fn read_id<D>(device: &mut D) -> Result<u8, ErrorKind>
+    /// where
+    ///     D: SpiDevice<Error = DeviceError<ErrorKind, Infallible>>,
+    /// {
+    ///     let mut buf = [0x9F, 0];
+    ///     device
+    ///         .transfer_in_place(&mut buf)
+    ///         .map_err(DeviceError::into_bus_error)?;
+    ///     Ok(buf[1])
+    /// }
+    ///
+    /// let error: DeviceError<ErrorKind, Infallible> = DeviceError::Spi(ErrorKind::Overrun);
+    /// assert_eq!(error.into_bus_error(), ErrorKind::Overrun);
+    /// let error: DeviceError<ErrorKind, Infallible> = DeviceError::TooLong;
+    /// assert_eq!(error.into_bus_error(), ErrorKind::Other);
+    /// ```
+    #[inline]
+    pub fn into_bus_error(self) -> BUS
+    where
+        BUS: From<ErrorKind>,
+    {
+        match self {
+            Self::Spi(e) => e,
+            Self::Cs(e) => match e {},
+            Self::BusBusy
+            | Self::Poisoned
+            | Self::TooLong
+            | Self::Timeout
+            | Self::DelayUnsupported => ErrorKind::Other.into(),
+        }
+    }
 }
 
 impl<BUS, CS> Error for DeviceError<BUS, CS>
@@ -37,10 +284,437 @@ where
         match self {
             Self::Spi(e) => e.kind(),
             Self::Cs(_) => ErrorKind::ChipSelectFault,
+            Self::BusBusy
+            | Self::Poisoned
+            | Self::TooLong
+            | Self::Timeout
+            | Self::DelayUnsupported => ErrorKind::Other,
         }
     }
 }
 
+/// Converts the error to its [kind](Error::kind), discarding the inner error.
+///
+/// This is useful for drivers whose error type holds an `ErrorKind`, so `?` can be used directly
+/// on transactions.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::ErrorKind;
+/// use embedded_hal_bus::spi::DeviceError;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum DriverError {
+///     Spi(ErrorKind),
+///     InvalidId,
+/// }
+///
+/// impl From<ErrorKind> for DriverError {
+///     fn from(kind: ErrorKind) -> Self {
+///         Self::Spi(kind)
+///     }
+/// }
+///
+/// fn check_id(res: Result<u8, DeviceError<ErrorKind, ()>>) -> Result<(), DriverError> {
+///     match res.map_err(ErrorKind::from)? {
+///         0x42 => Ok(()),
+///         _ => Err(DriverError::InvalidId),
+///     }
+/// }
+///
+/// assert_eq!(check_id(Ok(0x42)), Ok(()));
+/// assert_eq!(
+///     check_id(Err(DeviceError::Cs(()))),
+///     Err(DriverError::Spi(ErrorKind::ChipSelectFault))
+/// );
+/// assert_eq!(
+///     ErrorKind::from(DeviceError::<_, ()>::Spi(ErrorKind::Overrun)),
+///     ErrorKind::Overrun
+/// );
+/// ```
+impl<BUS, CS> From<DeviceError<BUS, CS>> for ErrorKind
+where
+    BUS: Error + Debug,
+    CS: Debug,
+{
+    #[inline]
+    fn from(error: DeviceError<BUS, CS>) -> Self {
+        error.kind()
+    }
+}
+
+impl<BUS, CS> fmt::Display for DeviceError<BUS, CS>
+where
+    BUS: fmt::Display,
+    CS: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spi(e) => write!(f, "SPI bus error: {}", e),
+            Self::Cs(e) => write!(f, "chip select error: {}", e),
+            Self::BusBusy => f.write_str("the shared bus is already in use"),
+            Self::Poisoned => f.write_str("the shared bus mutex is poisoned"),
+            Self::TooLong => f.write_str("the transaction exceeds the maximum length"),
+            Self::Timeout => f.write_str("the transaction timed out"),
+            Self::DelayUnsupported => f.write_str("the transaction contains an unsupported delay"),
+        }
+    }
+}
+
+/// The inner bus or CS error is returned as the [`source`](std::error::Error::source).
+///
+/// ```
+/// use std::error::Error;
+/// use embedded_hal_bus::spi::DeviceError;
+/// # #[derive(Debug)]
+/// # struct BusError;
+/// # impl std::fmt::Display for BusError {
+/// #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str("overrun") }
+/// # }
+/// # impl Error for BusError {}
+/// # type CsError = BusError;
+///
+/// let error: DeviceError<BusError, CsError> = DeviceError::Spi(BusError);
+///
+/// assert_eq!(error.to_string(), "SPI bus error: overrun");
+/// assert!(error.source().unwrap().is::<BusError>());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<BUS, CS> std::error::Error for DeviceError<BUS, CS>
+where
+    BUS: std::error::Error + 'static,
+    CS: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Spi(e) => Some(e),
+            Self::Cs(e) => Some(e),
+            Self::BusBusy
+            | Self::Poisoned
+            | Self::TooLong
+            | Self::Timeout
+            | Self::DelayUnsupported => None,
+        }
+    }
+}
+
+/// Returns the number of bytes clocked on the bus by `operations`.
+///
+/// Reads and writes count their length, transfers the longest of their two buffers (the
+/// transfer runs for this long), and delays count 0. This can be used to size DMA buffers, or to
+/// report progress, before performing the transaction.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::Operation;
+/// use embedded_hal_bus::spi::operations_byte_count;
+///
+/// let mut response = [0; 4];
+/// let mut status = [0; 2];
+/// let mut buf = [0; 3];
+/// let operations = [
+///     Operation::Write(&[0x9F]),
+///     Operation::DelayUs(10),
+///     Operation::Read(&mut response),
+///     Operation::Transfer(&mut status, &[0x05, 0x00, 0x00, 0x00, 0x00]),
+///     Operation::TransferInPlace(&mut buf),
+/// ];
+///
+/// assert_eq!(operations_byte_count(&operations), 1 + 4 + 5 + 3);
+/// assert_eq!(operations_byte_count(&[]), 0);
+/// ```
+pub fn operations_byte_count(operations: &[Operation<'_, u8>]) -> usize {
+    operations_len(operations)
+}
+
+/// Returns the number of words clocked on the bus by `operations`.
+#[inline]
+fn operations_len<Word>(operations: &[Operation<'_, Word>]) -> usize {
+    operations
+        .iter()
+        .map(|op| match op {
+            Operation::Read(buf) => buf.len(),
+            Operation::Write(buf) => buf.len(),
+            Operation::Transfer(read, write) => read.len().max(write.len()),
+            Operation::TransferInPlace(buf) => buf.len(),
+            Operation::DelayUs(_) => 0,
+        })
+        .sum()
+}
+
+/// Returns an estimate of the duration of a transaction performing `operations`, in nanoseconds.
+///
+/// This is the time needed to clock the [bytes of the operations](operations_byte_count) at
+/// `bus_freq_hz`, plus the delay operations, plus `cs_settle_ns` for the CS pin to settle. The
+/// bus isn't touched. Software overhead and gaps between bytes are not accounted for, so the
+/// actual duration will be longer.
+///
+/// # Panics
+///
+/// Panics if `bus_freq_hz` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::Operation;
+/// use embedded_hal_bus::spi::estimate_duration_ns;
+///
+/// let mut buf = [0; 3];
+/// let operations = [
+///     Operation::Write(&[0x03, 0x00]),
+///     Operation::DelayUs(5),
+///     Operation::Read(&mut buf),
+/// ];
+///
+/// // 5 bytes at 1 MHz, 8 µs each, plus the 5 µs delay and 100 ns for CS.
+/// assert_eq!(estimate_duration_ns(&operations, 1_000_000, 100), 40_000 + 5_000 + 100);
+/// ```
+// `u64::div_ceil` is newer than the MSRV.
+#[allow(clippy::manual_div_ceil)]
+pub fn estimate_duration_ns(
+    operations: &[Operation<'_, u8>],
+    bus_freq_hz: u32,
+    cs_settle_ns: u32,
+) -> u64 {
+    let bits = operations_byte_count(operations) as u64 * 8;
+    let freq = u64::from(bus_freq_hz);
+    let transfer_ns = (bits * 1_000_000_000 + freq - 1) / freq;
+    let delay_ns: u64 = operations
+        .iter()
+        .map(|op| match op {
+            Operation::DelayUs(us) => u64::from(*us) * 1000,
+            _ => 0,
+        })
+        .sum();
+    transfer_ns + delay_ns + u64::from(cs_settle_ns)
+}
+
+/// Returns [`DeviceError::TooLong`] if `operations` are longer than `max` words.
+#[inline]
+fn check_len<Word, BUS, CS>(
+    operations: &[Operation<'_, Word>],
+    max: Option<usize>,
+) -> Result<(), DeviceError<BUS, CS>> {
+    check_words(operations_len(operations), max)
+}
+
+/// Returns [`DeviceError::TooLong`] if `len` words are more than `max`.
+#[inline]
+fn check_words<BUS, CS>(len: usize, max: Option<usize>) -> Result<(), DeviceError<BUS, CS>> {
+    match max {
+        Some(max) if len > max => Err(DeviceError::TooLong),
+        _ => Ok(()),
+    }
+}
+
+/// Returns [`DeviceError::DelayUnsupported`] if `operations` contain a delay and `delay` is
+/// `false`.
+#[inline]
+fn check_delay<Word, BUS, CS>(
+    operations: &[Operation<'_, Word>],
+    delay: bool,
+) -> Result<(), DeviceError<BUS, CS>> {
+    if !delay
+        && operations
+            .iter()
+            .any(|op| matches!(op, Operation::DelayUs(_)))
+    {
+        return Err(DeviceError::DelayUnsupported);
+    }
+    Ok(())
+}
+
+/// Runs `f` on `bus` with `cs` asserted, then flushes the bus and deasserts `cs`.
+///
+/// Like in the `SpiDevice::transaction` implementations, the bus is flushed and CS deasserted
+/// even if `f` fails, and bus errors take priority over CS errors.
+#[inline]
+fn transaction_with<Word, BUS, CS, R>(
+    bus: &mut BUS,
+    cs: &mut CS,
+    f: impl FnOnce(&mut BUS) -> Result<R, BUS::Error>,
+) -> Result<R, DeviceError<BUS::Error, CS::Error>>
+where
+    Word: Copy + 'static,
+    BUS: SpiBus<Word>,
+    CS: OutputPin,
+{
+    cs.set_low().map_err(DeviceError::Cs)?;
+
+    let f_res = f(bus);
+
+    // On failure, it's important to still flush and deassert CS.
+    let flush_res = bus.flush();
+    let cs_res = cs.set_high();
+
+    let r = f_res.map_err(DeviceError::Spi)?;
+    flush_res.map_err(DeviceError::Spi)?;
+    cs_res.map_err(DeviceError::Cs)?;
+
+    Ok(r)
+}
+
+/// Performs `operations` on `bus`, flushing it before each delay.
+///
+/// On failure, the index of the failed operation is stored in `failed_at`.
+#[inline]
+fn run_operations<Word, BUS, D>(
+    bus: &mut BUS,
+    delay: &mut D,
+    operations: &mut [Operation<'_, Word>],
+    failed_at: &mut Option<usize>,
+) -> Result<(), BUS::Error>
+where
+    Word: Copy + 'static,
+    BUS: SpiBus<Word>,
+    D: DelayUs,
+{
+    for (i, op) in operations.iter_mut().enumerate() {
+        let res = match op {
+            Operation::Read(buf) => bus.read(buf),
+            Operation::Write(buf) => bus.write(buf),
+            Operation::Transfer(read, write) => bus.transfer(read, write),
+            Operation::TransferInPlace(buf) => bus.transfer_in_place(buf),
+            Operation::DelayUs(us) => bus.flush().map(|()| delay.delay_us(*us)),
+        };
+        if let Err(e) = res {
+            *failed_at = Some(i);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Writes `data` to `bus` in chunks of at most `N` words.
+#[inline]
+fn write_chunks<const N: usize, Word, BUS>(bus: &mut BUS, data: &[Word]) -> Result<(), BUS::Error>
+where
+    Word: Copy + 'static,
+    BUS: SpiBus<Word>,
+{
+    data.chunks(N).try_for_each(|chunk| bus.write(chunk))
+}
+
+/// Writes `data` to `bus` in chunks of the bus's maximum transfer size, or of `N` words if it
+/// has none or reports 0.
+#[inline]
+fn write_bus_chunks<const N: usize, Word, BUS>(
+    bus: &mut BUS,
+    data: &[Word],
+) -> Result<(), BUS::Error>
+where
+    Word: Copy + 'static,
+    BUS: SpiBus<Word> + MaxTransferSize,
+{
+    let chunk = match bus.max_transfer_words() {
+        Some(0) | None => N,
+        Some(max) => max,
+    };
+    assert!(chunk > 0, "chunk size must not be 0");
+    data.chunks(chunk).try_for_each(|chunk| bus.write(chunk))
+}
+
+/// Writes the words of `words` to `bus`, buffering them in chunks of at most `N` words.
+///
+/// Returns the number of words written. If there are more than `max` words, the chunk going over
+/// it is not written, and the returned number includes it, so that it is over `max` too.
+#[inline]
+fn write_iter<const N: usize, BUS, I>(
+    bus: &mut BUS,
+    words: I,
+    max: Option<usize>,
+) -> Result<usize, BUS::Error>
+where
+    BUS: SpiBus<u8>,
+    I: IntoIterator<Item = u8>,
+{
+    assert!(N > 0, "chunk size must not be 0");
+
+    let mut words = words.into_iter();
+    let mut buf = [0; N];
+    let mut total = 0;
+    loop {
+        let mut len = 0;
+        for (slot, word) in buf.iter_mut().zip(&mut words) {
+            *slot = word;
+            len += 1;
+        }
+        total += len;
+        if len == 0 || matches!(max, Some(max) if total > max) {
+            return Ok(total);
+        }
+        bus.write(&buf[..len])?;
+        if len < N {
+            return Ok(total);
+        }
+    }
+}
+
+/// Writes `cmd` to `bus`, clocks `dummy` bytes and discards them, then reads into `buf`.
+#[inline]
+fn read_with_dummy<BUS>(
+    bus: &mut BUS,
+    cmd: &[u8],
+    dummy: usize,
+    buf: &mut [u8],
+) -> Result<(), BUS::Error>
+where
+    BUS: SpiBus<u8>,
+{
+    bus.write(cmd)?;
+    read_dummy(bus, dummy)?;
+    bus.read(buf)
+}
+
+/// Clocks `count` bytes on `bus`, discarding the received ones.
+#[inline]
+fn read_dummy<BUS>(bus: &mut BUS, count: usize) -> Result<(), BUS::Error>
+where
+    BUS: SpiBus<u8>,
+{
+    let mut scratch = [0; 16];
+    let mut remaining = count;
+    while remaining > 0 {
+        let len = remaining.min(scratch.len());
+        bus.read(&mut scratch[..len])?;
+        remaining -= len;
+    }
+    Ok(())
+}
+
+/// Transfers `write` on `bus`, discarding the first `skip` received bytes and reading the
+/// following ones into `read`.
+#[inline]
+fn transfer_tail<BUS>(
+    bus: &mut BUS,
+    write: &[u8],
+    skip: usize,
+    read: &mut [u8],
+) -> Result<(), BUS::Error>
+where
+    BUS: SpiBus<u8>,
+{
+    let head = write.len().min(skip);
+    bus.write(&write[..head])?;
+    read_dummy(bus, skip - head)?;
+    bus.transfer(read, &write[head..])
+}
+
+/// Writes `data` to `bus`, followed by its CRC computed with `crc`.
+#[inline]
+fn write_with_crc<BUS, C>(bus: &mut BUS, data: &[u8], crc: C) -> Result<(), BUS::Error>
+where
+    BUS: SpiBus<u8>,
+    C: FnOnce(&[u8]) -> u8,
+{
+    let crc = crc(data);
+    bus.write(data)?;
+    bus.write(&[crc])
+}
+
 /// Dummy `DelayUs` implementation that panics on use.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
@@ -71,3 +745,42 @@ impl embedded_hal_async::delay::DelayUs for NoDelay {
         no_delay_panic();
     }
 }
+
+/// Optional `DelayUs` implementation, used by [`ExclusiveDevice::new_maybe_delay`].
+///
+/// Delays are forwarded to the inner delay if there is one. Without one, it panics on use like
+/// [`NoDelay`], but devices using it return [`DeviceError::DelayUnsupported`] instead of running
+/// transactions containing delays.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct MaybeDelay<D>(pub(crate) Option<D>);
+
+impl<D: embedded_hal::delay::DelayUs> embedded_hal::delay::DelayUs for MaybeDelay<D> {
+    #[inline]
+    fn delay_us(&mut self, us: u32) {
+        match &mut self.0 {
+            Some(delay) => delay.delay_us(us),
+            None => no_delay_panic(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl<D: embedded_hal_async::delay::DelayUs> embedded_hal_async::delay::DelayUs for MaybeDelay<D> {
+    #[inline]
+    async fn delay_us(&mut self, us: u32) {
+        match &mut self.0 {
+            Some(delay) => delay.delay_us(us).await,
+            None => no_delay_panic(),
+        }
+    }
+
+    #[inline]
+    async fn delay_ms(&mut self, ms: u32) {
+        match &mut self.0 {
+            Some(delay) => delay.delay_ms(ms).await,
+            None => no_delay_panic(),
+        }
+    }
+}
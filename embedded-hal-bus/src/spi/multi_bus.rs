@@ -0,0 +1,210 @@
+//! SPI device table spanning multiple buses.
+
+use core::fmt;
+use core::ops::{Index, IndexMut};
+
+use embedded_hal::spi::{Error, ErrorKind, ErrorType, Operation, SpiDevice};
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// Table of [`SpiDevice`]s, dispatching transactions to the selected one.
+///
+/// This gives a uniform view over the devices of a board, even when they are spread over
+/// several buses: each device can be accessed by its index, either by [indexing](Index) the
+/// table or by [selecting](Self::select) it and using the table itself as an `SpiDevice`.
+///
+/// All devices must have the same type. Devices on two buses of different types can be stored
+/// as [`EitherDevice`]s, which can themselves be nested for more buses.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::SpiDevice;
+/// use embedded_hal_bus::spi::{EitherDevice, MultiBusDevice};
+/// # use core::cell::RefCell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::spi::{self, ErrorKind, Operation};
+/// # type Log = RefCell<Vec<(&'static str, u8)>>;
+/// # fn record(log: &Log, name: &'static str, operations: &mut [Operation<'_, u8>]) {
+/// #     for op in operations {
+/// #         if let Operation::Write(words) = op {
+/// #             log.borrow_mut().extend(words.iter().map(|w| (name, *w)));
+/// #         }
+/// #     }
+/// # }
+/// # struct Bus1Device<'a>(&'a Log, &'static str);
+/// # impl spi::ErrorType for Bus1Device<'_> { type Error = Infallible; }
+/// # impl SpiDevice for Bus1Device<'_> {
+/// #     fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Infallible> {
+/// #         Ok(record(self.0, self.1, operations))
+/// #     }
+/// # }
+/// # struct Bus2Device<'a>(&'a Log, &'static str);
+/// # impl spi::ErrorType for Bus2Device<'_> { type Error = ErrorKind; }
+/// # impl SpiDevice for Bus2Device<'_> {
+/// #     fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), ErrorKind> {
+/// #         Ok(record(self.0, self.1, operations))
+/// #     }
+/// # }
+/// # let log = RefCell::new(Vec::new());
+/// // The first bus carries two devices, and the second bus, of a different type, carries one.
+/// let mut devices = MultiBusDevice::new([
+///     EitherDevice::A(Bus1Device(&log, "flash")),
+///     EitherDevice::A(Bus1Device(&log, "eeprom")),
+///     EitherDevice::B(Bus2Device(&log, "display")),
+/// ]);
+///
+/// devices[2].write(&[0x01]).unwrap();
+///
+/// devices.select(1);
+/// devices.write(&[0x02]).unwrap();
+///
+/// assert_eq!(*log.borrow(), [("display", 0x01), ("eeprom", 0x02)]);
+/// ```
+pub struct MultiBusDevice<D, const N: usize> {
+    devices: [D; N],
+    selected: usize,
+}
+
+impl<D, const N: usize> MultiBusDevice<D, N> {
+    /// Create a new `MultiBusDevice`, with the first device selected.
+    #[inline]
+    pub fn new(devices: [D; N]) -> Self {
+        Self {
+            devices,
+            selected: 0,
+        }
+    }
+
+    /// Select the device transactions are dispatched to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than `N`.
+    #[inline]
+    pub fn select(&mut self, index: usize) {
+        assert!(index < N, "device index out of range");
+        self.selected = index;
+    }
+
+    /// Returns the index of the selected device.
+    #[inline]
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Returns a mutable reference to the device at `index`, or `None` if it's out of range.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut D> {
+        self.devices.get_mut(index)
+    }
+
+    /// Destroys the table, returning the devices.
+    #[inline]
+    pub fn into_inner(self) -> [D; N] {
+        self.devices
+    }
+}
+
+impl<D, const N: usize> Index<usize> for MultiBusDevice<D, N> {
+    type Output = D;
+
+    #[inline]
+    fn index(&self, index: usize) -> &D {
+        &self.devices[index]
+    }
+}
+
+impl<D, const N: usize> IndexMut<usize> for MultiBusDevice<D, N> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut D {
+        &mut self.devices[index]
+    }
+}
+
+impl<D: ErrorType, const N: usize> ErrorType for MultiBusDevice<D, N> {
+    type Error = D::Error;
+}
+
+impl<Word: Copy + 'static, D: SpiDevice<Word>, const N: usize> SpiDevice<Word>
+    for MultiBusDevice<D, N>
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        self.devices[self.selected].transaction(operations)
+    }
+}
+
+/// [`SpiDevice`] that is one of two device types.
+///
+/// This allows storing devices on buses of different types in a single [`MultiBusDevice`].
+#[derive(Debug)]
+pub enum EitherDevice<A, B> {
+    /// Device of the first type.
+    A(A),
+    /// Device of the second type.
+    B(B),
+}
+
+impl<A: ErrorType, B: ErrorType> ErrorType for EitherDevice<A, B> {
+    type Error = EitherError<A::Error, B::Error>;
+}
+
+impl<Word: Copy + 'static, A, B> SpiDevice<Word> for EitherDevice<A, B>
+where
+    A: SpiDevice<Word>,
+    B: SpiDevice<Word>,
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        match self {
+            Self::A(device) => device.transaction(operations).map_err(EitherError::A),
+            Self::B(device) => device.transaction(operations).map_err(EitherError::B),
+        }
+    }
+}
+
+/// Error type for [`EitherDevice`] operations.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum EitherError<A, B> {
+    /// Error of a device of the first type.
+    A(A),
+    /// Error of a device of the second type.
+    B(B),
+}
+
+impl<A: Error, B: Error> Error for EitherError<A, B> {
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::A(e) => e.kind(),
+            Self::B(e) => e.kind(),
+        }
+    }
+}
+
+impl<A: fmt::Display, B: fmt::Display> fmt::Display for EitherError<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::A(e) => e.fmt(f),
+            Self::B(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<A, B> std::error::Error for EitherError<A, B>
+where
+    A: std::error::Error + 'static,
+    B: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::A(e) => Some(e),
+            Self::B(e) => Some(e),
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use embedded_hal::delay::DelayUs;
 use embedded_hal::digital::OutputPin;
 use embedded_hal::spi::{ErrorType, Operation, SpiBus, SpiDevice};
-use std::sync::Mutex;
+use std::sync::{Mutex, MutexGuard};
 
-use super::DeviceError;
+use super::{DeviceError, DeviceStats, TransactionError};
 
 /// `std` `Mutex`-based shared bus [`SpiDevice`] implementation.
 ///
@@ -13,18 +13,121 @@ use super::DeviceError;
 /// Sharing is implemented with a `std` [`Mutex`](std::sync::Mutex). It allows a single bus across multiple threads,
 /// with finer-grained locking than [`CriticalSectionDevice`](super::CriticalSectionDevice). The downside is
 /// it is only available in `std` targets.
+///
+/// If a thread panics while holding the bus lock, the `Mutex` becomes poisoned and transactions
+/// return [`DeviceError::Poisoned`] from then on. See [`MutexDevice::ignore_poison`] to recover.
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub struct MutexDevice<'a, BUS, CS, D> {
     bus: &'a Mutex<BUS>,
     cs: CS,
     delay: D,
+    max_transaction_bytes: Option<usize>,
+    stats: DeviceStats,
+    ignore_poison: bool,
 }
 
 impl<'a, BUS, CS, D> MutexDevice<'a, BUS, CS, D> {
     /// Create a new MutexDevice.
     #[inline]
     pub fn new(bus: &'a Mutex<BUS>, cs: CS, delay: D) -> Self {
-        Self { bus, cs, delay }
+        Self {
+            bus,
+            cs,
+            delay,
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
+            ignore_poison: false,
+        }
+    }
+
+    /// Reject transactions longer than `max` words with [`DeviceError::TooLong`].
+    ///
+    /// The length of a transaction is counted like [`operations_byte_count`](super::operations_byte_count)
+    /// does, in words (bytes for the usual 8-bit words). Longer transactions are rejected before
+    /// touching the bus or CS, which helps catching driver bugs that would otherwise stall the
+    /// bus with a huge transfer. By default, transactions are not limited.
+    #[inline]
+    pub fn with_max_transaction_bytes(mut self, max: usize) -> Self {
+        self.max_transaction_bytes = Some(max);
+        self
+    }
+
+    /// Returns the statistics of the transactions performed by this device.
+    ///
+    /// All the transactions of this device are counted, including the ones of helper methods such
+    /// as [`write_chunks`](super::SpiDeviceExt::write_chunks).
+    #[cfg(feature = "stats")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    #[inline]
+    pub fn stats(&self) -> super::Stats {
+        self.stats.get()
+    }
+
+    /// Keep using the bus after another thread panicked while holding its lock.
+    ///
+    /// From then on, this device ignores the poisoning of the `Mutex`: it never returns
+    /// [`DeviceError::Poisoned`], and uses the bus even if another thread panics while holding
+    /// it later on. This can't be undone. This lets a supervisor recover the bus after a worker
+    /// thread panics mid-transaction, assuming the bus is in a usable state (it may be worth
+    /// re-initializing it first).
+    ///
+    /// The poison flag of the shared `Mutex` itself isn't cleared (`Mutex::clear_poison` requires
+    /// a newer Rust than this crate's MSRV), so other devices on the same bus keep returning
+    /// `DeviceError::Poisoned` until this method is called on them too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use embedded_hal::spi::SpiDevice;
+    /// use embedded_hal_bus::spi::{DeviceError, MutexDevice};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # struct Bus;
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn write(&mut self, _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// let bus = Arc::new(Mutex::new(Bus));
+    /// let mut device = MutexDevice::new_no_delay(&*bus, Cs);
+    ///
+    /// // A worker thread panics while holding the bus.
+    /// let worker_bus = bus.clone();
+    /// std::thread::spawn(move || {
+    ///     let _guard = worker_bus.lock().unwrap();
+    ///     panic!("worker failed");
+    /// })
+    /// .join()
+    /// .unwrap_err();
+    /// assert_eq!(device.write(&[0x01]), Err(DeviceError::Poisoned));
+    ///
+    /// device.ignore_poison();
+    /// assert_eq!(device.write(&[0x01]), Ok(()));
+    /// assert!(bus.is_poisoned());
+    /// ```
+    #[inline]
+    pub fn ignore_poison(&mut self) {
+        self.ignore_poison = true;
+    }
+
+    /// Locks the bus, mapping poisoning to [`DeviceError::Poisoned`] unless it's ignored.
+    #[inline]
+    fn lock<E, F>(&self) -> Result<MutexGuard<'a, BUS>, DeviceError<E, F>> {
+        match self.bus.lock() {
+            Ok(guard) => Ok(guard),
+            Err(e) if self.ignore_poison => Ok(e.into_inner()),
+            Err(_) => Err(DeviceError::Poisoned),
+        }
     }
 }
 
@@ -41,10 +144,50 @@ impl<'a, BUS, CS> MutexDevice<'a, BUS, CS, super::NoDelay> {
             bus,
             cs,
             delay: super::NoDelay,
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
+            ignore_poison: false,
         }
     }
 }
 
+impl<'a, BUS, CS, D> MutexDevice<'a, BUS, CS, D>
+where
+    CS: OutputPin,
+{
+    /// Perform a transaction, returning the index and kind of the failed operation on error.
+    ///
+    /// This is the same as [`transaction`](SpiDevice::transaction), but the returned
+    /// [`TransactionError`] also tells which operation failed, which makes logged errors more
+    /// informative.
+    #[inline]
+    pub fn transaction_with_context<Word: Copy + 'static>(
+        &mut self,
+        operations: &mut [Operation<'_, Word>],
+    ) -> Result<(), TransactionError<BUS::Error, CS::Error>>
+    where
+        BUS: SpiBus<Word>,
+        D: DelayUs,
+    {
+        if operations.is_empty() {
+            return Ok(());
+        }
+        // Locked outside of the closure, which can only borrow the fields of `self`.
+        let bus =
+            super::check_len(operations, self.max_transaction_bytes).and_then(|()| self.lock());
+        self.stats.record(super::operations_len(operations), || {
+            let mut failed_at = None;
+            let bus = &mut *bus?;
+
+            let delay = &mut self.delay;
+            let res = super::transaction_with(bus, &mut self.cs, |bus| {
+                super::run_operations(bus, delay, operations, &mut failed_at)
+            });
+            res.map_err(|e| TransactionError::new(e, failed_at, operations))
+        })
+    }
+}
+
 impl<'a, BUS, CS, D> ErrorType for MutexDevice<'a, BUS, CS, D>
 where
     BUS: ErrorType,
@@ -53,6 +196,33 @@ where
     type Error = DeviceError<BUS::Error, CS::Error>;
 }
 
+impl<'a, BUS, CS, D> super::ext::sealed::Device for MutexDevice<'a, BUS, CS, D>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+    type Cs = CS;
+
+    #[inline]
+    fn max_transaction_bytes(&self) -> Option<usize> {
+        self.max_transaction_bytes
+    }
+
+    #[inline]
+    fn lock_bus<R>(
+        &mut self,
+        f: impl FnOnce(&mut BUS, &mut CS) -> Result<R, Self::Error>,
+    ) -> Result<R, Self::Error> {
+        f(&mut *self.lock()?, &mut self.cs)
+    }
+
+    #[inline]
+    fn record<R>(&mut self, words: usize, res: Result<R, Self::Error>) -> Result<R, Self::Error> {
+        self.stats.record(words, || res)
+    }
+}
+
 impl<'a, Word: Copy + 'static, BUS, CS, D> SpiDevice<Word> for MutexDevice<'a, BUS, CS, D>
 where
     BUS: SpiBus<Word>,
@@ -61,30 +231,39 @@ where
 {
     #[inline]
     fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
-        let bus = &mut *self.bus.lock().unwrap();
-
-        self.cs.set_low().map_err(DeviceError::Cs)?;
-
-        let op_res = operations.iter_mut().try_for_each(|op| match op {
-            Operation::Read(buf) => bus.read(buf),
-            Operation::Write(buf) => bus.write(buf),
-            Operation::Transfer(read, write) => bus.transfer(read, write),
-            Operation::TransferInPlace(buf) => bus.transfer_in_place(buf),
-            Operation::DelayUs(us) => {
-                bus.flush()?;
-                self.delay.delay_us(*us);
-                Ok(())
-            }
-        });
-
-        // On failure, it's important to still flush and deassert CS.
-        let flush_res = bus.flush();
-        let cs_res = self.cs.set_high();
-
-        op_res.map_err(DeviceError::Spi)?;
-        flush_res.map_err(DeviceError::Spi)?;
-        cs_res.map_err(DeviceError::Cs)?;
-
-        Ok(())
+        // An empty transaction does nothing, without even asserting CS.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        // Locked outside of the closure, which can only borrow the fields of `self`.
+        let bus =
+            super::check_len(operations, self.max_transaction_bytes).and_then(|()| self.lock());
+        self.stats.record(super::operations_len(operations), || {
+            let bus = &mut *bus?;
+
+            self.cs.set_low().map_err(DeviceError::Cs)?;
+
+            let op_res = operations.iter_mut().try_for_each(|op| match op {
+                Operation::Read(buf) => bus.read(buf),
+                Operation::Write(buf) => bus.write(buf),
+                Operation::Transfer(read, write) => bus.transfer(read, write),
+                Operation::TransferInPlace(buf) => bus.transfer_in_place(buf),
+                Operation::DelayUs(us) => {
+                    bus.flush()?;
+                    self.delay.delay_us(*us);
+                    Ok(())
+                }
+            });
+
+            // On failure, it's important to still flush and deassert CS.
+            let flush_res = bus.flush();
+            let cs_res = self.cs.set_high();
+
+            op_res.map_err(DeviceError::Spi)?;
+            flush_res.map_err(DeviceError::Spi)?;
+            cs_res.map_err(DeviceError::Cs)?;
+
+            Ok(())
+        })
     }
 }
@@ -0,0 +1,195 @@
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::spi::{ErrorType, Operation, SpiBus, SpiDevice};
+use parking_lot::Mutex;
+
+use super::{DeviceError, DeviceStats, TransactionError};
+
+/// `parking_lot` `Mutex`-based shared bus [`SpiDevice`] implementation.
+///
+/// This allows for sharing an [`SpiBus`](embedded_hal::spi::SpiBus), obtaining multiple [`SpiDevice`] instances,
+/// each with its own `CS` pin.
+///
+/// This is the same as [`ParkingLotDevice`](super::ParkingLotDevice), but sharing is implemented with a
+/// [`parking_lot::Mutex`], which is faster to lock and unlock than the `std` one. It also doesn't
+/// support poisoning, so a thread panicking while holding the bus doesn't prevent other devices
+/// from using it, and [`DeviceError::Poisoned`] is never returned.
+#[cfg_attr(docsrs, doc(cfg(feature = "parking-lot")))]
+pub struct ParkingLotDevice<'a, BUS, CS, D> {
+    bus: &'a Mutex<BUS>,
+    cs: CS,
+    delay: D,
+    max_transaction_bytes: Option<usize>,
+    stats: DeviceStats,
+}
+
+impl<'a, BUS, CS, D> ParkingLotDevice<'a, BUS, CS, D> {
+    /// Create a new ParkingLotDevice.
+    #[inline]
+    pub fn new(bus: &'a Mutex<BUS>, cs: CS, delay: D) -> Self {
+        Self {
+            bus,
+            cs,
+            delay,
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
+        }
+    }
+
+    /// Reject transactions longer than `max` words with [`DeviceError::TooLong`].
+    ///
+    /// The length of a transaction is counted like [`operations_byte_count`](super::operations_byte_count)
+    /// does, in words (bytes for the usual 8-bit words). Longer transactions are rejected before
+    /// touching the bus or CS, which helps catching driver bugs that would otherwise stall the
+    /// bus with a huge transfer. By default, transactions are not limited.
+    #[inline]
+    pub fn with_max_transaction_bytes(mut self, max: usize) -> Self {
+        self.max_transaction_bytes = Some(max);
+        self
+    }
+
+    /// Returns the statistics of the transactions performed by this device.
+    ///
+    /// All the transactions of this device are counted, including the ones of helper methods such
+    /// as [`write_chunks`](super::SpiDeviceExt::write_chunks).
+    #[cfg(feature = "stats")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    #[inline]
+    pub fn stats(&self) -> super::Stats {
+        self.stats.get()
+    }
+}
+
+impl<'a, BUS, CS> ParkingLotDevice<'a, BUS, CS, super::NoDelay> {
+    /// Create a new ParkingLotDevice without support for in-transaction delays.
+    ///
+    /// # Panics
+    ///
+    /// The returned device will panic if you try to execute a transaction
+    /// that contains any operations of type `Operation::DelayUs`.
+    #[inline]
+    pub fn new_no_delay(bus: &'a Mutex<BUS>, cs: CS) -> Self {
+        Self {
+            bus,
+            cs,
+            delay: super::NoDelay,
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
+        }
+    }
+}
+
+impl<'a, BUS, CS, D> ParkingLotDevice<'a, BUS, CS, D>
+where
+    CS: OutputPin,
+{
+    /// Perform a transaction, returning the index and kind of the failed operation on error.
+    ///
+    /// This is the same as [`transaction`](SpiDevice::transaction), but the returned
+    /// [`TransactionError`] also tells which operation failed, which makes logged errors more
+    /// informative.
+    #[inline]
+    pub fn transaction_with_context<Word: Copy + 'static>(
+        &mut self,
+        operations: &mut [Operation<'_, Word>],
+    ) -> Result<(), TransactionError<BUS::Error, CS::Error>>
+    where
+        BUS: SpiBus<Word>,
+        D: DelayUs,
+    {
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.stats.record(super::operations_len(operations), || {
+            super::check_len(operations, self.max_transaction_bytes)?;
+
+            let mut failed_at = None;
+            let bus = &mut *self.bus.lock();
+
+            let delay = &mut self.delay;
+            let res = super::transaction_with(bus, &mut self.cs, |bus| {
+                super::run_operations(bus, delay, operations, &mut failed_at)
+            });
+            res.map_err(|e| TransactionError::new(e, failed_at, operations))
+        })
+    }
+}
+
+impl<'a, BUS, CS, D> ErrorType for ParkingLotDevice<'a, BUS, CS, D>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Error = DeviceError<BUS::Error, CS::Error>;
+}
+
+impl<'a, BUS, CS, D> super::ext::sealed::Device for ParkingLotDevice<'a, BUS, CS, D>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+    type Cs = CS;
+
+    #[inline]
+    fn max_transaction_bytes(&self) -> Option<usize> {
+        self.max_transaction_bytes
+    }
+
+    #[inline]
+    fn lock_bus<R>(
+        &mut self,
+        f: impl FnOnce(&mut BUS, &mut CS) -> Result<R, Self::Error>,
+    ) -> Result<R, Self::Error> {
+        f(&mut self.bus.lock(), &mut self.cs)
+    }
+
+    #[inline]
+    fn record<R>(&mut self, words: usize, res: Result<R, Self::Error>) -> Result<R, Self::Error> {
+        self.stats.record(words, || res)
+    }
+}
+
+impl<'a, Word: Copy + 'static, BUS, CS, D> SpiDevice<Word> for ParkingLotDevice<'a, BUS, CS, D>
+where
+    BUS: SpiBus<Word>,
+    CS: OutputPin,
+    D: DelayUs,
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        // An empty transaction does nothing, without even asserting CS.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.stats.record(super::operations_len(operations), || {
+            super::check_len(operations, self.max_transaction_bytes)?;
+
+            let bus = &mut *self.bus.lock();
+
+            self.cs.set_low().map_err(DeviceError::Cs)?;
+
+            let op_res = operations.iter_mut().try_for_each(|op| match op {
+                Operation::Read(buf) => bus.read(buf),
+                Operation::Write(buf) => bus.write(buf),
+                Operation::Transfer(read, write) => bus.transfer(read, write),
+                Operation::TransferInPlace(buf) => bus.transfer_in_place(buf),
+                Operation::DelayUs(us) => {
+                    bus.flush()?;
+                    self.delay.delay_us(*us);
+                    Ok(())
+                }
+            });
+
+            // On failure, it's important to still flush and deassert CS.
+            let flush_res = bus.flush();
+            let cs_res = self.cs.set_high();
+
+            op_res.map_err(DeviceError::Spi)?;
+            flush_res.map_err(DeviceError::Spi)?;
+            cs_res.map_err(DeviceError::Cs)?;
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,149 @@
+//! SPI bus adapter clocking out a fixed byte during reads.
+
+use embedded_hal::spi::{ErrorType, SpiBus};
+
+/// [`SpiBus`] adapter that clocks out a configurable fill byte while reading.
+///
+/// [`SpiBus::read`] leaves the words written during a read up to the implementation, usually
+/// 0x00 or 0xFF, but some devices interpret them, for example SD cards expect 0xFF. This adapter
+/// makes the fill byte explicit: reads are done with [`SpiBus::transfer_in_place`] on a buffer
+/// filled with it. The words of a [`SpiBus::transfer`] past the end of the write buffer are read
+/// the same way.
+///
+/// Like [`ByteDelayed`](super::ByteDelayed), the adapter wraps the bus, so it can be used as the
+/// bus of any of the [`SpiDevice`](embedded_hal::spi::SpiDevice) implementations in this crate.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::SpiDevice;
+/// use embedded_hal_bus::spi::{ExclusiveDevice, ReadFill};
+/// # use core::convert::Infallible;
+/// # use embedded_hal::{digital, spi};
+/// # #[derive(Default)]
+/// # struct Bus { mosi: Vec<u8> }
+/// # impl spi::ErrorType for Bus { type Error = Infallible; }
+/// # impl spi::SpiBus for Bus {
+/// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { unreachable!() }
+/// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.mosi.extend(words); Ok(()) }
+/// #     fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Infallible> {
+/// #         assert_eq!(read.len(), write.len());
+/// #         self.mosi.extend(write);
+/// #         read.fill(0x42);
+/// #         Ok(())
+/// #     }
+/// #     fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+/// #         self.mosi.extend(&*words);
+/// #         words.fill(0x42);
+/// #         Ok(())
+/// #     }
+/// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// # struct Cs;
+/// # impl digital::ErrorType for Cs { type Error = Infallible; }
+/// # impl digital::OutputPin for Cs {
+/// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+/// # }
+/// let mut device = ExclusiveDevice::new_no_delay(ReadFill::new(Bus::default(), 0xFF), Cs);
+///
+/// let mut buf = [0; 3];
+/// device.read(&mut buf).unwrap();
+/// assert_eq!(buf, [0x42; 3]);
+///
+/// let mut buf = [0; 4];
+/// device.transfer(&mut buf, &[0x01, 0x02]).unwrap();
+///
+/// // The bus saw the fill byte on MOSI while reading.
+/// assert_eq!(device.bus().bus().mosi, [0xFF, 0xFF, 0xFF, 0x01, 0x02, 0xFF, 0xFF]);
+/// ```
+pub struct ReadFill<BUS> {
+    bus: BUS,
+    fill: u8,
+}
+
+impl<BUS> ReadFill<BUS> {
+    /// Create a new `ReadFill`, clocking out `fill` while reading.
+    #[inline]
+    pub fn new(bus: BUS, fill: u8) -> Self {
+        Self { bus, fill }
+    }
+
+    /// Returns the byte clocked out while reading.
+    #[inline]
+    pub fn fill(&self) -> u8 {
+        self.fill
+    }
+
+    /// Sets the byte clocked out while reading.
+    #[inline]
+    pub fn set_fill(&mut self, fill: u8) {
+        self.fill = fill;
+    }
+
+    /// Returns a reference to the underlying bus object.
+    #[inline]
+    pub fn bus(&self) -> &BUS {
+        &self.bus
+    }
+
+    /// Returns a mutable reference to the underlying bus object.
+    #[inline]
+    pub fn bus_mut(&mut self) -> &mut BUS {
+        &mut self.bus
+    }
+
+    /// Destroys the adapter, returning the bus.
+    #[inline]
+    pub fn into_inner(self) -> BUS {
+        self.bus
+    }
+}
+
+impl<BUS> ErrorType for ReadFill<BUS>
+where
+    BUS: ErrorType,
+{
+    type Error = BUS::Error;
+}
+
+impl<BUS> SpiBus<u8> for ReadFill<BUS>
+where
+    BUS: SpiBus<u8>,
+{
+    #[inline]
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        if words.is_empty() {
+            return Ok(());
+        }
+        words.fill(self.fill);
+        self.bus.transfer_in_place(words)
+    }
+
+    #[inline]
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.bus.write(words)
+    }
+
+    #[inline]
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        if read.len() <= write.len() {
+            return self.bus.transfer(read, write);
+        }
+        let (read, tail) = read.split_at_mut(write.len());
+        if !write.is_empty() {
+            self.bus.transfer(read, write)?;
+        }
+        self.read(tail)
+    }
+
+    #[inline]
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.bus.transfer_in_place(words)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.bus.flush()
+    }
+}
@@ -3,7 +3,7 @@ use embedded_hal::delay::DelayUs;
 use embedded_hal::digital::OutputPin;
 use embedded_hal::spi::{ErrorType, Operation, SpiBus, SpiDevice};
 
-use super::DeviceError;
+use super::{DeviceError, DeviceStats, TransactionError};
 
 /// `RefCell`-based shared bus [`SpiDevice`] implementation.
 ///
@@ -17,13 +17,92 @@ pub struct RefCellDevice<'a, BUS, CS, D> {
     bus: &'a RefCell<BUS>,
     cs: CS,
     delay: D,
+    max_transaction_bytes: Option<usize>,
+    stats: DeviceStats,
 }
 
 impl<'a, BUS, CS, D> RefCellDevice<'a, BUS, CS, D> {
     /// Create a new RefCellDevice.
     #[inline]
     pub fn new(bus: &'a RefCell<BUS>, cs: CS, delay: D) -> Self {
-        Self { bus, cs, delay }
+        Self {
+            bus,
+            cs,
+            delay,
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
+        }
+    }
+
+    /// Reject transactions longer than `max` words with [`DeviceError::TooLong`].
+    ///
+    /// The length of a transaction is counted like [`operations_byte_count`](super::operations_byte_count)
+    /// does, in words (bytes for the usual 8-bit words). Longer transactions are rejected before
+    /// touching the bus or CS, which helps catching driver bugs that would otherwise stall the
+    /// bus with a huge transfer. By default, transactions are not limited.
+    #[inline]
+    pub fn with_max_transaction_bytes(mut self, max: usize) -> Self {
+        self.max_transaction_bytes = Some(max);
+        self
+    }
+
+    /// Returns the statistics of the transactions performed by this device.
+    ///
+    /// All the transactions of this device are counted, including the ones of helper methods such
+    /// as [`write_chunks`](super::SpiDeviceExt::write_chunks).
+    #[cfg(feature = "stats")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    #[inline]
+    pub fn stats(&self) -> super::Stats {
+        self.stats.get()
+    }
+
+    /// Run `f` with exclusive access to the bus, for example to reset or reinitialize it after an
+    /// error left it in a bad state.
+    ///
+    /// CS is not asserted or deasserted, and the bus is not flushed. Like for
+    /// [`SpiDevice::transaction`], this panics if the bus `RefCell` is already borrowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::cell::RefCell;
+    /// use embedded_hal::spi::{ErrorKind, SpiDevice};
+    /// use embedded_hal_bus::spi::{DeviceError, RefCellDevice};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # struct Bus { overrun: bool }
+    /// # impl Bus {
+    /// #     fn reset_fifo(&mut self) { self.overrun = false; }
+    /// #     fn check(&self) -> Result<(), ErrorKind> { if self.overrun { Err(ErrorKind::Overrun) } else { Ok(()) } }
+    /// # }
+    /// # impl spi::ErrorType for Bus { type Error = ErrorKind; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), ErrorKind> { self.check() }
+    /// #     fn write(&mut self, _: &[u8]) -> Result<(), ErrorKind> { self.check() }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), ErrorKind> { self.check() }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), ErrorKind> { self.check() }
+    /// #     fn flush(&mut self) -> Result<(), ErrorKind> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// // A bus whose FIFO overran.
+    /// let bus = RefCell::new(Bus { overrun: true });
+    /// let mut device = RefCellDevice::new_no_delay(&bus, Cs);
+    ///
+    /// let res = device.write(&[0x01]);
+    /// assert_eq!(res, Err(DeviceError::Spi(ErrorKind::Overrun)));
+    ///
+    /// device.with_bus(|bus| bus.reset_fifo());
+    /// assert_eq!(device.write(&[0x01]), Ok(()));
+    /// ```
+    #[inline]
+    pub fn with_bus<R>(&self, f: impl FnOnce(&mut BUS) -> R) -> R {
+        f(&mut self.bus.borrow_mut())
     }
 }
 
@@ -40,7 +119,113 @@ impl<'a, BUS, CS> RefCellDevice<'a, BUS, CS, super::NoDelay> {
             bus,
             cs,
             delay: super::NoDelay,
+            max_transaction_bytes: None,
+            stats: DeviceStats::default(),
+        }
+    }
+}
+
+impl<'a, BUS, CS, D> RefCellDevice<'a, BUS, CS, D>
+where
+    CS: OutputPin,
+{
+    /// Perform a transaction, returning an error instead of panicking if the bus is already in use.
+    ///
+    /// [`SpiDevice::transaction`] panics if the bus `RefCell` is already borrowed, for example when
+    /// another `RefCellDevice` on the same bus is used reentrantly from a nested interrupt handler.
+    /// This method returns [`DeviceError::BusBusy`] in that case instead, without touching CS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::cell::RefCell;
+    /// use embedded_hal::spi::Operation;
+    /// use embedded_hal_bus::spi::{DeviceError, RefCellDevice};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::{digital, spi};
+    /// # struct Bus;
+    /// # impl spi::ErrorType for Bus { type Error = Infallible; }
+    /// # impl spi::SpiBus for Bus {
+    /// #     fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn write(&mut self, _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// # struct Cs;
+    /// # impl digital::ErrorType for Cs { type Error = Infallible; }
+    /// # impl digital::OutputPin for Cs {
+    /// #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+    /// # }
+    /// let bus = RefCell::new(Bus);
+    /// let mut device = RefCellDevice::new_no_delay(&bus, Cs);
+    ///
+    /// let guard = bus.borrow_mut();
+    /// let res = device.try_transaction(&mut [Operation::Write(&[0x01])]);
+    /// assert_eq!(res, Err(DeviceError::BusBusy));
+    ///
+    /// drop(guard);
+    /// let res = device.try_transaction(&mut [Operation::Write(&[0x01])]);
+    /// assert_eq!(res, Ok(()));
+    /// ```
+    #[inline]
+    pub fn try_transaction<Word: Copy + 'static>(
+        &mut self,
+        operations: &mut [Operation<'_, Word>],
+    ) -> Result<(), DeviceError<BUS::Error, CS::Error>>
+    where
+        BUS: SpiBus<Word>,
+        D: DelayUs,
+    {
+        // An empty transaction does nothing, without even asserting CS.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.stats.record(super::operations_len(operations), || {
+            super::check_len(operations, self.max_transaction_bytes)?;
+
+            let bus = &mut *self
+                .bus
+                .try_borrow_mut()
+                .map_err(|_| DeviceError::BusBusy)?;
+
+            let delay = &mut self.delay;
+            super::transaction_with(bus, &mut self.cs, |bus| {
+                super::run_operations(bus, delay, operations, &mut None)
+            })
+        })
+    }
+
+    /// Perform a transaction, returning the index and kind of the failed operation on error.
+    ///
+    /// This is the same as [`transaction`](SpiDevice::transaction), but the returned
+    /// [`TransactionError`] also tells which operation failed, which makes logged errors more
+    /// informative.
+    #[inline]
+    pub fn transaction_with_context<Word: Copy + 'static>(
+        &mut self,
+        operations: &mut [Operation<'_, Word>],
+    ) -> Result<(), TransactionError<BUS::Error, CS::Error>>
+    where
+        BUS: SpiBus<Word>,
+        D: DelayUs,
+    {
+        if operations.is_empty() {
+            return Ok(());
         }
+        self.stats.record(super::operations_len(operations), || {
+            super::check_len(operations, self.max_transaction_bytes)?;
+
+            let mut failed_at = None;
+            let bus = &mut *self.bus.borrow_mut();
+
+            let delay = &mut self.delay;
+            let res = super::transaction_with(bus, &mut self.cs, |bus| {
+                super::run_operations(bus, delay, operations, &mut failed_at)
+            });
+            res.map_err(|e| TransactionError::new(e, failed_at, operations))
+        })
     }
 }
 
@@ -52,6 +237,33 @@ where
     type Error = DeviceError<BUS::Error, CS::Error>;
 }
 
+impl<'a, BUS, CS, D> super::ext::sealed::Device for RefCellDevice<'a, BUS, CS, D>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+    type Cs = CS;
+
+    #[inline]
+    fn max_transaction_bytes(&self) -> Option<usize> {
+        self.max_transaction_bytes
+    }
+
+    #[inline]
+    fn lock_bus<R>(
+        &mut self,
+        f: impl FnOnce(&mut BUS, &mut CS) -> Result<R, Self::Error>,
+    ) -> Result<R, Self::Error> {
+        f(&mut self.bus.borrow_mut(), &mut self.cs)
+    }
+
+    #[inline]
+    fn record<R>(&mut self, words: usize, res: Result<R, Self::Error>) -> Result<R, Self::Error> {
+        self.stats.record(words, || res)
+    }
+}
+
 impl<'a, Word: Copy + 'static, BUS, CS, D> SpiDevice<Word> for RefCellDevice<'a, BUS, CS, D>
 where
     BUS: SpiBus<Word>,
@@ -60,30 +272,38 @@ where
 {
     #[inline]
     fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
-        let bus = &mut *self.bus.borrow_mut();
-
-        self.cs.set_low().map_err(DeviceError::Cs)?;
-
-        let op_res = operations.iter_mut().try_for_each(|op| match op {
-            Operation::Read(buf) => bus.read(buf),
-            Operation::Write(buf) => bus.write(buf),
-            Operation::Transfer(read, write) => bus.transfer(read, write),
-            Operation::TransferInPlace(buf) => bus.transfer_in_place(buf),
-            Operation::DelayUs(us) => {
-                bus.flush()?;
-                self.delay.delay_us(*us);
-                Ok(())
-            }
-        });
-
-        // On failure, it's important to still flush and deassert CS.
-        let flush_res = bus.flush();
-        let cs_res = self.cs.set_high();
-
-        op_res.map_err(DeviceError::Spi)?;
-        flush_res.map_err(DeviceError::Spi)?;
-        cs_res.map_err(DeviceError::Cs)?;
-
-        Ok(())
+        // An empty transaction does nothing, without even asserting CS.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.stats.record(super::operations_len(operations), || {
+            super::check_len(operations, self.max_transaction_bytes)?;
+
+            let bus = &mut *self.bus.borrow_mut();
+
+            self.cs.set_low().map_err(DeviceError::Cs)?;
+
+            let op_res = operations.iter_mut().try_for_each(|op| match op {
+                Operation::Read(buf) => bus.read(buf),
+                Operation::Write(buf) => bus.write(buf),
+                Operation::Transfer(read, write) => bus.transfer(read, write),
+                Operation::TransferInPlace(buf) => bus.transfer_in_place(buf),
+                Operation::DelayUs(us) => {
+                    bus.flush()?;
+                    self.delay.delay_us(*us);
+                    Ok(())
+                }
+            });
+
+            // On failure, it's important to still flush and deassert CS.
+            let flush_res = bus.flush();
+            let cs_res = self.cs.set_high();
+
+            op_res.map_err(DeviceError::Spi)?;
+            flush_res.map_err(DeviceError::Spi)?;
+            cs_res.map_err(DeviceError::Cs)?;
+
+            Ok(())
+        })
     }
 }
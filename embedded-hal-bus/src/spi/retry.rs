@@ -0,0 +1,137 @@
+//! SPI device wrapper retrying failed transactions.
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::spi::{Error, ErrorKind, ErrorType, Operation, SpiDevice};
+
+/// [`SpiDevice`] wrapper retrying transactions failing with a recoverable error.
+///
+/// When a transaction fails with an error whose [`ErrorKind`] is accepted by the `is_retryable`
+/// predicate, the whole transaction is run again, up to `max_retries` times, optionally waiting
+/// `delay_us` microseconds before each retry. Errors that aren't retryable, and the error of the
+/// last retry, are returned as is.
+///
+/// Each attempt is a separate transaction of the inner device, which deasserts CS when it ends,
+/// even on failure. The device therefore sees every retry as a new selection.
+///
+/// As [`Operation::TransferInPlace`] overwrites its write data with what it reads, a transaction
+/// containing one can't be replayed: its errors are returned without retrying.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::{ErrorKind, SpiDevice};
+/// use embedded_hal_bus::spi::RetryDevice;
+/// # use embedded_hal::spi;
+/// # struct Device { failures: u32 }
+/// # impl spi::ErrorType for Device { type Error = ErrorKind; }
+/// # impl spi::SpiDevice for Device {
+/// #     fn transaction(&mut self, _: &mut [spi::Operation<'_, u8>]) -> Result<(), ErrorKind> {
+/// #         if self.failures > 0 {
+/// #             self.failures -= 1;
+/// #             return Err(ErrorKind::Overrun);
+/// #         }
+/// #         Ok(())
+/// #     }
+/// # }
+/// # let device = Device { failures: 1 };
+/// // Retry overruns, at most twice.
+/// let mut device = RetryDevice::new_no_delay(device, 2, |kind| kind == ErrorKind::Overrun);
+///
+/// // Fails once with an overrun, then succeeds.
+/// device.write(&[0x01]).unwrap();
+/// ```
+pub struct RetryDevice<D, DL, F> {
+    device: D,
+    delay: DL,
+    delay_us: u32,
+    max_retries: u32,
+    is_retryable: F,
+}
+
+impl<D, DL, F> RetryDevice<D, DL, F>
+where
+    F: FnMut(ErrorKind) -> bool,
+{
+    /// Create a new `RetryDevice`, retrying transactions failing with an error accepted by
+    /// `is_retryable` up to `max_retries` times, waiting `delay_us` microseconds with `delay`
+    /// before each retry. `delay` isn't used if `delay_us` is 0.
+    #[inline]
+    pub fn new(device: D, delay: DL, delay_us: u32, max_retries: u32, is_retryable: F) -> Self {
+        Self {
+            device,
+            delay,
+            delay_us,
+            max_retries,
+            is_retryable,
+        }
+    }
+
+    /// Returns a reference to the inner device.
+    #[inline]
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Returns a mutable reference to the inner device.
+    ///
+    /// Transactions done directly on the inner device are not retried.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+
+    /// Returns the inner device.
+    #[inline]
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+}
+
+impl<D, F> RetryDevice<D, super::NoDelay, F>
+where
+    F: FnMut(ErrorKind) -> bool,
+{
+    /// Create a new `RetryDevice` retrying transactions right away, without waiting between
+    /// attempts.
+    #[inline]
+    pub fn new_no_delay(device: D, max_retries: u32, is_retryable: F) -> Self {
+        Self::new(device, super::NoDelay, 0, max_retries, is_retryable)
+    }
+}
+
+impl<D, DL, F> ErrorType for RetryDevice<D, DL, F>
+where
+    D: ErrorType,
+{
+    type Error = D::Error;
+}
+
+impl<Word: Copy + 'static, D, DL, F> SpiDevice<Word> for RetryDevice<D, DL, F>
+where
+    D: SpiDevice<Word>,
+    DL: DelayUs,
+    F: FnMut(ErrorKind) -> bool,
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        let replayable = !operations
+            .iter()
+            .any(|op| matches!(op, Operation::TransferInPlace(_)));
+        let mut retries = 0;
+        loop {
+            match self.device.transaction(operations) {
+                Err(e)
+                    if replayable
+                        && retries < self.max_retries
+                        && (self.is_retryable)(e.kind()) =>
+                {
+                    retries += 1;
+                    if self.delay_us > 0 {
+                        self.delay.delay_us(self.delay_us);
+                    }
+                }
+                res => return res,
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Scoped async SPI transactions.
+
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::spi::SpiBus as AsyncSpiBus;
+
+use super::DeviceError;
+
+/// Transaction in progress, with CS asserted, created by
+/// [`ExclusiveDevice::transaction_scoped`](super::ExclusiveDevice::transaction_scoped).
+///
+/// It dereferences to the bus, so any [`SpiBus`](AsyncSpiBus) method can be called while CS is
+/// asserted. Call [`finish`](Self::finish) to flush the bus and deassert CS.
+///
+/// If the transaction is dropped without calling [`finish`](Self::finish), for example because
+/// the future running it was cancelled or returned early on an error, CS is deasserted anyway.
+/// The bus can't be flushed from `drop` though, so in that case CS may be deasserted before the
+/// last operation has completed on the bus.
+pub struct ScopedTransaction<'a, BUS, CS: OutputPin> {
+    bus: &'a mut BUS,
+    cs: &'a mut CS,
+    finished: bool,
+}
+
+impl<'a, BUS, CS: OutputPin> ScopedTransaction<'a, BUS, CS> {
+    /// Asserts `cs`, starting the transaction.
+    #[inline]
+    pub(super) fn new(bus: &'a mut BUS, cs: &'a mut CS) -> Result<Self, CS::Error> {
+        cs.set_low()?;
+        Ok(Self {
+            bus,
+            cs,
+            finished: false,
+        })
+    }
+
+    /// Flushes the bus, then deasserts CS, ending the transaction.
+    ///
+    /// CS is deasserted even if flushing fails, and bus errors take priority over CS errors.
+    #[inline]
+    pub async fn finish<Word: Copy + 'static>(
+        mut self,
+    ) -> Result<(), DeviceError<BUS::Error, CS::Error>>
+    where
+        BUS: AsyncSpiBus<Word>,
+    {
+        let flush_res = self.bus.flush().await;
+        self.finished = true;
+        let cs_res = self.cs.set_high();
+
+        flush_res.map_err(DeviceError::Spi)?;
+        cs_res.map_err(DeviceError::Cs)?;
+
+        Ok(())
+    }
+}
+
+impl<BUS, CS: OutputPin> Deref for ScopedTransaction<'_, BUS, CS> {
+    type Target = BUS;
+
+    #[inline]
+    fn deref(&self) -> &BUS {
+        self.bus
+    }
+}
+
+impl<BUS, CS: OutputPin> DerefMut for ScopedTransaction<'_, BUS, CS> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut BUS {
+        self.bus
+    }
+}
+
+impl<BUS, CS: OutputPin> Drop for ScopedTransaction<'_, BUS, CS> {
+    #[inline]
+    fn drop(&mut self) {
+        if !self.finished {
+            // There is no way to report this error from `drop`.
+            let _ = self.cs.set_high();
+        }
+    }
+}
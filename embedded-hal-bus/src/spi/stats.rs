@@ -0,0 +1,59 @@
+#[cfg(all(feature = "stats", feature = "defmt-03"))]
+use crate::defmt;
+
+/// Statistics of the transactions performed by an [`SpiDevice`](embedded_hal::spi::SpiDevice).
+///
+/// Returned by the `stats` methods of the `SpiDevice` implementations of this crate, such as
+/// [`ExclusiveDevice::stats`](super::ExclusiveDevice::stats). Counters wrap around on overflow.
+#[cfg(feature = "stats")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Stats {
+    /// Number of transactions, including failed ones. Empty transactions, which do nothing, aren't
+    /// counted.
+    pub transactions: u64,
+    /// Number of words clocked on the bus by successful transactions, counted like
+    /// [`operations_byte_count`](super::operations_byte_count).
+    pub bytes: u64,
+    /// Number of failed transactions, including the ones rejected before touching the bus.
+    pub errors: u64,
+}
+
+/// Statistics kept by a device, empty without the `stats` feature.
+#[derive(Default)]
+pub(crate) struct DeviceStats {
+    #[cfg(feature = "stats")]
+    stats: Stats,
+}
+
+impl DeviceStats {
+    /// Runs the transaction `f`, of `words` words, and records its result.
+    #[inline]
+    pub(crate) fn record<R, E>(
+        &mut self,
+        words: usize,
+        f: impl FnOnce() -> Result<R, E>,
+    ) -> Result<R, E> {
+        let res = f();
+        #[cfg(feature = "stats")]
+        {
+            let stats = &mut self.stats;
+            stats.transactions = stats.transactions.wrapping_add(1);
+            match res {
+                Ok(_) => stats.bytes = stats.bytes.wrapping_add(words as u64),
+                Err(_) => stats.errors = stats.errors.wrapping_add(1),
+            }
+        }
+        #[cfg(not(feature = "stats"))]
+        let _ = words;
+        res
+    }
+
+    /// Returns the recorded statistics.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub(crate) fn get(&self) -> Stats {
+        self.stats
+    }
+}
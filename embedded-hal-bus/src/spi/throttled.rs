@@ -0,0 +1,181 @@
+//! SPI device wrapper limiting bus utilization.
+
+use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+
+/// [`SpiDevice`] wrapper limiting the fraction of time the bus is active, for EMC-sensitive
+/// designs.
+///
+/// The time spent in each transaction is measured, and counted against a budget of `percent`% of
+/// `window_us`. The budget is replenished continuously, at `percent`% of the elapsed time. A burst
+/// of transactions is started right away until its active time exceeds the budget, then each new
+/// transaction is delayed until the excess has been replenished. In the long run, the bus is
+/// active at most `percent`% of the time, and never more than the budget plus one transaction
+/// ahead of that.
+///
+/// Time is read from `clock`, a closure returning a monotonic timestamp in microseconds that
+/// wraps around at `u32::MAX`, like for [`MinDeselect`](super::MinDeselect). Waiting busy-polls
+/// the clock.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::SpiDevice;
+/// use embedded_hal_bus::spi::Throttled;
+/// # use core::cell::Cell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::spi;
+/// # struct Device<'a> { now: &'a Cell<u32>, starts: Vec<u32> }
+/// # impl spi::ErrorType for Device<'_> { type Error = Infallible; }
+/// # impl spi::SpiDevice for Device<'_> {
+/// #     fn transaction(&mut self, _: &mut [spi::Operation<'_, u8>]) -> Result<(), Infallible> {
+/// #         self.starts.push(self.now.get());
+/// #         self.now.set(self.now.get() + 100);
+/// #         Ok(())
+/// #     }
+/// # }
+/// // A fake clock, advancing by 1 µs every time it is read, and a device taking 100 µs per
+/// // transaction.
+/// let now = Cell::new(0);
+/// let clock = || {
+///     now.set(now.get() + 1);
+///     now.get()
+/// };
+/// # let device = Device { now: &now, starts: Vec::new() };
+///
+/// // Active at most 50% of the time, with bursts of up to 500 µs.
+/// let mut device = Throttled::new(device, clock, 50, 1_000);
+///
+/// for _ in 0..20 {
+///     device.write(&[0x00; 16]).unwrap();
+/// }
+///
+/// // The burst starts back-to-back, then is slowed down to 50% utilization.
+/// let starts = &device.inner().starts;
+/// let gaps: Vec<u32> = starts.windows(2).map(|w| w[1] - w[0]).collect();
+/// assert!(gaps[..10].iter().all(|&gap| gap < 110));
+/// assert!(gaps[10..].iter().all(|&gap| gap >= 200));
+/// assert!(device.active_us() >= 2_000);
+/// ```
+pub struct Throttled<D, C> {
+    device: D,
+    clock: C,
+    percent: u32,
+    budget_us: u32,
+    used_us: u32,
+    last: Option<u32>,
+    active_us: u64,
+}
+
+impl<D, C> Throttled<D, C>
+where
+    C: FnMut() -> u32,
+{
+    /// Create a new `Throttled` device, keeping the bus active at most `percent`% of the time,
+    /// allowing bursts of `percent`% of `window_us` microseconds.
+    ///
+    /// The first transactions are started immediately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percent` is 0 or greater than 100.
+    #[inline]
+    pub fn new(device: D, clock: C, percent: u8, window_us: u32) -> Self {
+        assert!(
+            (1..=100).contains(&percent),
+            "utilization must be between 1 and 100%, got {}%",
+            percent
+        );
+        let percent = u32::from(percent);
+        Self {
+            device,
+            clock,
+            percent,
+            budget_us: (u64::from(window_us) * u64::from(percent) / 100) as u32,
+            used_us: 0,
+            last: None,
+            active_us: 0,
+        }
+    }
+
+    /// Returns the total time spent in transactions, in microseconds.
+    #[inline]
+    pub fn active_us(&self) -> u64 {
+        self.active_us
+    }
+
+    /// Returns a reference to the inner device.
+    #[inline]
+    pub fn inner(&self) -> &D {
+        &self.device
+    }
+
+    /// Returns a mutable reference to the inner device.
+    ///
+    /// Transactions done directly on the inner device are not tracked.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+
+    /// Destroys the wrapper, returning the inner device.
+    #[inline]
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+    /// Replenishes the budget for the time elapsed since the last call, up to `now`.
+    #[inline]
+    fn replenish(&mut self, now: u32) {
+        if let Some(last) = self.last {
+            let replenished = u64::from(now.wrapping_sub(last)) * u64::from(self.percent) / 100;
+            self.used_us = self.used_us.saturating_sub(replenished as u32);
+        }
+        self.last = Some(now);
+    }
+
+    /// Waits until the active time in excess of the budget has been replenished.
+    #[inline]
+    // `u64::div_ceil` is newer than the MSRV.
+    #[allow(clippy::manual_div_ceil)]
+    fn wait_budget(&mut self) {
+        let now = (self.clock)();
+        self.replenish(now);
+        if self.used_us > self.budget_us {
+            let excess = u64::from(self.used_us - self.budget_us);
+            let percent = u64::from(self.percent);
+            let wait_us = ((excess * 100 + percent - 1) / percent).min(u64::from(u32::MAX)) as u32;
+            while (self.clock)().wrapping_sub(now) < wait_us {}
+            let now = (self.clock)();
+            self.replenish(now);
+        }
+    }
+}
+
+impl<D, C> ErrorType for Throttled<D, C>
+where
+    D: ErrorType,
+{
+    type Error = D::Error;
+}
+
+impl<Word: Copy + 'static, D, C> SpiDevice<Word> for Throttled<D, C>
+where
+    D: SpiDevice<Word>,
+    C: FnMut() -> u32,
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error> {
+        // An empty transaction does nothing, so there is nothing to wait for.
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.wait_budget();
+        let start = (self.clock)();
+        let res = self.device.transaction(operations);
+        // The bus was active even if the transaction failed.
+        let active_us = (self.clock)().wrapping_sub(start);
+        self.used_us = self.used_us.saturating_add(active_us);
+        self.active_us += u64::from(active_us);
+        res
+    }
+}
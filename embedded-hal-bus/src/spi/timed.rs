@@ -0,0 +1,227 @@
+//! SPI bus adapter recording the duration of each operation.
+
+use embedded_hal::spi::{ErrorType, SpiBus};
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// Bus operation timed by [`TimedBus`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum TimedOperation {
+    /// [`SpiBus::read`].
+    Read,
+    /// [`SpiBus::write`].
+    Write,
+    /// [`SpiBus::transfer`].
+    Transfer,
+    /// [`SpiBus::transfer_in_place`].
+    TransferInPlace,
+    /// [`SpiBus::flush`].
+    Flush,
+}
+
+/// Duration of a bus operation, recorded by [`TimedBus`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Timing {
+    /// The timed operation.
+    pub operation: TimedOperation,
+    /// Number of words clocked by the operation, 0 for flushes.
+    ///
+    /// For transfers, this is the longest of the two buffers.
+    pub len: usize,
+    /// Time taken by the operation, in microseconds.
+    pub duration_us: u32,
+}
+
+impl Timing {
+    const EMPTY: Self = Self {
+        operation: TimedOperation::Flush,
+        len: 0,
+        duration_us: 0,
+    };
+}
+
+/// [`SpiBus`] adapter recording how long each operation took, for profiling on the target.
+///
+/// The duration of every operation is recorded in a ring buffer holding the last `N` [`Timing`]s,
+/// which can be read back with [`timings`](Self::timings). Older timings are overwritten once the
+/// buffer is full. Operations are timed whether they succeed or not.
+///
+/// Time is read from `clock`, a closure returning a monotonic timestamp in microseconds that
+/// wraps around at `u32::MAX`. It is read before and after each operation, so its own overhead is
+/// included in the durations. Like [`ByteDelayed`](super::ByteDelayed), the adapter wraps the
+/// bus: use it as the bus of any of the [`SpiDevice`](embedded_hal::spi::SpiDevice)
+/// implementations in this crate.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::SpiBus;
+/// use embedded_hal_bus::spi::{TimedBus, TimedOperation, Timing};
+/// # use core::cell::Cell;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::spi;
+/// # struct Bus<'a>(&'a Cell<u32>);
+/// # impl Bus<'_> {
+/// #     fn take(&self, words: usize) -> Result<(), Infallible> { self.0.set(self.0.get().wrapping_add(8 * words as u32)); Ok(()) }
+/// # }
+/// # impl spi::ErrorType for Bus<'_> { type Error = Infallible; }
+/// # impl spi::SpiBus for Bus<'_> {
+/// #     fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> { self.take(words.len()) }
+/// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> { self.take(words.len()) }
+/// #     fn transfer(&mut self, r: &mut [u8], w: &[u8]) -> Result<(), Infallible> { self.take(r.len().max(w.len())) }
+/// #     fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Infallible> { self.take(words.len()) }
+/// #     fn flush(&mut self) -> Result<(), Infallible> { self.take(0) }
+/// # }
+/// // A fake clock, and a fake bus taking 8 µs per word.
+/// let now = Cell::new(u32::MAX - 20);
+/// let clock = || now.get();
+/// # let bus = Bus(&now);
+///
+/// let mut bus = TimedBus::<_, _, 2>::new(bus, clock);
+///
+/// bus.flush().unwrap();
+/// // The clock wraps around during this read.
+/// bus.read(&mut [0; 4]).unwrap();
+/// bus.write(&[1, 2]).unwrap();
+///
+/// // Only the last 2 timings are kept.
+/// let timings: Vec<Timing> = bus.timings().collect();
+/// assert_eq!(
+///     timings,
+///     [
+///         Timing { operation: TimedOperation::Read, len: 4, duration_us: 32 },
+///         Timing { operation: TimedOperation::Write, len: 2, duration_us: 16 },
+///     ]
+/// );
+///
+/// bus.clear_timings();
+/// assert_eq!(bus.timings().count(), 0);
+/// ```
+pub struct TimedBus<BUS, C, const N: usize> {
+    bus: BUS,
+    clock: C,
+    timings: [Timing; N],
+    next: usize,
+    len: usize,
+}
+
+impl<BUS, C, const N: usize> TimedBus<BUS, C, N> {
+    /// Create a new `TimedBus`, keeping the last `N` timings.
+    #[inline]
+    pub fn new(bus: BUS, clock: C) -> Self {
+        Self {
+            bus,
+            clock,
+            timings: [Timing::EMPTY; N],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the recorded timings, oldest first.
+    #[inline]
+    pub fn timings(&self) -> impl Iterator<Item = Timing> + '_ {
+        let start = self.next + N - self.len;
+        (start..start + self.len).map(move |i| self.timings[i % N])
+    }
+
+    /// Discards the recorded timings.
+    #[inline]
+    pub fn clear_timings(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+
+    /// Returns a reference to the underlying bus object.
+    #[inline]
+    pub fn bus(&self) -> &BUS {
+        &self.bus
+    }
+
+    /// Returns a mutable reference to the underlying bus object.
+    #[inline]
+    pub fn bus_mut(&mut self) -> &mut BUS {
+        &mut self.bus
+    }
+
+    /// Destroys the adapter, returning the bus and the clock.
+    #[inline]
+    pub fn into_inner(self) -> (BUS, C) {
+        (self.bus, self.clock)
+    }
+}
+
+impl<BUS, C, const N: usize> TimedBus<BUS, C, N>
+where
+    C: FnMut() -> u32,
+{
+    /// Runs `f` on the bus, recording how long it took.
+    #[inline]
+    fn timed<R>(
+        &mut self,
+        operation: TimedOperation,
+        len: usize,
+        f: impl FnOnce(&mut BUS) -> R,
+    ) -> R {
+        let start = (self.clock)();
+        let res = f(&mut self.bus);
+        let duration_us = (self.clock)().wrapping_sub(start);
+
+        if N > 0 {
+            self.timings[self.next] = Timing {
+                operation,
+                len,
+                duration_us,
+            };
+            self.next = (self.next + 1) % N;
+            self.len = (self.len + 1).min(N);
+        }
+        res
+    }
+}
+
+impl<BUS, C, const N: usize> ErrorType for TimedBus<BUS, C, N>
+where
+    BUS: ErrorType,
+{
+    type Error = BUS::Error;
+}
+
+impl<Word: Copy + 'static, BUS, C, const N: usize> SpiBus<Word> for TimedBus<BUS, C, N>
+where
+    BUS: SpiBus<Word>,
+    C: FnMut() -> u32,
+{
+    #[inline]
+    fn read(&mut self, words: &mut [Word]) -> Result<(), Self::Error> {
+        self.timed(TimedOperation::Read, words.len(), |bus| bus.read(words))
+    }
+
+    #[inline]
+    fn write(&mut self, words: &[Word]) -> Result<(), Self::Error> {
+        self.timed(TimedOperation::Write, words.len(), |bus| bus.write(words))
+    }
+
+    #[inline]
+    fn transfer(&mut self, read: &mut [Word], write: &[Word]) -> Result<(), Self::Error> {
+        let len = read.len().max(write.len());
+        self.timed(TimedOperation::Transfer, len, |bus| {
+            bus.transfer(read, write)
+        })
+    }
+
+    #[inline]
+    fn transfer_in_place(&mut self, words: &mut [Word]) -> Result<(), Self::Error> {
+        self.timed(TimedOperation::TransferInPlace, words.len(), |bus| {
+            bus.transfer_in_place(words)
+        })
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.timed(TimedOperation::Flush, 0, |bus| bus.flush())
+    }
+}
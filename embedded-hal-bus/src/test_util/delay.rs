@@ -0,0 +1,42 @@
+use embedded_hal::delay::DelayUs;
+
+/// Mock [`DelayUs`] implementation, recording the requested delays instead of waiting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MockDelay {
+    calls: u32,
+    total_us: u64,
+}
+
+impl MockDelay {
+    /// Create a new `MockDelay`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of delays requested.
+    #[inline]
+    pub fn calls(&self) -> u32 {
+        self.calls
+    }
+
+    /// Returns the total requested delay, in microseconds.
+    #[inline]
+    pub fn total_us(&self) -> u64 {
+        self.total_us
+    }
+}
+
+impl DelayUs for MockDelay {
+    #[inline]
+    fn delay_us(&mut self, us: u32) {
+        self.calls += 1;
+        self.total_us += u64::from(us);
+    }
+
+    #[inline]
+    fn delay_ms(&mut self, ms: u32) {
+        self.calls += 1;
+        self.total_us += u64::from(ms) * 1000;
+    }
+}
@@ -0,0 +1,90 @@
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, PinState};
+use heapless::Deque;
+
+/// Expected [`MockPin`] transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinTransaction {
+    /// The pin is expected to be set to the given state.
+    Set(PinState),
+    /// The pin is expected to be read, returning the given state.
+    Get(PinState),
+}
+
+/// Mock pin, implementing [`InputPin`] and [`OutputPin`].
+///
+/// Holds up to `N` expected transactions.
+#[derive(Debug)]
+pub struct MockPin<const N: usize> {
+    expected: RefCell<Deque<PinTransaction, N>>,
+}
+
+impl<const N: usize> MockPin<N> {
+    /// Create a new `MockPin` expecting `transactions`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `N` transactions.
+    #[inline]
+    pub fn new(transactions: &[PinTransaction]) -> Self {
+        Self {
+            expected: RefCell::new(super::queue(transactions)),
+        }
+    }
+
+    /// Checks all expected transactions were done.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some transactions are left.
+    #[inline]
+    pub fn done(&self) {
+        super::done(&*self.expected.borrow());
+    }
+
+    fn get(&self) -> PinState {
+        let mut expected = self.expected.borrow_mut();
+        match super::next(&mut expected, "pin read") {
+            PinTransaction::Get(state) => state,
+            expectation => panic!("unexpected pin read, expected {:?}", expectation),
+        }
+    }
+
+    fn set(&mut self, state: PinState) {
+        let got = PinTransaction::Set(state);
+        let expectation = super::next(self.expected.get_mut(), got);
+        assert_eq!(expectation, got, "unexpected pin transaction");
+    }
+}
+
+impl<const N: usize> ErrorType for MockPin<N> {
+    type Error = Infallible;
+}
+
+impl<const N: usize> InputPin for MockPin<N> {
+    #[inline]
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        Ok(self.get() == PinState::High)
+    }
+
+    #[inline]
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        Ok(self.get() == PinState::Low)
+    }
+}
+
+impl<const N: usize> OutputPin for MockPin<N> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set(PinState::Low);
+        Ok(())
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set(PinState::High);
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use embedded_hal::spi::{Error, ErrorKind};
+
+/// SPI bus error with a configurable [`ErrorKind`], for testing the error paths of drivers.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::spi::{Error, ErrorKind};
+/// use embedded_hal_bus::spi::DeviceError;
+/// use embedded_hal_bus::test_util::DummyError;
+///
+/// let error: DeviceError<DummyError, ()> = DeviceError::Spi(DummyError::new(ErrorKind::Overrun));
+/// assert_eq!(error.kind(), ErrorKind::Overrun);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DummyError {
+    kind: ErrorKind,
+}
+
+impl DummyError {
+    /// Create a new `DummyError` of the given kind.
+    #[inline]
+    pub const fn new(kind: ErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl Error for DummyError {
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
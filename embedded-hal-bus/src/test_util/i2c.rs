@@ -0,0 +1,80 @@
+use core::convert::Infallible;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+use heapless::Deque;
+
+/// Expected [`MockI2c`] operation.
+///
+/// Each operation of a transaction is expected separately: a `write_read` is expected as a
+/// `Write` followed by a `Read`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum I2cTransaction<'a> {
+    /// A read from the given address is expected, returning the given bytes.
+    Read(u8, &'a [u8]),
+    /// A write of the given bytes to the given address is expected.
+    Write(u8, &'a [u8]),
+}
+
+/// Mock [`I2c`] bus, with 7-bit addresses.
+///
+/// Holds up to `N` expected operations.
+#[derive(Debug)]
+pub struct MockI2c<'a, const N: usize> {
+    expected: Deque<I2cTransaction<'a>, N>,
+}
+
+impl<'a, const N: usize> MockI2c<'a, N> {
+    /// Create a new `MockI2c` expecting `operations`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `N` operations.
+    #[inline]
+    pub fn new(operations: &[I2cTransaction<'a>]) -> Self {
+        Self {
+            expected: super::queue(operations),
+        }
+    }
+
+    /// Checks all expected operations were done.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some operations are left.
+    #[inline]
+    pub fn done(&self) {
+        super::done(&self.expected);
+    }
+}
+
+impl<const N: usize> ErrorType for MockI2c<'_, N> {
+    type Error = Infallible;
+}
+
+impl<const N: usize> I2c for MockI2c<'_, N> {
+    #[inline]
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        for operation in operations {
+            match operation {
+                Operation::Read(buf) => match super::next(&mut self.expected, "read") {
+                    I2cTransaction::Read(expected, data) if expected == address => {
+                        buf.copy_from_slice(data)
+                    }
+                    expectation => panic!(
+                        "unexpected read from {:#04x}, expected {:?}",
+                        address, expectation
+                    ),
+                },
+                Operation::Write(buf) => {
+                    let expectation = super::next(&mut self.expected, "write");
+                    assert_eq!(expectation, I2cTransaction::Write(address, buf));
+                }
+            }
+        }
+        Ok(())
+    }
+}
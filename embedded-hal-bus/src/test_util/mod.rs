@@ -0,0 +1,82 @@
+//! `no_std` test doubles for testing drivers.
+//!
+//! The mocks in this module are built on `heapless`, so they can be used in `no_std` test
+//! harnesses, for example when running driver tests on the target. Each mock is created with a
+//! list of expected transactions, and panics as soon as it is used in an unexpected way. Call
+//! `done` at the end of the test to check all expectations were met.
+//!
+//! The expectation lists are stored with a fixed capacity `N`, a const generic parameter of each
+//! mock.
+//!
+//! # Examples
+//!
+//! ```
+//! use embedded_hal::digital::PinState;
+//! use embedded_hal::spi::{Operation, SpiDevice};
+//! use embedded_hal_bus::spi::ExclusiveDevice;
+//! use embedded_hal_bus::test_util::{MockPin, MockSpiBus, PinTransaction, SpiTransaction};
+//!
+//! let mut bus = MockSpiBus::<4>::new(&[
+//!     SpiTransaction::Write(&[0x80]),
+//!     SpiTransaction::Read(&[0x42]),
+//!     SpiTransaction::Flush,
+//! ]);
+//! let mut cs = MockPin::<2>::new(&[
+//!     PinTransaction::Set(PinState::Low),
+//!     PinTransaction::Set(PinState::High),
+//! ]);
+//! let mut device = ExclusiveDevice::new_no_delay(&mut bus, &mut cs);
+//!
+//! let mut id = [0];
+//! device
+//!     .transaction(&mut [Operation::Write(&[0x80]), Operation::Read(&mut id)])
+//!     .unwrap();
+//! assert_eq!(id, [0x42]);
+//!
+//! bus.done();
+//! cs.done();
+//! ```
+
+mod delay;
+pub use delay::*;
+mod digital;
+pub use digital::*;
+mod error;
+pub use error::*;
+mod i2c;
+pub use i2c::*;
+mod spi;
+pub use spi::*;
+
+use core::fmt::Debug;
+use heapless::Deque;
+
+/// Creates a queue holding `expectations`.
+fn queue<T: Copy, const N: usize>(expectations: &[T]) -> Deque<T, N> {
+    let mut queue = Deque::new();
+    for expectation in expectations {
+        if queue.push_back(*expectation).is_err() {
+            panic!("too many expectations, the capacity is {}", N);
+        }
+    }
+    queue
+}
+
+/// Pops the next expectation, panicking if there is none left.
+fn next<T: Debug, const N: usize>(queue: &mut Deque<T, N>, got: impl Debug) -> T {
+    match queue.pop_front() {
+        Some(expectation) => expectation,
+        None => panic!("unexpected {:?}, all expectations were already met", got),
+    }
+}
+
+/// Panics if some expectations are left.
+fn done<T: Debug, const N: usize>(queue: &Deque<T, N>) {
+    if let Some(expectation) = queue.front() {
+        panic!(
+            "{} expectations not met, the next one is {:?}",
+            queue.len(),
+            expectation
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use core::convert::Infallible;
+
+use embedded_hal::spi::{ErrorType, SpiBus};
+use heapless::Deque;
+
+/// Expected [`MockSpiBus`] transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpiTransaction<'a> {
+    /// A read is expected, returning the given words.
+    Read(&'a [u8]),
+    /// A write of the given words is expected.
+    Write(&'a [u8]),
+    /// A transfer writing the first words is expected, returning the second ones.
+    Transfer(&'a [u8], &'a [u8]),
+    /// An in-place transfer writing the first words is expected, returning the second ones.
+    TransferInPlace(&'a [u8], &'a [u8]),
+    /// A flush is expected.
+    Flush,
+}
+
+/// Mock 8-bit [`SpiBus`].
+///
+/// Holds up to `N` expected transactions.
+#[derive(Debug)]
+pub struct MockSpiBus<'a, const N: usize> {
+    expected: Deque<SpiTransaction<'a>, N>,
+}
+
+impl<'a, const N: usize> MockSpiBus<'a, N> {
+    /// Create a new `MockSpiBus` expecting `transactions`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than `N` transactions.
+    #[inline]
+    pub fn new(transactions: &[SpiTransaction<'a>]) -> Self {
+        Self {
+            expected: super::queue(transactions),
+        }
+    }
+
+    /// Checks all expected transactions were done.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some transactions are left.
+    #[inline]
+    pub fn done(&self) {
+        super::done(&self.expected);
+    }
+
+    fn next(&mut self, got: &str) -> SpiTransaction<'a> {
+        super::next(&mut self.expected, got)
+    }
+}
+
+impl<const N: usize> ErrorType for MockSpiBus<'_, N> {
+    type Error = Infallible;
+}
+
+impl<const N: usize> SpiBus for MockSpiBus<'_, N> {
+    #[inline]
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        match self.next("read") {
+            SpiTransaction::Read(data) => words.copy_from_slice(data),
+            expectation => panic!("unexpected read, expected {:?}", expectation),
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        let expectation = self.next("write");
+        assert_eq!(expectation, SpiTransaction::Write(words));
+        Ok(())
+    }
+
+    #[inline]
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        match self.next("transfer") {
+            SpiTransaction::Transfer(expected, data) => {
+                assert_eq!(expected, write, "unexpected transfer write data");
+                read.copy_from_slice(data);
+            }
+            expectation => panic!("unexpected transfer, expected {:?}", expectation),
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        match self.next("in-place transfer") {
+            SpiTransaction::TransferInPlace(expected, data) => {
+                assert_eq!(expected, words, "unexpected in-place transfer write data");
+                words.copy_from_slice(data);
+            }
+            expectation => panic!("unexpected in-place transfer, expected {:?}", expectation),
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        let expectation = self.next("flush");
+        assert_eq!(expectation, SpiTransaction::Flush);
+        Ok(())
+    }
+}
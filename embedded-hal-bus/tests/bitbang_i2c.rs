@@ -0,0 +1,173 @@
+use core::cell::RefCell;
+use core::convert::Infallible;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::i2c::{ErrorKind, I2c, NoAcknowledgeSource};
+use embedded_hal_bus::i2c::{BitBangError, BitBangI2c, BitOrder};
+
+/// Decoded bus activity: `S` for starts, `P` for stops and `0`/`1` for each bit clocked, as driven
+/// by the master.
+#[derive(Default)]
+struct Wire {
+    scl: bool,
+    sda: bool,
+    /// SDA level at the last SCL rising edge, if no start or stop happened since.
+    sampled: Option<bool>,
+    /// Levels driven by the device, returned by SDA reads.
+    device: VecDeque<bool>,
+    log: String,
+}
+
+impl Wire {
+    fn new(device: &[u8]) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            scl: true,
+            sda: true,
+            device: device.iter().map(|&bit| bit != 0).collect(),
+            ..Default::default()
+        }))
+    }
+
+    fn set_scl(&mut self, high: bool) {
+        match (self.scl, high) {
+            (false, true) => self.sampled = Some(self.sda),
+            (true, false) => {
+                if let Some(bit) = self.sampled.take() {
+                    self.log.push(if bit { '1' } else { '0' });
+                }
+            }
+            _ => {}
+        }
+        self.scl = high;
+    }
+
+    fn set_sda(&mut self, high: bool) {
+        if self.scl && self.sda != high {
+            self.log.push(if high { 'P' } else { 'S' });
+            self.sampled = None;
+        }
+        self.sda = high;
+    }
+}
+
+struct Scl(Rc<RefCell<Wire>>);
+
+impl ErrorType for Scl {
+    type Error = Infallible;
+}
+
+impl OutputPin for Scl {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().set_scl(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().set_scl(true);
+        Ok(())
+    }
+}
+
+struct Sda(Rc<RefCell<Wire>>);
+
+impl ErrorType for Sda {
+    type Error = Infallible;
+}
+
+impl OutputPin for Sda {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().set_sda(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().set_sda(true);
+        Ok(())
+    }
+}
+
+impl InputPin for Sda {
+    fn is_high(&self) -> Result<bool, Infallible> {
+        Ok(self
+            .0
+            .borrow_mut()
+            .device
+            .pop_front()
+            .expect("unexpected SDA read"))
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+struct NoDelay;
+
+impl DelayUs for NoDelay {
+    fn delay_us(&mut self, _us: u32) {}
+}
+
+fn bitbang(wire: &Rc<RefCell<Wire>>) -> BitBangI2c<Scl, Sda, NoDelay> {
+    BitBangI2c::new(Scl(wire.clone()), Sda(wire.clone()), NoDelay, 5)
+}
+
+#[test]
+fn write_msb_first() {
+    // The device acknowledges the address and data bytes.
+    let wire = Wire::new(&[0, 0]);
+    bitbang(&wire).write(0x48, &[0xA1]).unwrap();
+
+    // Acknowledge bits are released by the master, so read as 1.
+    assert_eq!(wire.borrow().log, "S100100001101000011P");
+    assert!(wire.borrow().device.is_empty());
+}
+
+#[test]
+fn write_lsb_first() {
+    let wire = Wire::new(&[0, 0]);
+    let mut i2c = bitbang(&wire);
+    i2c.set_bit_order(BitOrder::LsbFirst);
+    i2c.write(0x48, &[0xA1]).unwrap();
+
+    assert_eq!(wire.borrow().log, "S000010011100001011P");
+}
+
+#[test]
+fn read_both_orders() {
+    let byte = [1, 1, 0, 0, 0, 0, 0, 0];
+    for (bit_order, expected, address) in [
+        (BitOrder::MsbFirst, 0xC0, "10010001"),
+        (BitOrder::LsbFirst, 0x03, "10001001"),
+    ] {
+        // Address acknowledge, then the data byte.
+        let wire = Wire::new(&[&[0][..], &byte].concat());
+        let mut i2c = bitbang(&wire);
+        i2c.set_bit_order(bit_order);
+
+        let mut buf = [0];
+        i2c.read(0x48, &mut buf).unwrap();
+        assert_eq!(buf, [expected]);
+        // The master releases SDA while the device sends, and doesn't acknowledge the last byte.
+        assert_eq!(wire.borrow().log, format!("S{}1111111111P", address));
+    }
+}
+
+#[test]
+fn address_not_acknowledged() {
+    let wire = Wire::new(&[1]);
+    let err = bitbang(&wire).write(0x48, &[0xA1]).unwrap_err();
+
+    assert_eq!(
+        err,
+        BitBangError::NoAcknowledge(NoAcknowledgeSource::Address)
+    );
+    assert_eq!(
+        embedded_hal::i2c::Error::kind(&err),
+        ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)
+    );
+    // The transaction is aborted with a stop.
+    assert_eq!(wire.borrow().log, "S100100001P");
+}
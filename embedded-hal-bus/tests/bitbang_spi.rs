@@ -0,0 +1,343 @@
+//! Checks `BitBangSpi` in each mode against a device simulated at the pin level, and that
+//! configurations requested through `ConfigurableDevice` take effect.
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{self, InputPin, OutputPin};
+use embedded_hal::spi::{Mode, Phase, Polarity, SpiBus, SpiDevice, MODE_0, MODE_1, MODE_2, MODE_3};
+use embedded_hal_bus::spi::{
+    BitBangMode, BitBangSpi, BitBangSpiError, BitBangSpiMode0, BitBangSpiMode1, BitBangSpiMode2,
+    BitBangSpiMode3, BitOrder, ConfigurableDevice, ExclusiveDevice, Mode0, Mode1, Mode2, Mode3,
+    SpiConfig,
+};
+
+/// Device capturing MOSI and driving MISO according to its own mode.
+struct Wire {
+    mode: Mode,
+    sck: bool,
+    mosi: bool,
+    /// MOSI levels at the capture edges.
+    captured: Vec<bool>,
+    /// Levels returned by MISO reads.
+    miso: VecDeque<bool>,
+    delays: Vec<u32>,
+}
+
+impl Wire {
+    fn new(mode: Mode, sck: bool) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            mode,
+            sck,
+            mosi: false,
+            captured: Vec::new(),
+            miso: VecDeque::new(),
+            delays: Vec::new(),
+        }))
+    }
+
+    fn set_sck(&mut self, high: bool) {
+        if high != self.sck {
+            let idle_high = self.mode.polarity == Polarity::IdleHigh;
+            let first = self.sck == idle_high;
+            let capture = match self.mode.phase {
+                Phase::CaptureOnFirstTransition => first,
+                Phase::CaptureOnSecondTransition => !first,
+            };
+            if capture {
+                self.captured.push(self.mosi);
+            }
+        }
+        self.sck = high;
+    }
+}
+
+struct Sck(Rc<RefCell<Wire>>);
+struct Mosi(Rc<RefCell<Wire>>);
+struct Miso(Rc<RefCell<Wire>>);
+struct Delay(Rc<RefCell<Wire>>);
+
+impl digital::ErrorType for Sck {
+    type Error = Infallible;
+}
+
+impl OutputPin for Sck {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().set_sck(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().set_sck(true);
+        Ok(())
+    }
+}
+
+impl digital::ErrorType for Mosi {
+    type Error = Infallible;
+}
+
+impl OutputPin for Mosi {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().mosi = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().mosi = true;
+        Ok(())
+    }
+}
+
+impl digital::ErrorType for Miso {
+    type Error = Infallible;
+}
+
+impl InputPin for Miso {
+    fn is_high(&self) -> Result<bool, Infallible> {
+        Ok(self.0.borrow_mut().miso.pop_front().unwrap_or(false))
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+impl DelayUs for Delay {
+    fn delay_us(&mut self, us: u32) {
+        self.0.borrow_mut().delays.push(us);
+    }
+}
+
+type Spi<M> = BitBangSpi<Sck, Mosi, Miso, Delay, M>;
+
+fn config(mode: Mode) -> SpiConfig {
+    SpiConfig {
+        mode,
+        frequency_hz: 100_000,
+        bit_order: BitOrder::MsbFirst,
+    }
+}
+
+fn spi<M: BitBangMode>(wire: &Rc<RefCell<Wire>>) -> Spi<M> {
+    BitBangSpi::new(
+        Sck(wire.clone()),
+        Mosi(wire.clone()),
+        Miso(wire.clone()),
+        Delay(wire.clone()),
+        100_000,
+        BitOrder::MsbFirst,
+    )
+}
+
+fn bits(byte: u8) -> Vec<bool> {
+    (0..8).rev().map(|i| byte >> i & 1 != 0).collect()
+}
+
+/// Transfers a word with a device in the mode of `M`, through the bus returned by `new`.
+fn check_mode<M: BitBangMode>(new: fn(Sck, Mosi, Miso, Delay, u32, BitOrder) -> Spi<M>) {
+    let idle = M::MODE.polarity == Polarity::IdleHigh;
+    let wire = Wire::new(M::MODE, idle);
+    let mut spi = new(
+        Sck(wire.clone()),
+        Mosi(wire.clone()),
+        Miso(wire.clone()),
+        Delay(wire.clone()),
+        100_000,
+        BitOrder::MsbFirst,
+    );
+    assert_eq!(*spi.config(), config(M::MODE));
+    wire.borrow_mut().miso.extend(bits(0x3C));
+
+    let mut buf = [0xA5];
+    spi.transfer_in_place(&mut buf).unwrap();
+
+    assert_eq!(buf, [0x3C]);
+    let wire = wire.borrow();
+    assert_eq!(wire.captured, bits(0xA5));
+    assert_eq!(wire.sck, idle);
+}
+
+#[test]
+fn mode_0() {
+    assert_eq!(Mode0::MODE, MODE_0);
+    check_mode(BitBangSpiMode0::new);
+}
+
+#[test]
+fn mode_1() {
+    assert_eq!(Mode1::MODE, MODE_1);
+    check_mode(BitBangSpiMode1::new);
+}
+
+#[test]
+fn mode_2() {
+    assert_eq!(Mode2::MODE, MODE_2);
+    check_mode(BitBangSpiMode2::new);
+}
+
+#[test]
+fn mode_3() {
+    assert_eq!(Mode3::MODE, MODE_3);
+    check_mode(BitBangSpiMode3::new);
+}
+
+#[test]
+fn config_reflects_new_and_set_config() {
+    let wire = Wire::new(MODE_3, true);
+    let mut spi = BitBangSpiMode3::new(
+        Sck(wire.clone()),
+        Mosi(wire.clone()),
+        Miso(wire.clone()),
+        Delay(wire.clone()),
+        250_000,
+        BitOrder::LsbFirst,
+    );
+    let config = spi.config();
+    assert_eq!(config.mode, MODE_3);
+    assert_eq!(config.frequency_hz, 250_000);
+    assert_eq!(config.bit_order, BitOrder::LsbFirst);
+
+    let new_config = SpiConfig {
+        mode: MODE_3,
+        frequency_hz: 2_000_000,
+        bit_order: BitOrder::MsbFirst,
+    };
+    spi.set_config(new_config).unwrap();
+    assert_eq!(*spi.config(), new_config);
+}
+
+#[test]
+fn set_config_rejects_other_modes() {
+    let wire = Wire::new(MODE_0, false);
+    let mut spi = spi::<Mode0>(&wire);
+
+    for mode in [MODE_1, MODE_2, MODE_3] {
+        let res = spi.set_config(SpiConfig {
+            frequency_hz: 1_000_000,
+            ..config(mode)
+        });
+        assert_eq!(res, Err(BitBangSpiError::UnsupportedMode));
+    }
+    assert_eq!(*spi.config(), config(MODE_0));
+
+    // The bus still runs in mode 0, at the original frequency.
+    spi.write(&[0x81]).unwrap();
+    let wire = wire.borrow();
+    assert_eq!(wire.captured, bits(0x81));
+    assert_eq!(wire.delays, [5; 16]);
+    assert!(!wire.sck);
+}
+
+#[test]
+fn set_config_changes_bit_order() {
+    let wire = Wire::new(MODE_0, false);
+    let mut spi = spi::<Mode0>(&wire);
+    spi.set_config(SpiConfig {
+        bit_order: BitOrder::LsbFirst,
+        ..config(MODE_0)
+    })
+    .unwrap();
+
+    wire.borrow_mut().miso.extend(bits(0x80));
+    let mut buf = [0];
+    spi.transfer(&mut buf, &[0x01]).unwrap();
+
+    assert_eq!(wire.borrow().captured, bits(0x80));
+    assert_eq!(buf, [0x01]);
+}
+
+#[test]
+fn set_config_changes_frequency() {
+    for (frequency_hz, half_period_us) in [(100_000, 5), (200_000, 3), (1_000_000, 1), (1, 500_000)]
+    {
+        let wire = Wire::new(MODE_1, false);
+        let mut spi = spi::<Mode1>(&wire);
+        spi.set_config(SpiConfig {
+            frequency_hz,
+            ..config(MODE_1)
+        })
+        .unwrap();
+
+        spi.write(&[0x00]).unwrap();
+        assert_eq!(wire.borrow().delays, [half_period_us; 16]);
+    }
+}
+
+#[test]
+fn invalid_frequency() {
+    let wire = Wire::new(MODE_0, false);
+    let mut spi = spi::<Mode0>(&wire);
+    let res = spi.set_config(SpiConfig {
+        frequency_hz: 0,
+        bit_order: BitOrder::LsbFirst,
+        ..config(MODE_0)
+    });
+
+    assert_eq!(res, Err(BitBangSpiError::InvalidFrequency));
+    assert_eq!(*spi.config(), config(MODE_0));
+    assert!(!wire.borrow().sck);
+}
+
+#[test]
+fn exclusive_device_forwards_config() {
+    struct Cs;
+
+    impl digital::ErrorType for Cs {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for Cs {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    let wire = Wire::new(MODE_2, true);
+    let mut device = ExclusiveDevice::new_no_delay(spi::<Mode2>(&wire), Cs);
+
+    let config = SpiConfig {
+        bit_order: BitOrder::LsbFirst,
+        ..config(MODE_2)
+    };
+    device.set_config(config).unwrap();
+    assert_eq!(*device.bus().config(), config);
+
+    device.write(&[0x1E]).unwrap();
+    assert_eq!(wire.borrow().captured, bits(0x78));
+}
+
+#[test]
+fn swapped_data_pins() {
+    let wire = Wire::new(MODE_0, false);
+    // On the crossed board, the pin meant for MOSI reads the data output of the device, and the
+    // one meant for MISO drives its data input.
+    let spi = BitBangSpiMode0::new(
+        Sck(wire.clone()),
+        Miso(wire.clone()),
+        Mosi(wire.clone()),
+        Delay(wire.clone()),
+        100_000,
+        BitOrder::MsbFirst,
+    );
+    let mut spi = spi.swap_data_pins();
+    wire.borrow_mut().miso.extend(bits(0xC3));
+
+    let mut buf = [0];
+    spi.transfer(&mut buf, &[0x5A]).unwrap();
+
+    // The written word went to the data input of the device, and the read one came from its
+    // data output.
+    assert_eq!(wire.borrow().captured, bits(0x5A));
+    assert_eq!(buf, [0xC3]);
+
+    // Swapping again restores the original pin order.
+    let (_, Miso(_), Mosi(_), _) = spi.swap_data_pins().into_inner();
+}
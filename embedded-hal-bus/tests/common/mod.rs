@@ -0,0 +1,41 @@
+//! Critical section implementation for the tests using `critical_section`.
+//!
+//! The `std` implementation of the `critical-section` crate needs a newer Rust than the MSRV, so
+//! this is a global spin lock instead, reentrant within a thread.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+thread_local!(static IS_LOCKED: Cell<bool> = const { Cell::new(false) });
+
+struct SpinCriticalSection;
+critical_section::set_impl!(SpinCriticalSection);
+
+unsafe impl critical_section::Impl for SpinCriticalSection {
+    unsafe fn acquire() -> bool {
+        IS_LOCKED.with(|locked| {
+            if locked.get() {
+                // Nested in a critical section of the current thread.
+                return true;
+            }
+            while LOCKED
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                thread::yield_now();
+            }
+            locked.set(true);
+            false
+        })
+    }
+
+    unsafe fn release(nested: bool) {
+        if !nested {
+            IS_LOCKED.with(|locked| locked.set(false));
+            LOCKED.store(false, Ordering::Release);
+        }
+    }
+}
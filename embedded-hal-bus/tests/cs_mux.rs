@@ -0,0 +1,96 @@
+//! Checks `CsMuxDevice` selects the mux channel and runs the transaction atomically.
+
+mod common;
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+use std::sync::Arc;
+use std::thread;
+
+use critical_section::Mutex;
+use embedded_hal::i2c::{self, I2c, Operation};
+use embedded_hal_bus::i2c::CsMuxDevice;
+
+const MUX: u8 = 0x70;
+const SENSOR: u8 = 0x48;
+
+/// Bus recording the channel selected on the mux for each write to the sensor.
+#[derive(Default)]
+struct Bus {
+    channel: Option<u8>,
+    writes: Vec<(u8, u8)>,
+}
+
+impl i2c::ErrorType for Bus {
+    type Error = Infallible;
+}
+
+impl I2c for Bus {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Infallible> {
+        for op in operations {
+            match (address, op) {
+                (MUX, Operation::Write(buf)) => self.channel = Some(buf[0]),
+                (SENSOR, Operation::Write(buf)) => {
+                    // Give the other thread a chance to run mid-transaction.
+                    thread::yield_now();
+                    let channel = self.channel.take().expect("no channel selected");
+                    self.writes.push((channel, buf[0]));
+                }
+                (SENSOR, Operation::Read(buf)) => buf.fill(self.channel.take().unwrap()),
+                _ => panic!("unexpected operation on {:#04x}", address),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn select_then_transaction() {
+    let bus = Mutex::new(RefCell::new(Bus::default()));
+    let mut sensor0 = CsMuxDevice::new(&bus, MUX, 1 << 0);
+    let mut sensor1 = CsMuxDevice::new(&bus, MUX, 1 << 1);
+
+    let mut buf = [0; 2];
+    sensor0.read(SENSOR, &mut buf).unwrap();
+    assert_eq!(buf, [0b01; 2]);
+    sensor1.read(SENSOR, &mut buf).unwrap();
+    assert_eq!(buf, [0b10; 2]);
+
+    sensor1.write(SENSOR, &[0x11]).unwrap();
+    sensor0.write(SENSOR, &[0x10]).unwrap();
+
+    critical_section::with(|cs| {
+        assert_eq!(bus.borrow_ref(cs).writes, [(0b10, 0x11), (0b01, 0x10)]);
+    });
+}
+
+#[test]
+fn atomic_across_threads() {
+    let bus = Arc::new(Mutex::new(RefCell::new(Bus::default())));
+
+    let threads: Vec<_> = (0..2)
+        .map(|channel| {
+            let bus = Arc::clone(&bus);
+            thread::spawn(move || {
+                let mut sensor = CsMuxDevice::new(&*bus, MUX, 1 << channel);
+                for _ in 0..100 {
+                    sensor.write(SENSOR, &[1 << channel]).unwrap();
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    // Each write reached the sensor with its own channel selected.
+    critical_section::with(|cs| {
+        let writes = &bus.borrow_ref(cs).writes;
+        assert_eq!(writes.len(), 200);
+        assert!(writes.iter().all(|(channel, data)| channel == data));
+    });
+}
@@ -0,0 +1,137 @@
+//! Checks `AsyncDebounced` ignores bounces, and its sync and async versions agree.
+
+#![cfg(feature = "async")]
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal::digital::{self, InputPin};
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::digital::Wait;
+use embedded_hal_bus::digital::{AsyncDebounced, Debounced, Inverted};
+use futures::executor::block_on;
+
+/// Pin returning scripted levels, and panicking once they run out.
+struct Pin<'a> {
+    levels: RefCell<&'a [bool]>,
+}
+
+impl<'a> Pin<'a> {
+    fn new(levels: &'a [bool]) -> Self {
+        Self {
+            levels: RefCell::new(levels),
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.levels.borrow().len()
+    }
+}
+
+impl digital::ErrorType for Pin<'_> {
+    type Error = Infallible;
+}
+
+impl InputPin for Pin<'_> {
+    fn is_high(&self) -> Result<bool, Infallible> {
+        let mut levels = self.levels.borrow_mut();
+        let (&first, rest) = levels.split_first().expect("unexpected pin read");
+        *levels = rest;
+        Ok(first)
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+/// Delay recording the waits, usable both as a sync and an async delay.
+#[derive(Default)]
+struct Delay {
+    waits: Vec<u32>,
+}
+
+impl DelayUs for Delay {
+    async fn delay_us(&mut self, us: u32) {
+        self.waits.push(us);
+    }
+
+    async fn delay_ms(&mut self, ms: u32) {
+        self.waits.push(ms * 1000);
+    }
+}
+
+impl embedded_hal::delay::DelayUs for Delay {
+    fn delay_us(&mut self, us: u32) {
+        self.waits.push(us);
+    }
+}
+
+const BOUNCING_HIGH: [bool; 6] = [true, false, true, false, true, true];
+
+#[test]
+fn read_waits_for_stable_level() {
+    let mut pin = AsyncDebounced::new(Pin::new(&BOUNCING_HIGH), Delay::default(), 500, 2);
+
+    assert!(block_on(pin.read()).unwrap());
+
+    let (pin, delay) = pin.into_inner();
+    assert_eq!(pin.remaining(), 0);
+    assert_eq!(delay.waits, [500; 5]);
+}
+
+#[test]
+fn sync_and_async_agree() {
+    for samples in 1..=2 {
+        let mut blocking = Debounced::new(Pin::new(&BOUNCING_HIGH), Delay::default(), 500, samples);
+        let mut non_blocking =
+            AsyncDebounced::new(Pin::new(&BOUNCING_HIGH), Delay::default(), 500, samples);
+
+        assert_eq!(blocking.read(), block_on(non_blocking.read()));
+        let (sync_pin, sync_delay) = blocking.into_inner();
+        let (async_pin, async_delay) = non_blocking.into_inner();
+        assert_eq!(sync_pin.remaining(), async_pin.remaining());
+        assert_eq!(sync_delay.waits, async_delay.waits);
+    }
+}
+
+#[test]
+fn rising_edge_ignores_bounces() {
+    // Settles low, bounces once, then settles high.
+    let levels = [false, false, true, false, false, true, true];
+    let mut pin = AsyncDebounced::new(Pin::new(&levels), Delay::default(), 100, 2);
+
+    block_on(pin.wait_for_rising_edge()).unwrap();
+
+    assert_eq!(pin.inner().remaining(), 0);
+}
+
+#[test]
+fn any_edge() {
+    let levels = [true, true, false, true, true, false, false];
+    let mut pin = AsyncDebounced::new(Pin::new(&levels), Delay::default(), 100, 2);
+
+    block_on(pin.wait_for_any_edge()).unwrap();
+
+    assert_eq!(pin.inner().remaining(), 0);
+}
+
+#[test]
+fn inverted_waits_for_opposite_edge() {
+    // A falling edge of the debounced pin, seen as a rising edge through `Inverted`.
+    let levels = [true, true, false, false];
+    let pin = AsyncDebounced::new(Pin::new(&levels), Delay::default(), 100, 2);
+    let mut pin = Inverted::new(pin);
+
+    block_on(pin.wait_for_rising_edge()).unwrap();
+
+    assert_eq!(pin.inner().inner().remaining(), 0);
+}
+
+#[test]
+#[should_panic(expected = "the number of samples must not be 0")]
+fn zero_samples() {
+    AsyncDebounced::new(Pin::new(&[]), Delay::default(), 100, 0);
+}
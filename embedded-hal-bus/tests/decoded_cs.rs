@@ -0,0 +1,38 @@
+//! Checks `DecodedCs` drives the decoder address and enable pins.
+
+#![cfg(feature = "test-util")]
+
+use core::cell::RefCell;
+
+use embedded_hal::digital::PinState::{High, Low};
+use embedded_hal::spi::SpiDevice;
+use embedded_hal_bus::spi::{CsDecoder, DecodedCs, ExclusiveDevice};
+use embedded_hal_bus::test_util::{MockPin, MockSpiBus, PinTransaction::Set, SpiTransaction};
+
+#[test]
+fn channel_5() {
+    let address = [
+        MockPin::<1>::new(&[Set(High)]),
+        MockPin::<1>::new(&[Set(Low)]),
+        MockPin::<1>::new(&[Set(High)]),
+    ];
+    let enable = MockPin::<2>::new(&[Set(High), Set(Low)]);
+    let decoder = RefCell::new(CsDecoder::new(address, enable));
+
+    let bus = MockSpiBus::<2>::new(&[SpiTransaction::Write(&[0x42]), SpiTransaction::Flush]);
+    let mut device = ExclusiveDevice::new_no_delay(bus, DecodedCs::new(&decoder, 5));
+    device.write(&[0x42]).unwrap();
+    device.bus().done();
+
+    let (address, enable) = decoder.into_inner().into_inner();
+    address.iter().for_each(MockPin::done);
+    enable.done();
+}
+
+#[test]
+#[should_panic]
+fn channel_out_of_range() {
+    let address = [MockPin::<1>::new(&[]), MockPin::<1>::new(&[])];
+    let decoder = RefCell::new(CsDecoder::new(address, MockPin::<1>::new(&[])));
+    DecodedCs::new(&decoder, 4);
+}
@@ -0,0 +1,151 @@
+//! Checks `DualMode` can be driven by blocking and async code, and switched between the two.
+
+#![cfg(feature = "async")]
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+use core::cell::Cell;
+use core::convert::Infallible;
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::spi::{self, SpiDevice};
+use embedded_hal_async::spi::{SpiBus as AsyncSpiBus, SpiDevice as AsyncSpiDevice};
+use embedded_hal_bus::spi::DualMode;
+use futures::executor::block_on;
+
+/// Bus recording the written bytes, and whether they were written by async code.
+#[derive(Default)]
+struct Bus {
+    written: Vec<(u8, bool)>,
+}
+
+impl spi::ErrorType for Bus {
+    type Error = Infallible;
+}
+
+impl spi::SpiBus for Bus {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        words.fill(0x42);
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        self.written.extend(words.iter().map(|&w| (w, false)));
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Infallible> {
+        spi::SpiBus::read(self, read)?;
+        spi::SpiBus::write(self, write)
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        spi::SpiBus::write(self, words)
+    }
+
+    fn flush(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl AsyncSpiBus for Bus {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        words.fill(0x24);
+        Ok(())
+    }
+
+    async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        self.written.extend(words.iter().map(|&w| (w, true)));
+        Ok(())
+    }
+
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Infallible> {
+        AsyncSpiBus::read(self, read).await?;
+        AsyncSpiBus::write(self, write).await
+    }
+
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        AsyncSpiBus::write(self, words).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// CS pin counting how many times it was set low and high.
+struct Cs<'a> {
+    low: &'a Cell<u32>,
+    high: &'a Cell<u32>,
+}
+
+impl digital::ErrorType for Cs<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for Cs<'_> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.low.set(self.low.get() + 1);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.high.set(self.high.get() + 1);
+        Ok(())
+    }
+}
+
+#[test]
+fn blocking_then_async() {
+    let (low, high) = (Cell::new(0), Cell::new(0));
+    let mut device = DualMode::new_no_delay(
+        Bus::default(),
+        Cs {
+            low: &low,
+            high: &high,
+        },
+    );
+
+    let mut buf = [0; 2];
+    SpiDevice::write(&mut device, &[0x01]).unwrap();
+    SpiDevice::read(&mut device, &mut buf).unwrap();
+    assert_eq!(buf, [0x42; 2]);
+    assert_eq!((low.get(), high.get()), (2, 2));
+
+    // Switching modes doesn't touch CS.
+    let mut device = device.into_async();
+    assert_eq!((low.get(), high.get()), (2, 2));
+
+    block_on(async {
+        AsyncSpiDevice::write(&mut device, &[0x02]).await.unwrap();
+        AsyncSpiDevice::read(&mut device, &mut buf).await.unwrap();
+    });
+    assert_eq!(buf, [0x24; 2]);
+    assert_eq!((low.get(), high.get()), (4, 4));
+
+    assert_eq!(device.bus().written, [(0x01, false), (0x02, true)]);
+}
+
+#[test]
+fn back_to_blocking() {
+    let (low, high) = (Cell::new(0), Cell::new(0));
+    let device = DualMode::new_no_delay(
+        Bus::default(),
+        Cs {
+            low: &low,
+            high: &high,
+        },
+    );
+
+    let mut device = device.into_async();
+    block_on(AsyncSpiDevice::write(&mut device, &[0x01])).unwrap();
+
+    let mut device = device.into_blocking();
+    SpiDevice::write(&mut device, &[0x02]).unwrap();
+
+    assert_eq!((low.get(), high.get()), (2, 2));
+    assert_eq!(
+        device.into_inner().bus().written,
+        [(0x01, true), (0x02, false)]
+    );
+}
@@ -0,0 +1,90 @@
+//! Checks empty transactions touch neither the bus nor CS.
+
+#![cfg(feature = "test-util")]
+
+mod common;
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal::qspi::{self, Command, QspiDevice};
+use embedded_hal::spi::SpiDevice;
+use embedded_hal_bus::spi::{CriticalSectionDevice, ExclusiveDevice, RefCellDevice};
+use embedded_hal_bus::test_util::{MockDelay, MockPin, MockSpiBus};
+
+// The mocks expect no calls, and panic on any.
+type Bus = MockSpiBus<'static, 1>;
+type Cs = MockPin<1>;
+
+#[test]
+fn exclusive_device() {
+    let mut device = ExclusiveDevice::new(Bus::new(&[]), Cs::new(&[]), MockDelay::new());
+    device.transaction(&mut []).unwrap();
+    device.transaction_with_context::<u8>(&mut []).unwrap();
+    device.bus().done();
+}
+
+#[test]
+fn refcell_device() {
+    let bus = RefCell::new(Bus::new(&[]));
+    let mut device = RefCellDevice::new(&bus, Cs::new(&[]), MockDelay::new());
+    device.transaction(&mut []).unwrap();
+    device.transaction_with_context::<u8>(&mut []).unwrap();
+    device.try_transaction::<u8>(&mut []).unwrap();
+    bus.borrow().done();
+}
+
+#[test]
+fn critical_section_device() {
+    let bus = critical_section::Mutex::new(RefCell::new(Bus::new(&[])));
+    let mut device = CriticalSectionDevice::new(&bus, Cs::new(&[]), MockDelay::new());
+    device.transaction(&mut []).unwrap();
+    device.transaction_with_context::<u8>(&mut []).unwrap();
+    bus.into_inner().into_inner().done();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn mutex_device() {
+    let bus = std::sync::Mutex::new(Bus::new(&[]));
+    let mut device = embedded_hal_bus::spi::MutexDevice::new(&bus, Cs::new(&[]), MockDelay::new());
+    device.transaction(&mut []).unwrap();
+    device.transaction_with_context::<u8>(&mut []).unwrap();
+    bus.into_inner().unwrap().done();
+}
+
+#[test]
+fn exclusive_qspi_device() {
+    struct QspiBus;
+
+    impl qspi::ErrorType for QspiBus {
+        type Error = Infallible;
+    }
+
+    impl qspi::QspiBus for QspiBus {
+        fn command(&mut self, _: &mut Command<'_>) -> Result<(), Infallible> {
+            unreachable!()
+        }
+
+        fn flush(&mut self) -> Result<(), Infallible> {
+            unreachable!()
+        }
+    }
+
+    let mut device = ExclusiveDevice::new_no_delay(QspiBus, Cs::new(&[]));
+    QspiDevice::transaction(&mut device, &mut []).unwrap();
+}
+
+#[test]
+fn non_empty_transaction_still_asserts_cs() {
+    use embedded_hal::digital::PinState::{High, Low};
+    use embedded_hal::spi::Operation;
+    use embedded_hal_bus::test_util::{PinTransaction::Set, SpiTransaction};
+
+    // A transaction of an empty write isn't empty.
+    let bus = MockSpiBus::<2>::new(&[SpiTransaction::Write(&[]), SpiTransaction::Flush]);
+    let cs = MockPin::<2>::new(&[Set(Low), Set(High)]);
+    let mut device = ExclusiveDevice::new_no_delay(bus, cs);
+    device.transaction(&mut [Operation::Write(&[])]).unwrap();
+    device.bus().done();
+}
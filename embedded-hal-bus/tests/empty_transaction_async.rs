@@ -0,0 +1,57 @@
+//! Checks empty async transactions touch neither the bus nor CS.
+
+#![cfg(all(feature = "async", feature = "test-util"))]
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+use core::convert::Infallible;
+
+use embedded_hal_async::spi::{self, SpiBus, SpiDevice};
+use embedded_hal_bus::spi::ExclusiveDevice;
+use embedded_hal_bus::test_util::MockPin;
+use futures::executor::block_on;
+
+/// Bus panicking on any call.
+struct Bus;
+
+impl spi::ErrorType for Bus {
+    type Error = Infallible;
+}
+
+impl SpiBus for Bus {
+    async fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    async fn write(&mut self, _: &[u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    async fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    async fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    async fn flush(&mut self) -> Result<(), Infallible> {
+        unreachable!()
+    }
+}
+
+#[test]
+fn exclusive_device() {
+    // The CS mock expects no calls, and panics on any.
+    let mut device = ExclusiveDevice::new_no_delay(Bus, MockPin::<1>::new(&[]));
+    block_on(async {
+        SpiDevice::<u8>::transaction(&mut device, &mut [])
+            .await
+            .unwrap();
+        // Even an expired timeout doesn't matter, as there is nothing to do.
+        device
+            .transaction_timeout::<u8, _>(&mut [], async {})
+            .await
+            .unwrap();
+    });
+}
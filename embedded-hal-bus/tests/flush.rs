@@ -0,0 +1,118 @@
+//! Checks `flush` flushes the bus once, without toggling CS.
+
+mod common;
+
+use core::cell::{Cell, RefCell};
+use core::convert::Infallible;
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::spi::{self, SpiBus};
+use embedded_hal_bus::spi::{CriticalSectionDevice, ExclusiveDevice, RefCellDevice, SpiDeviceExt};
+
+/// Bus counting flushes, and panicking on any other call.
+#[derive(Default)]
+struct Bus {
+    flushes: u32,
+}
+
+impl spi::ErrorType for Bus {
+    type Error = Infallible;
+}
+
+impl SpiBus for Bus {
+    fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    fn write(&mut self, _: &[u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    fn flush(&mut self) -> Result<(), Infallible> {
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+/// CS pin counting its edges.
+struct Cs<'a>(&'a Cell<u32>);
+
+impl digital::ErrorType for Cs<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for Cs<'_> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.set(self.0.get() + 1);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.set(self.0.get() + 1);
+        Ok(())
+    }
+}
+
+#[test]
+fn exclusive_device() {
+    let edges = Cell::new(0);
+    let mut device = ExclusiveDevice::new_no_delay(Bus::default(), Cs(&edges));
+
+    device.flush::<u8>().unwrap();
+    assert_eq!(device.bus().flushes, 1);
+    assert_eq!(edges.get(), 0);
+}
+
+#[test]
+fn refcell_device() {
+    let edges = Cell::new(0);
+    let bus = RefCell::new(Bus::default());
+    let mut device = RefCellDevice::new_no_delay(&bus, Cs(&edges));
+
+    device.flush::<u8>().unwrap();
+    assert_eq!(bus.borrow().flushes, 1);
+    assert_eq!(edges.get(), 0);
+}
+
+#[test]
+fn critical_section_device() {
+    let edges = Cell::new(0);
+    let bus = critical_section::Mutex::new(RefCell::new(Bus::default()));
+    let mut device = CriticalSectionDevice::new_no_delay(&bus, Cs(&edges));
+
+    device.flush::<u8>().unwrap();
+    assert_eq!(bus.into_inner().into_inner().flushes, 1);
+    assert_eq!(edges.get(), 0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn mutex_device() {
+    let edges = Cell::new(0);
+    let bus = std::sync::Mutex::new(Bus::default());
+    let mut device = embedded_hal_bus::spi::MutexDevice::new_no_delay(&bus, Cs(&edges));
+
+    device.flush::<u8>().unwrap();
+    assert_eq!(bus.into_inner().unwrap().flushes, 1);
+    assert_eq!(edges.get(), 0);
+}
+
+#[cfg(feature = "parking-lot")]
+#[test]
+fn parking_lot_device() {
+    let edges = Cell::new(0);
+    let bus = parking_lot::Mutex::new(Bus::default());
+    let mut device = embedded_hal_bus::spi::ParkingLotDevice::new_no_delay(&bus, Cs(&edges));
+
+    device.flush::<u8>().unwrap();
+    assert_eq!(bus.into_inner().flushes, 1);
+    assert_eq!(edges.get(), 0);
+}
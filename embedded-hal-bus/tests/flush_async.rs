@@ -0,0 +1,75 @@
+//! Checks `wait_idle` flushes the async bus once, without toggling CS.
+
+#![cfg(feature = "async")]
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+use core::cell::Cell;
+use core::convert::Infallible;
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal_async::spi::{self, SpiBus};
+use embedded_hal_bus::spi::ExclusiveDevice;
+use futures::executor::block_on;
+
+/// Bus counting flushes, and panicking on any other call.
+#[derive(Default)]
+struct Bus {
+    flushes: u32,
+}
+
+impl spi::ErrorType for Bus {
+    type Error = Infallible;
+}
+
+impl SpiBus for Bus {
+    async fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    async fn write(&mut self, _: &[u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    async fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    async fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> {
+        unreachable!()
+    }
+
+    async fn flush(&mut self) -> Result<(), Infallible> {
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+/// CS pin counting its edges.
+struct Cs<'a>(&'a Cell<u32>);
+
+impl digital::ErrorType for Cs<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for Cs<'_> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.set(self.0.get() + 1);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.set(self.0.get() + 1);
+        Ok(())
+    }
+}
+
+#[test]
+fn exclusive_device_wait_idle() {
+    let edges = Cell::new(0);
+    let mut device = ExclusiveDevice::new_no_delay(Bus::default(), Cs(&edges));
+
+    block_on(device.wait_idle::<u8>()).unwrap();
+    assert_eq!(device.bus().flushes, 1);
+    assert_eq!(edges.get(), 0);
+}
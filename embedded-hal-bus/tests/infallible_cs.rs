@@ -0,0 +1,79 @@
+//! Checks errors of devices whose CS pin can't fail can be propagated as bus errors.
+
+use core::convert::Infallible;
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::spi::{ErrorKind, ErrorType, SpiBus, SpiDevice};
+use embedded_hal_bus::spi::{DeviceError, ExclusiveDevice};
+
+/// Bus failing every read with an overrun.
+struct Bus;
+
+impl ErrorType for Bus {
+    type Error = ErrorKind;
+}
+
+impl SpiBus for Bus {
+    fn read(&mut self, _words: &mut [u8]) -> Result<(), Self::Error> {
+        Err(ErrorKind::Overrun)
+    }
+
+    fn write(&mut self, _words: &[u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn transfer(&mut self, _read: &mut [u8], _write: &[u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn transfer_in_place(&mut self, _words: &mut [u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+struct Cs;
+
+impl digital::ErrorType for Cs {
+    type Error = Infallible;
+}
+
+impl OutputPin for Cs {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Driver using the bus error type, whatever the device.
+fn read_status<D>(device: &mut D) -> Result<u8, ErrorKind>
+where
+    D: SpiDevice<Error = DeviceError<ErrorKind, Infallible>>,
+{
+    device.write(&[0x05]).map_err(DeviceError::into_bus_error)?;
+    let mut status = [0];
+    device
+        .read(&mut status)
+        .map_err(DeviceError::into_bus_error)?;
+    Ok(status[0])
+}
+
+#[test]
+fn bus_errors_are_propagated_with_question_mark() {
+    let mut device = ExclusiveDevice::new_no_delay(Bus, Cs);
+
+    assert_eq!(read_status(&mut device), Err(ErrorKind::Overrun));
+}
+
+#[test]
+fn device_errors_are_propagated_as_other() {
+    let mut device = ExclusiveDevice::new_no_delay(Bus, Cs).with_max_transaction_bytes(0);
+
+    assert_eq!(read_status(&mut device), Err(ErrorKind::Other));
+}
@@ -0,0 +1,102 @@
+//! Checks `InterTransactionDelay` waits before every transaction but the first.
+
+use core::cell::RefCell;
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::spi::{self, ErrorKind, Operation, SpiDevice};
+use embedded_hal_bus::spi::InterTransactionDelay;
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Delay(u32),
+    Transaction(u8),
+}
+
+struct Device<'a> {
+    log: &'a RefCell<Vec<Event>>,
+    fail: bool,
+}
+
+impl spi::ErrorType for Device<'_> {
+    type Error = ErrorKind;
+}
+
+impl SpiDevice for Device<'_> {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), ErrorKind> {
+        let id = match operations {
+            [Operation::Write(&[id])] => id,
+            _ => panic!("unexpected operations"),
+        };
+        self.log.borrow_mut().push(Event::Transaction(id));
+        if self.fail {
+            Err(ErrorKind::Other)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct Delay<'a>(&'a RefCell<Vec<Event>>);
+
+impl DelayUs for Delay<'_> {
+    fn delay_us(&mut self, us: u32) {
+        self.0.borrow_mut().push(Event::Delay(us));
+    }
+}
+
+fn device(log: &RefCell<Vec<Event>>) -> InterTransactionDelay<Device<'_>, Delay<'_>> {
+    InterTransactionDelay::new(Device { log, fail: false }, Delay(log), 25)
+}
+
+#[test]
+fn delays_all_but_first_transaction() {
+    let log = RefCell::new(Vec::new());
+    let mut device = device(&log);
+
+    device.write(&[1]).unwrap();
+    device.write(&[2]).unwrap();
+    device.write(&[3]).unwrap();
+
+    assert_eq!(
+        log.into_inner(),
+        [
+            Event::Transaction(1),
+            Event::Delay(25),
+            Event::Transaction(2),
+            Event::Delay(25),
+            Event::Transaction(3),
+        ]
+    );
+}
+
+#[test]
+fn failed_transactions_count() {
+    let log = RefCell::new(Vec::new());
+    let mut device = device(&log);
+
+    device.inner_mut().fail = true;
+    device.write(&[1]).unwrap_err();
+    device.inner_mut().fail = false;
+    device.write(&[2]).unwrap();
+
+    assert_eq!(
+        log.into_inner(),
+        [
+            Event::Transaction(1),
+            Event::Delay(25),
+            Event::Transaction(2)
+        ]
+    );
+}
+
+#[test]
+fn empty_transactions_are_ignored() {
+    let log = RefCell::new(Vec::new());
+    let mut device = device(&log);
+
+    device.transaction(&mut []).unwrap();
+    device.write(&[1]).unwrap();
+    device.transaction(&mut []).unwrap();
+
+    assert_eq!(log.into_inner(), [Event::Transaction(1)]);
+}
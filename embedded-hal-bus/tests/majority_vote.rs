@@ -0,0 +1,36 @@
+//! Checks `MajorityVote` returns the level read most often.
+
+#![cfg(feature = "test-util")]
+
+use embedded_hal::digital::InputPin;
+use embedded_hal::digital::PinState::{High, Low};
+use embedded_hal_bus::digital::MajorityVote;
+use embedded_hal_bus::test_util::{MockPin, PinTransaction::Get};
+
+#[test]
+fn noisy_pattern() {
+    let pin = MockPin::<10>::new(&[
+        Get(High),
+        Get(Low),
+        Get(High),
+        Get(High),
+        Get(Low),
+        // Mostly low.
+        Get(Low),
+        Get(High),
+        Get(Low),
+        Get(Low),
+        Get(High),
+    ]);
+    let pin = MajorityVote::<_, 5>::new(pin);
+
+    assert!(pin.is_high().unwrap());
+    assert!(pin.is_low().unwrap());
+    pin.inner().done();
+}
+
+#[test]
+#[should_panic(expected = "the number of samples must be odd, got 4")]
+fn even_samples() {
+    MajorityVote::<_, 4>::new(MockPin::<1>::new(&[]));
+}
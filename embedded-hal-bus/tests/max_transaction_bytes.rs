@@ -0,0 +1,194 @@
+//! Checks the transaction helpers of the devices honor `with_max_transaction_bytes`.
+
+#![cfg(feature = "test-util")]
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal::digital::PinState::{High, Low};
+use embedded_hal::spi::{ErrorType, SpiBus};
+use embedded_hal_bus::spi::{
+    DeviceError, ExclusiveDevice, MaxTransferSize, MixedOperation, RefCellDevice, SpiDeviceExt,
+};
+use embedded_hal_bus::test_util::{MockPin, MockSpiBus, PinTransaction::Set, SpiTransaction};
+
+/// [`MockSpiBus`] reporting a maximum transfer size.
+struct LimitedBus<'a, const N: usize>(MockSpiBus<'a, N>, usize);
+
+impl<const N: usize> ErrorType for LimitedBus<'_, N> {
+    type Error = Infallible;
+}
+
+impl<const N: usize> SpiBus for LimitedBus<'_, N> {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.read(words)
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.0.write(words)
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        self.0.transfer(read, write)
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.transfer_in_place(words)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.0.flush()
+    }
+}
+
+impl<const N: usize> MaxTransferSize for LimitedBus<'_, N> {
+    fn max_transfer_words(&self) -> Option<usize> {
+        Some(self.1)
+    }
+}
+
+fn xor(data: &[u8]) -> u8 {
+    data.iter().fold(0, |crc, byte| crc ^ byte)
+}
+
+#[test]
+fn helpers_reject_long_transactions_before_bus_access() {
+    // The mocks expect no calls, and panic on any.
+    let bus = MockSpiBus::<1>::new(&[]);
+    let mut device =
+        ExclusiveDevice::new_no_delay(bus, MockPin::<1>::new(&[])).with_max_transaction_bytes(4);
+
+    assert_eq!(
+        device.write_chunks::<2, u8>(&[0; 5]),
+        Err(DeviceError::TooLong)
+    );
+    let operations = [
+        MixedOperation::Bytes(&[0x2C]),
+        MixedOperation::Words16(&[0; 2]),
+    ];
+    assert_eq!(
+        device.mixed_transaction(&operations),
+        Err(DeviceError::TooLong)
+    );
+    assert_eq!(
+        device.read_register_with_dummy(&[0x8F], 1, &mut [0; 3]),
+        Err(DeviceError::TooLong)
+    );
+    assert_eq!(
+        device.probe_register(&[0; 4], 0x6A),
+        Err(DeviceError::TooLong)
+    );
+    assert_eq!(
+        device.write_with_crc(&[0; 4], xor),
+        Err(DeviceError::TooLong)
+    );
+    assert_eq!(device.write_iter::<8, _>(0..5), Err(DeviceError::TooLong));
+    assert_eq!(
+        device.transfer_tail(&[0x9F], 2, &mut [0; 3]),
+        Err(DeviceError::TooLong)
+    );
+    device.bus().done();
+}
+
+#[test]
+fn helpers_accept_transactions_at_the_limit() {
+    let bus = RefCell::new(MockSpiBus::<3>::new(&[
+        SpiTransaction::Write(&[1, 2]),
+        SpiTransaction::Write(&[3, 4]),
+        SpiTransaction::Flush,
+    ]));
+    let cs = MockPin::<2>::new(&[Set(Low), Set(High)]);
+    let mut device = RefCellDevice::new_no_delay(&bus, cs).with_max_transaction_bytes(4);
+
+    device.write_chunks::<2, u8>(&[1, 2, 3, 4]).unwrap();
+    bus.borrow().done();
+}
+
+#[test]
+fn write_iter_rejects_iterators_that_may_be_too_long() {
+    // The mocks expect no calls, and panic on any.
+    let bus = MockSpiBus::<1>::new(&[]);
+    let mut device =
+        ExclusiveDevice::new_no_delay(bus, MockPin::<1>::new(&[])).with_max_transaction_bytes(4);
+
+    // Without an upper bound on their length.
+    let words = core::iter::repeat(0);
+    assert_eq!(device.write_iter::<2, _>(words), Err(DeviceError::TooLong));
+    // With an upper bound over the limit, even if they are short.
+    let words = (0..8).filter(|&i| i < 2);
+    assert_eq!(device.write_iter::<2, _>(words), Err(DeviceError::TooLong));
+    device.bus().done();
+}
+
+/// Iterator over `0..`, with a size hint claiming it yields at most 4 words.
+struct Unbounded(u8);
+
+impl Iterator for Unbounded {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        self.0 += 1;
+        Some(self.0 - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(4))
+    }
+}
+
+#[test]
+fn write_iter_with_a_wrong_size_hint_stops_before_the_limit() {
+    // The chunk going over the limit is not written.
+    let bus = MockSpiBus::<3>::new(&[
+        SpiTransaction::Write(&[0, 1]),
+        SpiTransaction::Write(&[2, 3]),
+        SpiTransaction::Flush,
+    ]);
+    let cs = MockPin::<2>::new(&[Set(Low), Set(High)]);
+    let mut device = ExclusiveDevice::new_no_delay(bus, cs).with_max_transaction_bytes(4);
+
+    assert_eq!(
+        device.write_iter::<2, _>(Unbounded(0)),
+        Err(DeviceError::TooLong)
+    );
+    device.bus().done();
+}
+
+#[test]
+fn write_bus_chunks_checks_the_total_length() {
+    let bus = LimitedBus(
+        MockSpiBus::<3>::new(&[
+            SpiTransaction::Write(&[1, 2]),
+            SpiTransaction::Write(&[3, 4]),
+            SpiTransaction::Flush,
+        ]),
+        2,
+    );
+    let cs = MockPin::<2>::new(&[Set(Low), Set(High)]);
+    let mut device = ExclusiveDevice::new_no_delay(bus, cs).with_max_transaction_bytes(4);
+
+    // Each chunk fits in the limit, but not the whole transaction.
+    assert_eq!(
+        device.write_bus_chunks::<1, u8>(&[0; 5]),
+        Err(DeviceError::TooLong)
+    );
+    device.write_bus_chunks::<1, u8>(&[1, 2, 3, 4]).unwrap();
+    device.bus().0.done();
+}
+
+#[test]
+fn write_bus_chunks_treats_a_zero_limit_as_none() {
+    let bus = LimitedBus(
+        MockSpiBus::<3>::new(&[
+            SpiTransaction::Write(&[1, 2, 3]),
+            SpiTransaction::Write(&[4, 5]),
+            SpiTransaction::Flush,
+        ]),
+        0,
+    );
+    let cs = MockPin::<2>::new(&[Set(Low), Set(High)]);
+    let mut device = ExclusiveDevice::new_no_delay(bus, cs);
+
+    device.write_bus_chunks::<3, u8>(&[1, 2, 3, 4, 5]).unwrap();
+    device.bus().0.done();
+}
@@ -0,0 +1,306 @@
+//! Checks `OneWire` against devices simulated at the time slot level.
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal_bus::digital::OneWirePin;
+use embedded_hal_bus::one_wire::{crc8, OneWire, OneWireError};
+
+/// ROM command state of a simulated device.
+#[derive(Clone, Copy)]
+enum State {
+    /// Receiving the ROM command, with the bits received so far.
+    Command { bits: u32, value: u8 },
+    /// Taking part in a search, at the given bit: sending it, then its complement, then
+    /// receiving the master's choice.
+    Search { bit: u32, phase: u8 },
+    /// Sending its ROM.
+    ReadRom { bit: u32 },
+    /// Receiving a ROM, still matching its own.
+    MatchRom { bit: u32 },
+    /// Addressed, waiting for a function command.
+    Selected,
+    /// Not addressed until the next reset.
+    Inactive,
+}
+
+struct Device {
+    rom: u64,
+    state: State,
+}
+
+impl Device {
+    fn new(rom: u64) -> Self {
+        Self {
+            rom,
+            state: State::Inactive,
+        }
+    }
+
+    fn rom_bit(&self, bit: u32) -> bool {
+        self.rom >> bit & 1 != 0
+    }
+
+    fn reset(&mut self) {
+        self.state = State::Command { bits: 0, value: 0 };
+    }
+
+    /// Returns the level the device leaves the line at during a read slot.
+    fn read_slot(&mut self) -> bool {
+        match self.state {
+            State::Search { bit, phase: 0 } => {
+                self.state = State::Search { bit, phase: 1 };
+                self.rom_bit(bit)
+            }
+            State::Search { bit, phase: 1 } => {
+                self.state = State::Search { bit, phase: 2 };
+                !self.rom_bit(bit)
+            }
+            State::ReadRom { bit } => {
+                self.state = if bit == 63 {
+                    State::Selected
+                } else {
+                    State::ReadRom { bit: bit + 1 }
+                };
+                self.rom_bit(bit)
+            }
+            _ => true,
+        }
+    }
+
+    fn write_slot(&mut self, value: bool) {
+        self.state = match self.state {
+            State::Command { bits, value: v } => {
+                let v = v | (value as u8) << bits;
+                match (bits, v) {
+                    (7, 0x33) => State::ReadRom { bit: 0 },
+                    (7, 0x55) => State::MatchRom { bit: 0 },
+                    (7, 0xCC) => State::Selected,
+                    (7, 0xF0) => State::Search { bit: 0, phase: 0 },
+                    (7, _) => State::Inactive,
+                    _ => State::Command {
+                        bits: bits + 1,
+                        value: v,
+                    },
+                }
+            }
+            State::Search { bit, phase: 2 } | State::MatchRom { bit }
+                if value != self.rom_bit(bit) =>
+            {
+                State::Inactive
+            }
+            State::Search { bit: 63, phase: 2 } | State::MatchRom { bit: 63 } => State::Selected,
+            State::Search { bit, phase: 2 } => State::Search {
+                bit: bit + 1,
+                phase: 0,
+            },
+            State::MatchRom { bit } => State::MatchRom { bit: bit + 1 },
+            state => state,
+        }
+    }
+}
+
+/// Where the master is within a time slot.
+#[derive(Clone, Copy, PartialEq)]
+enum Slot {
+    Idle,
+    /// The line is driven low.
+    Low,
+    /// The line was driven low for the given time.
+    LowFor(u32),
+    /// The line was released after being driven low for the given time.
+    Released(u32),
+    /// The line will be sampled at the given level.
+    Sampling(bool),
+}
+
+/// Wired-AND line shared by the master and the simulated devices.
+///
+/// Slots are told apart by their timing: the low time, then the wait after releasing the line.
+struct Line {
+    devices: Vec<Device>,
+    slot: Slot,
+    /// Function command bytes received by selected devices.
+    received: Vec<(u64, bool)>,
+}
+
+impl Line {
+    fn new(roms: &[u64]) -> RefCell<Self> {
+        RefCell::new(Self {
+            devices: roms.iter().map(|&rom| Device::new(rom)).collect(),
+            slot: Slot::Idle,
+            received: Vec::new(),
+        })
+    }
+
+    fn write_slot(&mut self, value: bool) {
+        for device in &mut self.devices {
+            if let State::Selected = device.state {
+                self.received.push((device.rom, value));
+            }
+            device.write_slot(value);
+        }
+        self.slot = Slot::Idle;
+    }
+
+    fn wait(&mut self, us: u32) {
+        self.slot = match self.slot {
+            Slot::Low => Slot::LowFor(us),
+            Slot::Released(480) => {
+                assert_eq!(us, 70);
+                self.devices.iter_mut().for_each(Device::reset);
+                Slot::Sampling(self.devices.is_empty())
+            }
+            Slot::Released(6) if us == 9 => {
+                let level = self
+                    .devices
+                    .iter_mut()
+                    .fold(true, |level, device| device.read_slot() & level);
+                Slot::Sampling(level)
+            }
+            Slot::Released(6) => {
+                assert_eq!(us, 64);
+                self.write_slot(true);
+                Slot::Idle
+            }
+            Slot::Released(60) => {
+                assert_eq!(us, 10);
+                self.write_slot(false);
+                Slot::Idle
+            }
+            Slot::Idle => Slot::Idle,
+            _ => panic!("unexpected wait of {} us", us),
+        }
+    }
+}
+
+struct Pin<'a>(&'a RefCell<Line>);
+
+impl ErrorType for Pin<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for Pin<'_> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().slot = Slot::Low;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        let mut line = self.0.borrow_mut();
+        if let Slot::LowFor(us) = line.slot {
+            line.slot = Slot::Released(us);
+        }
+        Ok(())
+    }
+}
+
+impl InputPin for Pin<'_> {
+    fn is_high(&self) -> Result<bool, Infallible> {
+        let mut line = self.0.borrow_mut();
+        match line.slot {
+            Slot::Sampling(level) => {
+                line.slot = Slot::Idle;
+                Ok(level)
+            }
+            _ => panic!("line sampled outside of a read slot"),
+        }
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+struct Delay<'a>(&'a RefCell<Line>);
+
+impl DelayUs for Delay<'_> {
+    fn delay_us(&mut self, us: u32) {
+        self.0.borrow_mut().wait(us);
+    }
+}
+
+/// Returns a ROM with the given family code and serial number, and a valid CRC.
+fn rom(family: u8, serial: u64) -> u64 {
+    let rom = u64::from(family) | serial << 8;
+    let crc = crc8(&rom.to_le_bytes()[..7]);
+    rom | u64::from(crc) << 56
+}
+
+fn bus(line: &RefCell<Line>) -> OneWire<Pin<'_>, Delay<'_>> {
+    OneWire::new(OneWirePin::new(Pin(line)), Delay(line))
+}
+
+#[test]
+fn search_two_devices() {
+    let roms = [
+        rom(0x28, 0x0000_0416_711A_4CFF),
+        rom(0x28, 0x0000_0416_711A_4DFF),
+    ];
+    let line = Line::new(&roms);
+    let found: Result<Vec<_>, _> = bus(&line).search().collect();
+    assert_eq!(found, Ok(roms.to_vec()));
+}
+
+#[test]
+fn search_devices_of_several_families() {
+    let mut roms = [
+        rom(0x28, 0x1234),
+        rom(0x10, 0x1234),
+        rom(0x28, 0x8000_0000_1234),
+        rom(0x3B, 0x0001),
+        rom(0x28, 0x1235),
+    ];
+    let line = Line::new(&roms);
+    let mut found: Vec<_> = bus(&line).search().map(Result::unwrap).collect();
+    found.sort_unstable();
+    roms.sort_unstable();
+    assert_eq!(found, roms);
+}
+
+#[test]
+fn search_empty_bus() {
+    let line = Line::new(&[]);
+    assert_eq!(bus(&line).search().count(), 0);
+    assert_eq!(bus(&line).skip_rom(), Err(OneWireError::NoPresence));
+}
+
+#[test]
+fn read_rom() {
+    let roms = [rom(0x28, 0x0000_0416_711A_4CFF)];
+    let line = Line::new(&roms);
+    assert_eq!(bus(&line).read_rom(), Ok(roms[0]));
+
+    // ROMs of several devices collide.
+    let line = Line::new(&[rom(0x28, 0x1234), rom(0x28, 0x4321)]);
+    assert_eq!(bus(&line).read_rom(), Err(OneWireError::Crc));
+}
+
+#[test]
+fn match_and_skip_rom() {
+    let roms = [rom(0x28, 0x1234), rom(0x28, 0x4321)];
+    let line = Line::new(&roms);
+    let mut bus = bus(&line);
+
+    bus.match_rom(roms[1]).unwrap();
+    bus.write_byte(0x44).unwrap();
+    let received = core::mem::take(&mut line.borrow_mut().received);
+    assert_eq!(received.len(), 8);
+    assert!(received.iter().all(|&(rom, _)| rom == roms[1]));
+
+    bus.skip_rom().unwrap();
+    bus.write_byte(0x44).unwrap();
+    let received = core::mem::take(&mut line.borrow_mut().received);
+    let bits = |rom| -> u8 {
+        received
+            .iter()
+            .filter(|&&(r, _)| r == rom)
+            .enumerate()
+            .map(|(i, &(_, bit))| (bit as u8) << i)
+            .sum()
+    };
+    assert_eq!(bits(roms[0]), 0x44);
+    assert_eq!(bits(roms[1]), 0x44);
+}
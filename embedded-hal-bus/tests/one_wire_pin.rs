@@ -0,0 +1,137 @@
+//! Checks the time slots of `OneWirePin` on a simulated line.
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+use std::collections::VecDeque;
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal_bus::digital::OneWirePin;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+    Low,
+    Release,
+    /// The line was sampled, returning the given level.
+    Sample(bool),
+    Wait(u32),
+}
+
+/// Log of the events on the line, with the levels devices drive when it is sampled.
+struct Line {
+    events: RefCell<Vec<Event>>,
+    samples: RefCell<VecDeque<bool>>,
+}
+
+impl Line {
+    fn new(samples: &[bool]) -> Self {
+        Self {
+            events: RefCell::new(Vec::new()),
+            samples: RefCell::new(samples.iter().copied().collect()),
+        }
+    }
+}
+
+struct Pin<'a>(&'a Line);
+
+impl ErrorType for Pin<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for Pin<'_> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.events.borrow_mut().push(Event::Low);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.events.borrow_mut().push(Event::Release);
+        Ok(())
+    }
+}
+
+impl InputPin for Pin<'_> {
+    fn is_high(&self) -> Result<bool, Infallible> {
+        let level = self
+            .0
+            .samples
+            .borrow_mut()
+            .pop_front()
+            .expect("unexpected sample");
+        self.0.events.borrow_mut().push(Event::Sample(level));
+        Ok(level)
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+struct Delay<'a>(&'a Line);
+
+impl DelayUs for Delay<'_> {
+    fn delay_us(&mut self, us: u32) {
+        self.0.events.borrow_mut().push(Event::Wait(us));
+    }
+}
+
+use Event::*;
+
+#[test]
+fn reset_and_presence() {
+    let line = Line::new(&[false, true]);
+    let mut pin = OneWirePin::new(Pin(&line));
+    let mut delay = Delay(&line);
+
+    assert!(pin.reset_pulse(&mut delay).unwrap());
+    assert!(!pin.reset_pulse(&mut delay).unwrap());
+
+    let pulse = |present: bool| {
+        [
+            Low,
+            Wait(480),
+            Release,
+            Wait(70),
+            Sample(!present),
+            Wait(410),
+        ]
+    };
+    assert_eq!(*line.events.borrow(), [pulse(true), pulse(false)].concat());
+}
+
+#[test]
+fn write_bits() {
+    let line = Line::new(&[]);
+    let mut pin = OneWirePin::new(Pin(&line));
+    let mut delay = Delay(&line);
+
+    pin.write_bit(&mut delay, true).unwrap();
+    pin.write_bit(&mut delay, false).unwrap();
+
+    assert_eq!(
+        *line.events.borrow(),
+        [
+            Low,
+            Wait(6),
+            Release,
+            Wait(64),
+            Low,
+            Wait(60),
+            Release,
+            Wait(10),
+        ]
+    );
+}
+
+#[test]
+fn read_bits() {
+    let line = Line::new(&[true, false]);
+    let mut pin = OneWirePin::new(Pin(&line));
+    let mut delay = Delay(&line);
+
+    assert!(pin.read_bit(&mut delay).unwrap());
+    assert!(!pin.read_bit(&mut delay).unwrap());
+
+    let slot = |bit| [Low, Wait(6), Release, Wait(9), Sample(bit), Wait(55)];
+    assert_eq!(*line.events.borrow(), [slot(true), slot(false)].concat());
+}
@@ -0,0 +1,99 @@
+//! Checks `ParkingLotDevice` can share a bus across threads, and isn't affected by panics.
+
+#![cfg(feature = "parking-lot")]
+
+use core::convert::Infallible;
+use std::thread;
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::spi::{self, SpiBus, SpiDevice};
+use embedded_hal_bus::spi::ParkingLotDevice;
+use parking_lot::Mutex;
+
+/// Bus recording the written bytes.
+#[derive(Default)]
+struct Bus {
+    written: Vec<u8>,
+}
+
+impl spi::ErrorType for Bus {
+    type Error = Infallible;
+}
+
+impl SpiBus for Bus {
+    fn read(&mut self, _words: &mut [u8]) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        self.written.extend_from_slice(words);
+        Ok(())
+    }
+
+    fn transfer(&mut self, _read: &mut [u8], write: &[u8]) -> Result<(), Infallible> {
+        self.write(write)
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Infallible> {
+        self.write(words)
+    }
+
+    fn flush(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+struct Cs;
+
+impl digital::ErrorType for Cs {
+    type Error = Infallible;
+}
+
+impl OutputPin for Cs {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+#[test]
+fn shared_across_threads() {
+    let bus = Mutex::new(Bus::default());
+
+    thread::scope(|s| {
+        for i in 0..4 {
+            let mut device = ParkingLotDevice::new_no_delay(&bus, Cs);
+            s.spawn(move || {
+                for _ in 0..100 {
+                    device.write(&[i, i]).unwrap();
+                }
+            });
+        }
+    });
+
+    let written = &bus.lock().written;
+    assert_eq!(written.len(), 800);
+    // Transactions are not interleaved.
+    assert!(written.chunks(2).all(|pair| pair[0] == pair[1]));
+}
+
+#[test]
+fn no_poisoning() {
+    let bus = Mutex::new(Bus::default());
+    let mut device = ParkingLotDevice::new_no_delay(&bus, Cs);
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            let _guard = bus.lock();
+            panic!("worker failed");
+        })
+        .join()
+        .unwrap_err();
+    });
+
+    assert_eq!(device.write(&[0x01]), Ok(()));
+    assert_eq!(bus.lock().written, [0x01]);
+}
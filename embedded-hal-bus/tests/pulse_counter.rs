@@ -0,0 +1,52 @@
+//! Checks `PulseCounter` counts the rising edges seen between polls.
+
+#![cfg(feature = "test-util")]
+
+use embedded_hal::digital::PinState::{High, Low};
+use embedded_hal_bus::digital::PulseCounter;
+use embedded_hal_bus::test_util::{MockPin, PinTransaction::Get};
+
+#[test]
+fn counts_rising_edges() {
+    let pin = MockPin::<8>::new(&[
+        Get(Low),
+        Get(High),
+        Get(High),
+        Get(Low),
+        Get(Low),
+        Get(High),
+        Get(Low),
+        Get(High),
+    ]);
+    let mut counter = PulseCounter::new(pin);
+
+    let counts: Vec<_> = (0..8).map(|_| counter.poll().unwrap()).collect();
+    assert_eq!(counts, [0, 1, 1, 1, 1, 2, 2, 3]);
+    assert_eq!(counter.count(), 3);
+    counter.inner().done();
+}
+
+#[test]
+fn initially_high_is_not_an_edge() {
+    let pin = MockPin::<3>::new(&[Get(High), Get(Low), Get(High)]);
+    let mut counter = PulseCounter::new(pin);
+
+    assert_eq!(counter.poll().unwrap(), 0);
+    assert_eq!(counter.poll().unwrap(), 0);
+    assert_eq!(counter.poll().unwrap(), 1);
+    counter.inner().done();
+}
+
+#[test]
+fn reset_keeps_last_level() {
+    let pin = MockPin::<4>::new(&[Get(Low), Get(High), Get(High), Get(Low)]);
+    let mut counter = PulseCounter::new(pin);
+
+    counter.poll().unwrap();
+    counter.poll().unwrap();
+    counter.reset();
+    // Still high since the last poll, so no new edge.
+    assert_eq!(counter.poll().unwrap(), 0);
+    assert_eq!(counter.poll().unwrap(), 0);
+    counter.into_inner().done();
+}
@@ -0,0 +1,163 @@
+//! Checks `RetryDevice` replays failed transactions, with CS deasserted between attempts.
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::spi::{self, Error, ErrorKind, Operation, SpiBus, SpiDevice};
+use embedded_hal_bus::spi::{ExclusiveDevice, NoDelay, RetryDevice};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Event {
+    Cs(bool),
+    Write(u8),
+    Delay(u32),
+}
+
+type Log = RefCell<Vec<Event>>;
+
+/// Bus failing the first writes with the given errors.
+struct Bus<'a> {
+    log: &'a Log,
+    errors: Vec<ErrorKind>,
+}
+
+impl spi::ErrorType for Bus<'_> {
+    type Error = ErrorKind;
+}
+
+impl SpiBus for Bus<'_> {
+    fn read(&mut self, _: &mut [u8]) -> Result<(), ErrorKind> {
+        unimplemented!()
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), ErrorKind> {
+        self.log.borrow_mut().push(Event::Write(words[0]));
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors.remove(0))
+        }
+    }
+
+    fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), ErrorKind> {
+        unimplemented!()
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), ErrorKind> {
+        self.write(words)
+    }
+
+    fn flush(&mut self) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+}
+
+struct Cs<'a>(&'a Log);
+
+impl digital::ErrorType for Cs<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for Cs<'_> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().push(Event::Cs(false));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().push(Event::Cs(true));
+        Ok(())
+    }
+}
+
+struct Delay<'a>(&'a Log);
+
+impl DelayUs for Delay<'_> {
+    fn delay_us(&mut self, us: u32) {
+        self.0.borrow_mut().push(Event::Delay(us));
+    }
+}
+
+type Device<'a> = ExclusiveDevice<Bus<'a>, Cs<'a>, NoDelay>;
+
+fn device<'a>(log: &'a Log, errors: &[ErrorKind]) -> Device<'a> {
+    let bus = Bus {
+        log,
+        errors: errors.to_vec(),
+    };
+    ExclusiveDevice::new_no_delay(bus, Cs(log))
+}
+
+fn is_overrun(kind: ErrorKind) -> bool {
+    kind == ErrorKind::Overrun
+}
+
+#[test]
+fn succeeds_after_two_retries() {
+    let log = RefCell::new(Vec::new());
+    let errors = [ErrorKind::Overrun, ErrorKind::Overrun];
+    let mut device = RetryDevice::new(device(&log, &errors), Delay(&log), 10, 3, is_overrun);
+
+    device.write(&[0x42]).unwrap();
+
+    let attempt = [Event::Cs(false), Event::Write(0x42), Event::Cs(true)];
+    let mut expected = Vec::new();
+    expected.extend(attempt);
+    expected.push(Event::Delay(10));
+    expected.extend(attempt);
+    expected.push(Event::Delay(10));
+    expected.extend(attempt);
+    assert_eq!(log.into_inner(), expected);
+}
+
+#[test]
+fn gives_up_after_max_retries() {
+    let log = RefCell::new(Vec::new());
+    let errors = [ErrorKind::Overrun; 3];
+    let mut device = RetryDevice::new_no_delay(device(&log, &errors), 2, is_overrun);
+
+    let err = device.write(&[0x42]).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::Overrun);
+    let writes = log
+        .borrow()
+        .iter()
+        .filter(|e| **e == Event::Write(0x42))
+        .count();
+    assert_eq!(writes, 3);
+}
+
+#[test]
+fn other_errors_are_not_retried() {
+    let log = RefCell::new(Vec::new());
+    let errors = [ErrorKind::ModeFault];
+    let mut device = RetryDevice::new_no_delay(device(&log, &errors), 2, is_overrun);
+
+    let err = device.write(&[0x42]).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::ModeFault);
+    assert_eq!(
+        log.into_inner(),
+        [Event::Cs(false), Event::Write(0x42), Event::Cs(true)]
+    );
+}
+
+#[test]
+fn transfer_in_place_is_not_retried() {
+    let log = RefCell::new(Vec::new());
+    let errors = [ErrorKind::Overrun];
+    let mut device = RetryDevice::new_no_delay(device(&log, &errors), 2, is_overrun);
+
+    let mut buf = [0x42];
+    let err = device
+        .transaction(&mut [Operation::TransferInPlace(&mut buf)])
+        .unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::Overrun);
+    assert_eq!(
+        log.into_inner(),
+        [Event::Cs(false), Event::Write(0x42), Event::Cs(true)]
+    );
+}
@@ -0,0 +1,114 @@
+//! Checks `scan_async` tells missing acknowledges apart from errors, and can be cancelled.
+
+#![cfg(feature = "async")]
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+use core::cell::RefCell;
+use core::future::poll_fn;
+use core::task::Poll;
+
+use embedded_hal_async::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+use embedded_hal_bus::i2c::scan_async;
+use futures::executor::block_on;
+use futures::FutureExt;
+
+/// Bus acknowledging a known set of addresses, and failing at others.
+struct Bus<'a> {
+    acked: &'a [u8],
+    failing: &'a [u8],
+    /// Addresses probed, with whether their transaction completed.
+    probes: RefCell<Vec<(u8, bool)>>,
+}
+
+impl<'a> Bus<'a> {
+    fn new(acked: &'a [u8], failing: &'a [u8]) -> Self {
+        Self {
+            acked,
+            failing,
+            probes: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl ErrorType for Bus<'_> {
+    type Error = ErrorKind;
+}
+
+impl I2c for Bus<'_> {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        assert!(matches!(operations, [Operation::Write([])]));
+        self.probes.borrow_mut().push((address, false));
+
+        // Let the executor run once, so the transaction can be cancelled.
+        let mut yielded = false;
+        poll_fn(|cx| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await;
+
+        self.probes.borrow_mut().last_mut().unwrap().1 = true;
+        if self.acked.contains(&address) {
+            Ok(())
+        } else if self.failing.contains(&address) {
+            Err(ErrorKind::Bus)
+        } else {
+            Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+        }
+    }
+}
+
+#[test]
+fn found_and_failed() {
+    let mut i2c = Bus::new(&[0x08, 0x3C, 0x48, 0x77], &[0x50]);
+    let mut results = Vec::new();
+    block_on(async {
+        let mut scan = scan_async(&mut i2c);
+        while let Some(res) = scan.next().await {
+            results.push(res);
+        }
+    });
+    assert_eq!(
+        results,
+        [Ok(0x08), Ok(0x3C), Ok(0x48), Err(ErrorKind::Bus), Ok(0x77)]
+    );
+
+    // Only the non-reserved addresses are probed.
+    let probes = i2c.probes.into_inner();
+    assert_eq!(probes.first(), Some(&(0x08, true)));
+    assert_eq!(probes.last(), Some(&(0x77, true)));
+    assert_eq!(probes.len(), 0x70);
+}
+
+#[test]
+fn cancelled() {
+    let mut i2c = Bus::new(&[0x20, 0x48], &[]);
+    let mut found = Vec::new();
+    {
+        let mut scan = scan_async(&mut i2c);
+
+        // Poll once, then drop the future in the middle of probing 0x08.
+        assert_eq!(scan.next().now_or_never(), None);
+
+        block_on(async {
+            while let Some(res) = scan.next().await {
+                found.push(res.unwrap());
+            }
+        });
+    }
+    assert_eq!(found, [0x20, 0x48]);
+
+    // The cancelled probe is done again.
+    let probes = i2c.probes.into_inner();
+    assert_eq!(probes[..2], [(0x08, false), (0x08, true)]);
+}
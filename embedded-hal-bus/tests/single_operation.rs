@@ -0,0 +1,189 @@
+//! Checks the single operation methods of `ExclusiveDevice` behave like the equivalent
+//! one-operation transactions, including on failures.
+
+use core::cell::RefCell;
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::spi::{self, ErrorKind, Operation, SpiBus, SpiDevice};
+use embedded_hal_bus::spi::{DeviceError, ExclusiveDevice};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+    Cs(bool),
+    Read(usize),
+    Write(Vec<u8>),
+    Transfer(usize, Vec<u8>),
+    TransferInPlace(Vec<u8>),
+    Flush,
+}
+
+/// Which calls fail.
+#[derive(Clone, Copy)]
+struct Failures {
+    op: bool,
+    flush: bool,
+    cs_low: bool,
+    cs_high: bool,
+}
+
+struct Log {
+    events: RefCell<Vec<Event>>,
+    failures: Failures,
+}
+
+impl Log {
+    fn push(&self, event: Event, fail: bool, error: ErrorKind) -> Result<(), ErrorKind> {
+        self.events.borrow_mut().push(event);
+        if fail {
+            Err(error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct Bus<'a>(&'a Log);
+
+impl spi::ErrorType for Bus<'_> {
+    type Error = ErrorKind;
+}
+
+impl SpiBus for Bus<'_> {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), ErrorKind> {
+        words.fill(0xA5);
+        let fail = self.0.failures.op;
+        self.0
+            .push(Event::Read(words.len()), fail, ErrorKind::Overrun)
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), ErrorKind> {
+        let fail = self.0.failures.op;
+        self.0
+            .push(Event::Write(words.to_vec()), fail, ErrorKind::Overrun)
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), ErrorKind> {
+        read.fill(0x5A);
+        let event = Event::Transfer(read.len(), write.to_vec());
+        self.0.push(event, self.0.failures.op, ErrorKind::Overrun)
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), ErrorKind> {
+        let event = Event::TransferInPlace(words.to_vec());
+        words.reverse();
+        self.0.push(event, self.0.failures.op, ErrorKind::Overrun)
+    }
+
+    fn flush(&mut self) -> Result<(), ErrorKind> {
+        let fail = self.0.failures.flush;
+        self.0.push(Event::Flush, fail, ErrorKind::ModeFault)
+    }
+}
+
+struct Cs<'a>(&'a Log);
+
+impl digital::ErrorType for Cs<'_> {
+    type Error = digital::ErrorKind;
+}
+
+impl OutputPin for Cs<'_> {
+    fn set_low(&mut self) -> Result<(), digital::ErrorKind> {
+        let fail = self.0.failures.cs_low;
+        self.0.events.borrow_mut().push(Event::Cs(false));
+        if fail {
+            Err(digital::ErrorKind::Other)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn set_high(&mut self) -> Result<(), digital::ErrorKind> {
+        let fail = self.0.failures.cs_high;
+        self.0.events.borrow_mut().push(Event::Cs(true));
+        if fail {
+            Err(digital::ErrorKind::Other)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+type Device<'a> = ExclusiveDevice<Bus<'a>, Cs<'a>, embedded_hal_bus::spi::NoDelay>;
+type DeviceResult = Result<(), DeviceError<ErrorKind, digital::ErrorKind>>;
+
+/// Runs `f` on a device with the given failures and length limit, returning its result, the
+/// events, and the data it read.
+fn run(
+    failures: Failures,
+    max: Option<usize>,
+    f: impl FnOnce(&mut Device<'_>, &mut [u8]) -> DeviceResult,
+) -> (DeviceResult, Vec<Event>, [u8; 4]) {
+    let log = Log {
+        events: RefCell::new(Vec::new()),
+        failures,
+    };
+    let mut device = ExclusiveDevice::new_no_delay(Bus(&log), Cs(&log));
+    if let Some(max) = max {
+        device = device.with_max_transaction_bytes(max);
+    }
+    let mut buf = [1, 2, 3, 4];
+    let res = f(&mut device, &mut buf);
+    (res, log.events.into_inner(), buf)
+}
+
+fn all_failures() -> impl Iterator<Item = Failures> {
+    (0..16).map(|bits| Failures {
+        op: bits & 1 != 0,
+        flush: bits & 2 != 0,
+        cs_low: bits & 4 != 0,
+        cs_high: bits & 8 != 0,
+    })
+}
+
+/// Checks `single` and `general` behave the same for all failures and length limits.
+fn assert_same(
+    single: impl Fn(&mut Device<'_>, &mut [u8]) -> DeviceResult,
+    general: impl Fn(&mut Device<'_>, &mut [u8]) -> DeviceResult,
+) {
+    for failures in all_failures() {
+        for max in [None, Some(3), Some(4)] {
+            assert_eq!(run(failures, max, &single), run(failures, max, &general));
+        }
+    }
+}
+
+#[test]
+fn read() {
+    assert_same(
+        |device, buf| device.read(buf),
+        |device, buf| device.transaction(&mut [Operation::Read(buf)]),
+    );
+}
+
+#[test]
+fn write() {
+    assert_same(
+        |device, buf| device.write(buf),
+        |device, buf| device.transaction(&mut [Operation::Write(buf)]),
+    );
+}
+
+#[test]
+fn transfer() {
+    assert_same(
+        |device, buf| device.transfer(&mut buf[..2], &[9, 8, 7]),
+        |device, buf| device.transaction(&mut [Operation::Transfer(&mut buf[..2], &[9, 8, 7])]),
+    );
+    assert_same(
+        |device, buf| device.transfer(buf, &[9, 8]),
+        |device, buf| device.transaction(&mut [Operation::Transfer(buf, &[9, 8])]),
+    );
+}
+
+#[test]
+fn transfer_in_place() {
+    assert_same(
+        |device, buf| device.transfer_in_place(buf),
+        |device, buf| device.transaction(&mut [Operation::TransferInPlace(buf)]),
+    );
+}
@@ -0,0 +1,172 @@
+//! Checks the transaction statistics of the `SpiDevice` implementations.
+#![cfg(feature = "stats")]
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::spi::{self, ErrorKind, Operation, SpiBus, SpiDevice};
+use embedded_hal_bus::spi::{ExclusiveDevice, RefCellDevice, SpiDeviceExt, Stats};
+
+/// Bus failing writes of `0xFF`.
+struct Bus;
+
+impl spi::ErrorType for Bus {
+    type Error = ErrorKind;
+}
+
+impl SpiBus for Bus {
+    fn read(&mut self, _: &mut [u8]) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), ErrorKind> {
+        if words.contains(&0xFF) {
+            Err(ErrorKind::Overrun)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn transfer(&mut self, _: &mut [u8], write: &[u8]) -> Result<(), ErrorKind> {
+        self.write(write)
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), ErrorKind> {
+        self.write(words)
+    }
+
+    fn flush(&mut self) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+}
+
+struct Cs;
+
+impl digital::ErrorType for Cs {
+    type Error = Infallible;
+}
+
+impl OutputPin for Cs {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// Runs transactions on `device`, 3 of them failing, moving 10 bytes in total.
+fn run<D: SpiDevice>(device: &mut D) {
+    let mut buf = [0; 4];
+    device.write(&[0x01, 0x02]).unwrap();
+    device.read(&mut buf).unwrap();
+    device.write(&[0x01, 0xFF]).unwrap_err();
+    device
+        .transaction(&mut [Operation::Write(&[0x03]), Operation::Read(&mut buf[..3])])
+        .unwrap();
+    device
+        .transaction(&mut [Operation::Read(&mut buf), Operation::Write(&[0xFF])])
+        .unwrap_err();
+    // Rejected, longer than the maximum of 8 bytes.
+    device.write(&[0; 9]).unwrap_err();
+}
+
+const EXPECTED: Stats = Stats {
+    transactions: 6,
+    bytes: 10,
+    errors: 3,
+};
+
+#[test]
+fn exclusive_device() {
+    let mut device = ExclusiveDevice::new_no_delay(Bus, Cs).with_max_transaction_bytes(8);
+    assert_eq!(device.stats(), Stats::default());
+
+    run(&mut device);
+    assert_eq!(device.stats(), EXPECTED);
+
+    let mut buf = [0; 2];
+    device
+        .transaction_with_context(&mut [Operation::Transfer(&mut buf, &[0x01, 0x02, 0x03])])
+        .unwrap();
+    assert_eq!(
+        device.stats(),
+        Stats {
+            transactions: 7,
+            bytes: 13,
+            errors: 3,
+        }
+    );
+}
+
+#[test]
+fn refcell_devices() {
+    let bus = RefCell::new(Bus);
+    let mut device1 = RefCellDevice::new_no_delay(&bus, Cs).with_max_transaction_bytes(8);
+    let mut device2 = RefCellDevice::new_no_delay(&bus, Cs);
+
+    run(&mut device1);
+    device2.write(&[0x01]).unwrap();
+
+    // Each device counts its own transactions.
+    assert_eq!(device1.stats(), EXPECTED);
+    assert_eq!(
+        device2.stats(),
+        Stats {
+            transactions: 1,
+            bytes: 1,
+            errors: 0,
+        }
+    );
+}
+
+#[test]
+fn helper_methods() {
+    let mut device = ExclusiveDevice::new_no_delay(Bus, Cs).with_max_transaction_bytes(8);
+    let xor = |data: &[u8]| data.iter().fold(0, |crc, byte| crc ^ byte);
+
+    device.write_chunks::<2, u8>(&[0x01; 5]).unwrap();
+    device.write_with_crc(&[0xFF], xor).unwrap_err();
+    device.transfer_tail(&[0x9F], 2, &mut [0; 2]).unwrap();
+    // Rejected from the upper bound of their size hint, even if shorter.
+    device.write_iter::<4, _>(0..9).unwrap_err();
+    device
+        .write_iter::<4, _>((0..9).filter(|&i| i < 2))
+        .unwrap_err();
+    device.write_iter::<4, _>((0..6).filter(|_| true)).unwrap();
+
+    assert_eq!(
+        device.stats(),
+        Stats {
+            transactions: 6,
+            bytes: 15,
+            errors: 3,
+        }
+    );
+}
+
+#[test]
+fn refcell_try_transaction() {
+    let bus = RefCell::new(Bus);
+    let mut device = RefCellDevice::new_no_delay(&bus, Cs);
+
+    device
+        .try_transaction(&mut [Operation::Write(&[0x01, 0x02])])
+        .unwrap();
+    let guard = bus.borrow_mut();
+    device
+        .try_transaction(&mut [Operation::Write(&[0x01])])
+        .unwrap_err();
+    drop(guard);
+
+    assert_eq!(
+        device.stats(),
+        Stats {
+            transactions: 2,
+            bytes: 2,
+            errors: 1,
+        }
+    );
+}
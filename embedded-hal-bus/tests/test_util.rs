@@ -0,0 +1,87 @@
+//! Checks the `test_util` mocks can be used from a `no_std` crate.
+
+#![cfg(feature = "test-util")]
+#![no_std]
+
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::{InputPin, PinState};
+use embedded_hal::i2c::I2c;
+use embedded_hal::spi::{Error, ErrorKind, Operation, SpiDevice};
+use embedded_hal_bus::spi::{DeviceError, ExclusiveDevice};
+use embedded_hal_bus::test_util::{
+    DummyError, I2cTransaction, MockDelay, MockI2c, MockPin, MockSpiBus, PinTransaction,
+    SpiTransaction,
+};
+
+#[test]
+fn spi_device() {
+    // The bus is flushed before the delay, and at the end of the transaction.
+    let mut bus = MockSpiBus::<4>::new(&[
+        SpiTransaction::Write(&[0x01, 0x02]),
+        SpiTransaction::Flush,
+        SpiTransaction::Flush,
+    ]);
+    let mut cs = MockPin::<2>::new(&[
+        PinTransaction::Set(PinState::Low),
+        PinTransaction::Set(PinState::High),
+    ]);
+    let mut delay = MockDelay::new();
+
+    let mut device = ExclusiveDevice::new(&mut bus, &mut cs, &mut delay);
+    device
+        .transaction(&mut [Operation::Write(&[0x01, 0x02]), Operation::DelayUs(10)])
+        .unwrap();
+
+    bus.done();
+    cs.done();
+    assert_eq!(delay.total_us(), 10);
+}
+
+#[test]
+fn i2c_write_read() {
+    let mut i2c = MockI2c::<2>::new(&[
+        I2cTransaction::Write(0x48, &[0x00]),
+        I2cTransaction::Read(0x48, &[0x12, 0x34]),
+    ]);
+
+    let mut buf = [0; 2];
+    i2c.write_read(0x48, &[0x00], &mut buf).unwrap();
+
+    assert_eq!(buf, [0x12, 0x34]);
+    i2c.done();
+}
+
+#[test]
+fn input_pin_and_delay() {
+    let pin = MockPin::<2>::new(&[
+        PinTransaction::Get(PinState::Low),
+        PinTransaction::Get(PinState::High),
+    ]);
+    let mut delay = MockDelay::new();
+
+    while pin.is_low().unwrap() {
+        delay.delay_ms(1);
+    }
+
+    pin.done();
+    assert_eq!(delay.calls(), 1);
+    assert_eq!(delay.total_us(), 1000);
+}
+
+#[test]
+fn dummy_error() {
+    let error: DeviceError<DummyError, DummyError> =
+        DeviceError::Spi(DummyError::new(ErrorKind::ModeFault));
+    assert_eq!(error.kind(), ErrorKind::ModeFault);
+
+    let error: DeviceError<DummyError, DummyError> =
+        DeviceError::Cs(DummyError::new(ErrorKind::Other));
+    assert_eq!(error.kind(), ErrorKind::ChipSelectFault);
+}
+
+#[test]
+#[should_panic]
+fn unexpected_transaction() {
+    let mut bus = MockSpiBus::<1>::new(&[SpiTransaction::Flush]);
+    embedded_hal::spi::SpiBus::write(&mut bus, &[0x01]).unwrap();
+}
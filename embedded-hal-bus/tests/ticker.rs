@@ -0,0 +1,84 @@
+//! Checks `Ticker` keeps to its schedule, and handles missed ticks.
+
+#![cfg(feature = "async")]
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+use core::cell::Cell;
+
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_bus::delay::{MissedTicks, Ticker};
+use futures::executor::block_on;
+
+/// Delay advancing a fake clock instead of waiting.
+struct Delay<'a>(&'a Cell<u32>);
+
+impl DelayUs for Delay<'_> {
+    async fn delay_us(&mut self, us: u32) {
+        self.0.set(self.0.get().wrapping_add(us));
+    }
+
+    async fn delay_ms(&mut self, ms: u32) {
+        self.delay_us(ms * 1000).await;
+    }
+}
+
+/// Runs the ticker once per element of `work_us`, spending that long in each iteration, and
+/// returns when each iteration started and how late its tick was.
+fn run(now: &Cell<u32>, missed_ticks: MissedTicks, work_us: &[u32]) -> Vec<(u32, u32)> {
+    let start = now.get();
+    let mut ticker = Ticker::new(Delay(now), || now.get(), 1_000);
+    ticker.set_missed_ticks(missed_ticks);
+    block_on(async {
+        let mut iterations = Vec::new();
+        for &work in work_us {
+            let iteration_start = now.get().wrapping_sub(start);
+            now.set(now.get().wrapping_add(work));
+            iterations.push((iteration_start, ticker.next().await));
+        }
+        iterations
+    })
+}
+
+#[test]
+fn no_drift() {
+    let now = Cell::new(u32::MAX - 2_500);
+    let iterations = run(&now, MissedTicks::Skip, &[100, 999, 0, 500, 1_000, 300]);
+    assert_eq!(
+        iterations,
+        [
+            (0, 0),
+            (1_000, 0),
+            (2_000, 0),
+            (3_000, 0),
+            (4_000, 0),
+            (5_000, 0)
+        ]
+    );
+}
+
+#[test]
+fn skip_missed_ticks() {
+    let now = Cell::new(0);
+    // The second iteration overruns by 1.5 periods, missing the ticks at 2 and 3 ms.
+    let iterations = run(&now, MissedTicks::Skip, &[100, 2_500, 100, 100]);
+    assert_eq!(iterations, [(0, 0), (1_000, 1_500), (3_500, 0), (4_000, 0)]);
+}
+
+#[test]
+fn burst_missed_ticks() {
+    let now = Cell::new(0);
+    // The second iteration overruns by 2.5 periods, then the ticks at 3 and 4 ms complete
+    // immediately, until the schedule is caught up.
+    let iterations = run(&now, MissedTicks::Burst, &[100, 3_500, 100, 100, 100]);
+    assert_eq!(
+        iterations,
+        [
+            (0, 0),
+            (1_000, 2_500),
+            (4_500, 1_600),
+            (4_600, 700),
+            (4_700, 0)
+        ]
+    );
+}
@@ -0,0 +1,128 @@
+//! Checks `write_chunked` keeps CS asserted while other tasks run between chunks.
+
+#![cfg(feature = "async")]
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+use core::future::poll_fn;
+use core::task::Poll;
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal_async::spi::{self, SpiBus};
+use embedded_hal_bus::spi::ExclusiveDevice;
+use futures::executor::block_on;
+use futures::future::join;
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Cs(bool),
+    Write(Vec<u8>),
+    /// The other task ran, while CS was high or not.
+    Tick(bool),
+}
+
+struct Log {
+    events: RefCell<Vec<Event>>,
+    cs_high: RefCell<bool>,
+}
+
+struct Bus<'a>(&'a Log);
+
+impl spi::ErrorType for Bus<'_> {
+    type Error = Infallible;
+}
+
+impl SpiBus for Bus<'_> {
+    async fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        self.0
+            .events
+            .borrow_mut()
+            .push(Event::Write(words.to_vec()));
+        Ok(())
+    }
+
+    async fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    async fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+struct CsPin<'a>(&'a Log);
+
+impl digital::ErrorType for CsPin<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for CsPin<'_> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        *self.0.cs_high.borrow_mut() = false;
+        self.0.events.borrow_mut().push(Event::Cs(false));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        *self.0.cs_high.borrow_mut() = true;
+        self.0.events.borrow_mut().push(Event::Cs(true));
+        Ok(())
+    }
+}
+
+async fn yield_now() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+#[test]
+fn cs_asserted_across_yields() {
+    let log = Log {
+        events: RefCell::new(Vec::new()),
+        cs_high: RefCell::new(true),
+    };
+    let mut device = ExclusiveDevice::new_no_delay(Bus(&log), CsPin(&log));
+
+    let other_task = async {
+        for _ in 0..2 {
+            let cs_high = *log.cs_high.borrow();
+            log.events.borrow_mut().push(Event::Tick(cs_high));
+            yield_now().await;
+        }
+    };
+    let (res, ()) = block_on(join(device.write_chunked(&[1, 2, 3, 4, 5], 2), other_task));
+    res.unwrap();
+
+    use Event::*;
+    assert_eq!(
+        *log.events.borrow(),
+        [
+            Cs(false),
+            Write(vec![1, 2]),
+            Tick(false),
+            Write(vec![3, 4]),
+            Tick(false),
+            Write(vec![5]),
+            Cs(true),
+        ]
+    );
+}
@@ -1,5 +1,10 @@
 //! Serial interface.
 
+#[cfg(feature = "embedded-io")]
+mod io;
+#[cfg(feature = "embedded-io")]
+pub use io::*;
+
 /// Serial error.
 pub trait Error: core::fmt::Debug {
     /// Convert error to a generic serial error kind
@@ -0,0 +1,146 @@
+//! `embedded-io` adapter.
+
+use super::{Error, ErrorKind, ErrorType, Read, Write};
+
+/// Adapter implementing the [`embedded_io`] blocking traits on top of an `nb` serial interface.
+///
+/// This allows using `embedded-io` based code, such as parsers, with a serial interface
+/// implementing the [`Read`] and [`Write`] traits of this crate. `WouldBlock` is handled by
+/// retrying until the operation completes, so the adapter always blocks.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal_nb::serial::SerialIo;
+/// use embedded_io::Read as _;
+/// # use std::collections::VecDeque;
+/// # use embedded_hal_nb::serial::{self, ErrorKind};
+/// # struct Serial { rx: VecDeque<nb::Result<u8, ErrorKind>> }
+/// # impl serial::ErrorType for Serial { type Error = ErrorKind; }
+/// # impl serial::Read for Serial {
+/// #     fn read(&mut self) -> nb::Result<u8, ErrorKind> {
+/// #         self.rx.pop_front().unwrap_or(Err(nb::Error::WouldBlock))
+/// #     }
+/// # }
+/// // A serial interface receiving "hi\n", with gaps between some of the bytes.
+/// let rx = [Ok(b'h'), Err(nb::Error::WouldBlock), Ok(b'i'), Ok(b'\n')];
+/// # let serial = Serial { rx: rx.into_iter().collect() };
+/// let mut io = SerialIo::new(serial);
+///
+/// let mut line = Vec::new();
+/// let mut buf = [0; 8];
+/// while !line.ends_with(b"\n") {
+///     let n = io.read(&mut buf).unwrap();
+///     line.extend_from_slice(&buf[..n]);
+/// }
+///
+/// assert_eq!(line, b"hi\n");
+/// ```
+#[derive(Debug)]
+pub struct SerialIo<S: ErrorType> {
+    serial: S,
+    read_error: Option<S::Error>,
+}
+
+impl<S: ErrorType> SerialIo<S> {
+    /// Create a new adapter.
+    #[inline]
+    pub fn new(serial: S) -> Self {
+        Self {
+            serial,
+            read_error: None,
+        }
+    }
+
+    /// Returns a reference to the serial interface.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.serial
+    }
+
+    /// Returns a mutable reference to the serial interface.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.serial
+    }
+
+    /// Destroys the adapter, returning the serial interface.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.serial
+    }
+}
+
+/// Error type for [`SerialIo`] operations, wrapping the error of the serial interface.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SerialIoError<E>(pub E);
+
+impl<E: Error> embedded_io::Error for SerialIoError<E> {
+    #[inline]
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self.0.kind() {
+            ErrorKind::FrameFormat | ErrorKind::Parity | ErrorKind::Noise => {
+                embedded_io::ErrorKind::InvalidData
+            }
+            _ => embedded_io::ErrorKind::Other,
+        }
+    }
+}
+
+impl<S: ErrorType> embedded_io::ErrorType for SerialIo<S> {
+    type Error = SerialIoError<S::Error>;
+}
+
+impl<S: Read> embedded_io::Read for SerialIo<S> {
+    /// Blocks until a byte is received, then reads bytes while they are available.
+    ///
+    /// Errors happening after some bytes were read are returned by the next call, so that no
+    /// received bytes are lost.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if let Some(e) = self.read_error.take() {
+            return Err(SerialIoError(e));
+        }
+
+        buf[0] = nb::block!(self.serial.read()).map_err(SerialIoError)?;
+        for (n, word) in buf.iter_mut().enumerate().skip(1) {
+            match self.serial.read() {
+                Ok(w) => *word = w,
+                Err(nb::Error::WouldBlock) => return Ok(n),
+                Err(nb::Error::Other(e)) => {
+                    self.read_error = Some(e);
+                    return Ok(n);
+                }
+            }
+        }
+        Ok(buf.len())
+    }
+}
+
+impl<S: Write> embedded_io::Write for SerialIo<S> {
+    /// Blocks until a byte is written, then writes bytes while the interface accepts them.
+    ///
+    /// Errors happening after some bytes were written are not returned, the next call will
+    /// retry the first byte that failed.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let (first, rest) = match buf.split_first() {
+            Some(split) => split,
+            None => return Ok(0),
+        };
+
+        nb::block!(self.serial.write(*first)).map_err(SerialIoError)?;
+        for (n, word) in rest.iter().enumerate() {
+            if self.serial.write(*word).is_err() {
+                return Ok(n + 1);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        nb::block!(self.serial.flush()).map_err(SerialIoError)
+    }
+}
@@ -6,6 +6,7 @@ pub mod delay;
 pub mod digital;
 pub mod i2c;
 pub mod pwm;
+pub mod qspi;
 pub mod spi;
 
 mod private {
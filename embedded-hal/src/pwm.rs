@@ -106,6 +106,42 @@ pub trait SetDutyCycle: ErrorType {
     fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<(), Self::Error> {
         self.set_duty_cycle_fraction(percent as u16, 100)
     }
+
+    /// Set the duty cycle to `millipercent / 100_000`
+    ///
+    /// This allows a finer resolution than [`set_duty_cycle_percent`](Self::set_duty_cycle_percent)
+    /// without floating-point math. Values above 100 000 (100%) are clamped.
+    ///
+    /// ```
+    /// use core::convert::Infallible;
+    /// use embedded_hal::pwm::{ErrorType, SetDutyCycle};
+    ///
+    /// struct Pwm(u16);
+    /// impl ErrorType for Pwm {
+    ///     type Error = Infallible;
+    /// }
+    /// impl SetDutyCycle for Pwm {
+    ///     fn get_max_duty_cycle(&self) -> u16 {
+    ///         u16::MAX
+    ///     }
+    ///     fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Infallible> {
+    ///         self.0 = duty;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut pwm = Pwm(0);
+    /// for (millipercent, duty) in [(0, 0), (50_000, 32_767), (100_000, u16::MAX), (200_000, u16::MAX)] {
+    ///     pwm.set_duty_cycle_millipercent(millipercent).unwrap();
+    ///     assert_eq!(pwm.0, duty);
+    /// }
+    /// ```
+    #[inline]
+    fn set_duty_cycle_millipercent(&mut self, millipercent: u32) -> Result<(), Self::Error> {
+        let millipercent = millipercent.min(100_000) as u64;
+        let duty = self.get_max_duty_cycle() as u64 * millipercent / 100_000;
+        self.set_duty_cycle(duty as u16)
+    }
 }
 
 impl<T: SetDutyCycle + ?Sized> SetDutyCycle for &mut T {
@@ -138,4 +174,9 @@ impl<T: SetDutyCycle + ?Sized> SetDutyCycle for &mut T {
     fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<(), Self::Error> {
         T::set_duty_cycle_percent(self, percent)
     }
+
+    #[inline]
+    fn set_duty_cycle_millipercent(&mut self, millipercent: u32) -> Result<(), Self::Error> {
+        T::set_duty_cycle_millipercent(self, millipercent)
+    }
 }
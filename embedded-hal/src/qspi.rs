@@ -0,0 +1,241 @@
+//! Quad-SPI traits.
+//!
+//! QSPI (Quad SPI) is an extension of SPI, commonly used by serial flash memories, where the data
+//! can be sent on 1, 2 or 4 lanes at once. Communication is structured as [`Command`]s made of up
+//! to four phases, each of them with its own number of lanes:
+//!
+//! - An instruction byte.
+//! - An optional address, of 1 to 4 bytes.
+//! - An optional number of dummy cycles, giving the device time to prepare the data.
+//! - An optional data phase, reading or writing bytes.
+//!
+//! As for SPI, [`QspiBus`] represents exclusive ownership over the whole bus, and [`QspiDevice`]
+//! represents a single device on it, selected with a CS (Chip Select) pin. See the
+//! [SPI module-level documentation](crate::spi) for details about the bus and device split, and
+//! about flushing.
+//!
+//! The error types are shared with the [SPI traits](crate::spi).
+//!
+//! # Example
+//!
+//! Reading the ID of a flash memory in QPI mode, where all phases use 4 lanes:
+//!
+//! ```
+//! use embedded_hal::qspi::{Command, Lanes, QspiBus};
+//! # use core::convert::Infallible;
+//! # use embedded_hal::qspi::{Data, ErrorType};
+//! # struct Bus;
+//! # impl ErrorType for Bus { type Error = Infallible; }
+//! # impl QspiBus for Bus {
+//! #     fn command(&mut self, command: &mut Command<'_>) -> Result<(), Infallible> {
+//! #         assert_eq!(command.instruction, 0xAF);
+//! #         assert_eq!(command.instruction_lanes, Lanes::Four);
+//! #         if let Data::Read(buf, Lanes::Four) = &mut command.data {
+//! #             buf.copy_from_slice(&[0xEF, 0x40, 0x18]);
+//! #         }
+//! #         Ok(())
+//! #     }
+//! #     fn flush(&mut self) -> Result<(), Infallible> { Ok(()) }
+//! # }
+//! # let mut bus = Bus;
+//! let mut id = [0; 3];
+//! let mut read_id = Command::new(0xAF)
+//!     .with_instruction_lanes(Lanes::Four)
+//!     .with_read(&mut id, Lanes::Four);
+//!
+//! bus.command(&mut read_id).unwrap();
+//! bus.flush().unwrap();
+//!
+//! assert_eq!(id, [0xEF, 0x40, 0x18]);
+//! ```
+
+pub use crate::spi::{Error, ErrorKind, ErrorType};
+
+#[cfg(feature = "defmt-03")]
+use crate::defmt;
+
+/// Number of lanes used by a phase of a [`Command`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Lanes {
+    /// Single lane, like regular SPI.
+    One,
+    /// Two lanes (dual SPI).
+    Two,
+    /// Four lanes (quad SPI).
+    Four,
+}
+
+/// Size of the address phase of a [`Command`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum AddressSize {
+    /// 8-bit address.
+    Bits8,
+    /// 16-bit address.
+    Bits16,
+    /// 24-bit address.
+    Bits24,
+    /// 32-bit address.
+    Bits32,
+}
+
+/// Address phase of a [`Command`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Address {
+    /// Address value. Only the lowest [`size`](Self::size) bits are sent.
+    pub value: u32,
+    /// Address size.
+    pub size: AddressSize,
+    /// Lanes used to send the address.
+    pub lanes: Lanes,
+}
+
+/// Data phase of a [`Command`].
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Data<'a> {
+    /// No data phase.
+    None,
+    /// Read data into the provided buffer, using the given lanes.
+    Read(&'a mut [u8], Lanes),
+    /// Write data from the provided buffer, using the given lanes.
+    Write(&'a [u8], Lanes),
+}
+
+/// QSPI command.
+///
+/// See the [module-level documentation](self) for a description of the phases.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Command<'a> {
+    /// Instruction byte.
+    pub instruction: u8,
+    /// Lanes used to send the instruction byte.
+    pub instruction_lanes: Lanes,
+    /// Address phase, if any.
+    pub address: Option<Address>,
+    /// Number of dummy cycles between the address and the data phases.
+    pub dummy_cycles: u8,
+    /// Data phase.
+    pub data: Data<'a>,
+}
+
+impl<'a> Command<'a> {
+    /// Create a command only sending `instruction` on a single lane.
+    #[inline]
+    pub fn new(instruction: u8) -> Self {
+        Self {
+            instruction,
+            instruction_lanes: Lanes::One,
+            address: None,
+            dummy_cycles: 0,
+            data: Data::None,
+        }
+    }
+
+    /// Send the instruction byte on `lanes`.
+    #[inline]
+    pub fn with_instruction_lanes(mut self, lanes: Lanes) -> Self {
+        self.instruction_lanes = lanes;
+        self
+    }
+
+    /// Add an address phase.
+    #[inline]
+    pub fn with_address(mut self, value: u32, size: AddressSize, lanes: Lanes) -> Self {
+        self.address = Some(Address { value, size, lanes });
+        self
+    }
+
+    /// Wait `dummy_cycles` clock cycles before the data phase.
+    #[inline]
+    pub fn with_dummy_cycles(mut self, dummy_cycles: u8) -> Self {
+        self.dummy_cycles = dummy_cycles;
+        self
+    }
+
+    /// Add a data phase reading into `buf`.
+    #[inline]
+    pub fn with_read(mut self, buf: &'a mut [u8], lanes: Lanes) -> Self {
+        self.data = Data::Read(buf, lanes);
+        self
+    }
+
+    /// Add a data phase writing `buf`.
+    #[inline]
+    pub fn with_write(mut self, buf: &'a [u8], lanes: Lanes) -> Self {
+        self.data = Data::Write(buf, lanes);
+        self
+    }
+}
+
+/// QSPI device trait.
+///
+/// `QspiDevice` represents ownership over a single QSPI device on a (possibly shared) bus,
+/// selected with a CS (Chip Select) pin.
+///
+/// This is the QSPI counterpart of [`SpiDevice`](crate::spi::SpiDevice), see its documentation
+/// for the requirements on implementations.
+pub trait QspiDevice: ErrorType {
+    /// Perform a transaction against the device.
+    ///
+    /// - Locks the bus
+    /// - Asserts the CS (Chip Select) pin.
+    /// - Performs all the commands.
+    /// - [Flushes](QspiBus::flush) the bus.
+    /// - Deasserts the CS pin.
+    /// - Unlocks the bus.
+    ///
+    /// On bus errors the implementation should try to deassert CS.
+    /// If an error occurs while deasserting CS the bus error should take priority as the return value.
+    fn transaction(&mut self, commands: &mut [Command<'_>]) -> Result<(), Self::Error>;
+
+    /// Perform a single command within a transaction.
+    ///
+    /// This is a convenience method equivalent to `device.transaction(&mut [command])`.
+    #[inline]
+    fn command(&mut self, command: &mut Command<'_>) -> Result<(), Self::Error> {
+        self.transaction(core::slice::from_mut(command))
+    }
+}
+
+impl<T: QspiDevice + ?Sized> QspiDevice for &mut T {
+    #[inline]
+    fn transaction(&mut self, commands: &mut [Command<'_>]) -> Result<(), Self::Error> {
+        T::transaction(self, commands)
+    }
+
+    #[inline]
+    fn command(&mut self, command: &mut Command<'_>) -> Result<(), Self::Error> {
+        T::command(self, command)
+    }
+}
+
+/// QSPI bus.
+///
+/// `QspiBus` represents **exclusive ownership** over the whole QSPI bus, with its clock and data
+/// lanes.
+pub trait QspiBus: ErrorType {
+    /// Perform `command`, storing any read data in its [`Data::Read`] buffer.
+    ///
+    /// Implementations are allowed to return before the operation is complete, see the
+    /// [SPI module-level documentation](crate::spi#flushing) for details.
+    fn command(&mut self, command: &mut Command<'_>) -> Result<(), Self::Error>;
+
+    /// Wait until all operations have completed and the bus is idle.
+    fn flush(&mut self) -> Result<(), Self::Error>;
+}
+
+impl<T: QspiBus + ?Sized> QspiBus for &mut T {
+    #[inline]
+    fn command(&mut self, command: &mut Command<'_>) -> Result<(), Self::Error> {
+        T::command(self, command)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        T::flush(self)
+    }
+}
@@ -161,7 +161,7 @@
 //! for them to return before the bus is idle. For example, assuming SPI mode 0, the last bit is sampled on the first (rising) edge
 //! of SCK, at which point a method could return, but the second (falling) SCK edge still has to happen before the bus is idle.
 
-use core::fmt::Debug;
+use core::{fmt::Debug, ops::Not};
 
 #[cfg(feature = "defmt-03")]
 use crate::defmt;
@@ -186,6 +186,30 @@ pub enum Phase {
     CaptureOnSecondTransition,
 }
 
+impl Not for Polarity {
+    type Output = Polarity;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        match self {
+            Polarity::IdleLow => Polarity::IdleHigh,
+            Polarity::IdleHigh => Polarity::IdleLow,
+        }
+    }
+}
+
+impl Not for Phase {
+    type Output = Phase;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        match self {
+            Phase::CaptureOnFirstTransition => Phase::CaptureOnSecondTransition,
+            Phase::CaptureOnSecondTransition => Phase::CaptureOnFirstTransition,
+        }
+    }
+}
+
 /// SPI mode.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
@@ -196,6 +220,28 @@ pub struct Mode {
     pub phase: Phase,
 }
 
+impl Mode {
+    /// Create a new `Mode` from its polarity and phase.
+    ///
+    /// ```
+    /// use embedded_hal::spi::{Mode, Phase, Polarity, MODE_0, MODE_1, MODE_2, MODE_3};
+    ///
+    /// let first = Phase::CaptureOnFirstTransition;
+    /// assert_eq!(Mode::new(Polarity::IdleLow, first), MODE_0);
+    /// assert_eq!(Mode::new(Polarity::IdleLow, !first), MODE_1);
+    /// assert_eq!(Mode::new(!Polarity::IdleLow, first), MODE_2);
+    /// assert_eq!(Mode::new(Polarity::IdleHigh, !first), MODE_3);
+    ///
+    /// assert_eq!(!Polarity::IdleHigh, Polarity::IdleLow);
+    /// assert_eq!(!!Polarity::IdleLow, Polarity::IdleLow);
+    /// assert_eq!(!Phase::CaptureOnSecondTransition, first);
+    /// ```
+    #[inline]
+    pub const fn new(polarity: Polarity, phase: Phase) -> Self {
+        Self { polarity, phase }
+    }
+}
+
 /// Helper for CPOL = 0, CPHA = 0.
 pub const MODE_0: Mode = Mode {
     polarity: Polarity::IdleLow,
@@ -350,6 +396,9 @@ pub trait SpiDevice<Word: Copy + 'static = u8>: ErrorType {
     ///
     /// On bus errors the implementation should try to deassert CS.
     /// If an error occurs while deasserting CS the bus error should take priority as the return value.
+    ///
+    /// A transaction with an empty `operations` slice should do nothing: implementations should
+    /// return `Ok(())` without locking the bus, asserting CS or flushing.
     fn transaction(&mut self, operations: &mut [Operation<'_, Word>]) -> Result<(), Self::Error>;
 
     /// Do a read within a transaction.